@@ -0,0 +1,107 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const SECS_PER_DAY: i64 = 86_400;
+
+/// Upper edges, in days since last modification, of every bucket but the last.
+pub const AGE_BUCKET_DAYS: [i64; 3] = [30, 90, 365];
+
+/// Cut-off frontends start from for "bytes older than N days".
+pub const DEFAULT_STALE_DAYS: u32 = 365;
+
+/// Bytes at or below a node, split by how long ago each file was last modified.
+///
+/// Bucket `i` holds files younger than `AGE_BUCKET_DAYS[i]` days (and at least as
+/// old as the previous edge); the last bucket holds everything 365 days or older.
+/// Files with a modification time in the future count as new.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AgeHistogram {
+    pub buckets: [u64; AGE_BUCKET_DAYS.len() + 1],
+}
+
+impl AgeHistogram {
+    /// Histogram of a single file of `bytes` last modified at `modified` (Unix seconds).
+    pub fn of_file(modified: i64, now: i64, bytes: u64) -> Self {
+        let mut histogram = Self::default();
+        histogram.buckets[Self::bucket_of(modified, now)] = bytes;
+        histogram
+    }
+
+    fn bucket_of(modified: i64, now: i64) -> usize {
+        let age_days = now.saturating_sub(modified).max(0) / SECS_PER_DAY;
+        AGE_BUCKET_DAYS
+            .iter()
+            .position(|edge| age_days < *edge)
+            .unwrap_or(AGE_BUCKET_DAYS.len())
+    }
+
+    pub fn add(&mut self, other: &AgeHistogram) {
+        for (bucket, bytes) in self.buckets.iter_mut().zip(other.buckets) {
+            *bucket += bytes;
+        }
+    }
+
+    pub fn subtract(&mut self, other: &AgeHistogram) {
+        for (bucket, bytes) in self.buckets.iter_mut().zip(other.buckets) {
+            *bucket = bucket.saturating_sub(bytes);
+        }
+    }
+
+    pub fn total(&self) -> u64 {
+        self.buckets.iter().sum()
+    }
+
+    /// Rows for display: label and bytes, newest bucket first.
+    pub fn rows(&self) -> impl Iterator<Item = (String, u64)> + '_ {
+        self.buckets.iter().enumerate().map(|(index, bytes)| (bucket_label(index), *bytes))
+    }
+}
+
+/// `< 30 days`, `30-90 days`, ..., `365+ days`.
+pub fn bucket_label(index: usize) -> String {
+    match (index.checked_sub(1).map(|i| AGE_BUCKET_DAYS[i]), AGE_BUCKET_DAYS.get(index)) {
+        (None, Some(upper)) => format!("< {} days", upper),
+        (Some(lower), Some(upper)) => format!("{}-{} days", lower, upper),
+        (Some(lower), None) => format!("{}+ days", lower),
+        (None, None) => String::new(),
+    }
+}
+
+/// Whether a file modified at `modified` is at least `days` old at `now`.
+pub fn is_older_than(modified: i64, now: i64, days: u32) -> bool {
+    now.saturating_sub(modified) >= i64::from(days) * SECS_PER_DAY
+}
+
+/// Current time in Unix seconds, the reference point for file ages.
+pub fn now_secs() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_buckets() {
+        let now = 1_700_000_000;
+        let days_ago = |days: i64| now - days * SECS_PER_DAY;
+
+        let mut histogram = AgeHistogram::default();
+        for (days, bytes) in [(0, 1), (29, 2), (30, 4), (200, 8), (365, 16), (4000, 32)] {
+            histogram.add(&AgeHistogram::of_file(days_ago(days), now, bytes));
+        }
+        histogram.add(&AgeHistogram::of_file(now + 3600, now, 64));
+        assert_eq!(histogram.buckets, [67, 4, 8, 48]);
+        assert_eq!(histogram.total(), 127);
+
+        histogram.subtract(&AgeHistogram::of_file(days_ago(400), now, 100));
+        assert_eq!(histogram.buckets[3], 0);
+
+        let labels: Vec<_> = histogram.rows().map(|(label, _)| label).collect();
+        assert_eq!(labels, ["< 30 days", "30-90 days", "90-365 days", "365+ days"]);
+        assert!(is_older_than(days_ago(90), now, 90));
+        assert!(!is_older_than(days_ago(89), now, 90));
+    }
+}
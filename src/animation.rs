@@ -128,7 +128,7 @@ impl LayoutAnimator {
 
         // Items are already sorted largest-first by the treemap layout.
         // Assign reveal delays in batches.
-        let batch_size = (targets.len() + num_batches - 1) / num_batches;
+        let batch_size = targets.len().div_ceil(num_batches);
 
         self.states = targets
             .iter()
@@ -8,15 +8,26 @@ use ratatui::buffer::Buffer;
 use ratatui::layout::{Constraint, Layout, Rect as UiRect};
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, Paragraph, Widget};
+use ratatui::widgets::{Block, Borders, Paragraph, Widget, Wrap};
 use ratatui::{Frame, Terminal};
-use spaceinsight::crawler::{FileCrawler, ScanPhase, ScanProgress, ScanStats};
+use spaceinsight::age::DEFAULT_STALE_DAYS;
+use spaceinsight::crawler::{
+    read_timeout_label, CancelToken, FileCrawler, ScanError, ScanOptions, ScanPhase, ScanProgress, ScanStats,
+    READ_TIMEOUT_CHOICES,
+};
+use spaceinsight::entry_meta::{format_timestamp, FileKind};
+use spaceinsight::exclude::ExcludeRule;
 use spaceinsight::expand_state::ExpansionState;
+use spaceinsight::file_types::{extension_label, percent_of, UsageTotals};
+use spaceinsight::owners::{OwnerNames, OwnerUsage};
+use spaceinsight::largest::{self, LargestEntry};
 use spaceinsight::render_tree::{build_render_tree, RenderNode};
-use spaceinsight::tree::FileTree;
+use spaceinsight::scan_cache::ScanCache;
+use spaceinsight::throttle::ScanProfile;
+use spaceinsight::tree::{FileTree, Placeholder, SizeMode, TreeNode};
 use spaceinsight::treemap::Rect;
 use std::cmp::Ordering;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::io::{self, stdout};
 use std::path::{Path, PathBuf};
 use std::sync::mpsc::{self, Receiver, TryRecvError};
@@ -25,10 +36,13 @@ use std::thread;
 use std::time::{Duration, Instant};
 
 const MAX_RENDER_DEPTH: usize = 5;
+const MIN_FILE_SIZE_STEPS: [u64; 4] = [0, 4 * 1024, 64 * 1024, 1024 * 1024];
+const GENTLE_THREAD_STEPS: [usize; 4] = [1, 2, 4, 8];
+const STALE_DAY_STEPS: [u32; 5] = [30, 90, 180, 365, 730];
 
 enum ScanEvent {
     Progress(ScanProgress),
-    Completed(Result<ScanResult, String>),
+    Completed(Result<Box<ScanResult>, String>),
 }
 
 struct ScanResult {
@@ -36,6 +50,13 @@ struct ScanResult {
     stats: ScanStats,
 }
 
+/// A single folder scanned again, to be grafted into the current tree.
+struct RescanResult {
+    path: PathBuf,
+    tree: FileTree,
+    stats: ScanStats,
+}
+
 #[derive(Clone)]
 struct VisibleTile {
     path: PathBuf,
@@ -43,6 +64,14 @@ struct VisibleTile {
     size: u64,
     is_dir: bool,
     is_aggregate: bool,
+    is_shared_link: bool,
+    is_ignored: bool,
+    partial: bool,
+    symlink_target: Option<PathBuf>,
+    placeholder: Option<Placeholder>,
+    special: Option<FileKind>,
+    /// Holds none of the highlighted user's bytes
+    dimmed: bool,
     depth: u16,
     rect: Rect,
 }
@@ -66,10 +95,13 @@ impl HitTile {
     }
 }
 
-#[derive(Default, Clone, Copy)]
+#[derive(Default, Clone)]
 struct UiLayoutState {
     path_input_area: Option<UiRect>,
     treemap_inner_area: Option<UiRect>,
+    largest_list_area: Option<UiRect>,
+    /// Entry behind each line of the largest files panel; headings have none
+    largest_rows: Vec<Option<PathBuf>>,
 }
 
 struct App {
@@ -77,13 +109,42 @@ struct App {
     input_mode: bool,
     status: String,
     is_scanning: bool,
+    cancel_token: Option<CancelToken>,
     scan_progress: Option<ScanProgress>,
     scan_rx: Option<Receiver<ScanEvent>>,
     last_scan_finished_at: Option<Instant>,
+    rescan_rx: Option<Receiver<RescanResult>>,
+    rescan_path: Option<PathBuf>,
 
     file_tree: Option<FileTree>,
     view_root_path: Option<PathBuf>,
     expansion_state: ExpansionState,
+    size_mode: SizeMode,
+    scan_options: ScanOptions,
+    /// Save finished scans and reuse their unchanged folders on the next scan
+    remember_scans: bool,
+    /// New exclude rule being typed, if any
+    exclude_input: Option<String>,
+    scan_errors: Vec<ScanError>,
+    scan_error_count: u64,
+    show_errors: bool,
+    largest_files: Vec<LargestEntry>,
+    largest_dirs: Vec<LargestEntry>,
+    show_largest: bool,
+    show_file_types: bool,
+    show_owners: bool,
+    /// Per-user totals of the folder the owners panel shows; dropped whenever the tree changes
+    owners: Option<(PathBuf, OwnerUsage)>,
+    /// Loaded the first time an owner is shown
+    owner_names: Option<OwnerNames>,
+    /// User whose tiles stay lit while everything else is dimmed
+    highlight_uid: Option<u32>,
+    /// That user's bytes at or below each node; rebuilt whenever the tree changes
+    highlight_sizes: Option<HashMap<indextree::NodeId, u64>>,
+    /// Cut-off of the "older than" figure shown for the selected folder
+    stale_days: u32,
+    /// Bytes older than `stale_days` in the selected folder; dropped whenever the tree changes
+    stale_size: Option<(PathBuf, u32, u64)>,
 
     selected_path: Option<PathBuf>,
     selected_size: Option<u64>,
@@ -101,12 +162,33 @@ impl Default for App {
             input_mode: true,
             status: String::from("Type path and press Enter to scan"),
             is_scanning: false,
+            cancel_token: None,
             scan_progress: None,
             scan_rx: None,
             last_scan_finished_at: None,
+            rescan_rx: None,
+            rescan_path: None,
             file_tree: None,
             view_root_path: None,
             expansion_state: ExpansionState::default(),
+            size_mode: SizeMode::default(),
+            scan_options: ScanOptions::default(),
+            remember_scans: true,
+            exclude_input: None,
+            scan_errors: Vec::new(),
+            scan_error_count: 0,
+            show_errors: false,
+            largest_files: Vec::new(),
+            largest_dirs: Vec::new(),
+            show_largest: false,
+            show_file_types: false,
+            show_owners: false,
+            owners: None,
+            owner_names: None,
+            highlight_uid: None,
+            highlight_sizes: None,
+            stale_days: DEFAULT_STALE_DAYS,
+            stale_size: None,
             selected_path: None,
             selected_size: None,
             selected_is_dir: false,
@@ -123,53 +205,71 @@ impl App {
             return;
         }
 
-        let path = if self.path_input.trim().is_empty() {
-            ".".to_string()
-        } else {
-            self.path_input.trim().to_string()
-        };
+        if self.path_input.trim().is_empty() {
+            self.path_input = ".".to_string();
+        }
+        // Several paths are separated like `PATH` entries and scanned side by side.
+        let paths: Vec<PathBuf> = std::env::split_paths(self.path_input.trim())
+            .filter(|path| !path.as_os_str().is_empty())
+            .collect();
 
-        if !Path::new(&path).exists() {
-            self.status = format!("Path does not exist: {}", path);
+        if let Some(missing) = paths.iter().find(|path| !path.exists()) {
+            self.status = format!("Path does not exist: {}", missing.display());
             return;
         }
 
-        self.path_input = path.clone();
-        self.status = format!("Scanning {} ...", path);
+        self.status = format!("Scanning {} ...", self.path_input.trim());
         self.is_scanning = true;
-        self.scan_progress = Some(ScanProgress {
-            phase: ScanPhase::Discovering,
-            discovered_entries: 0,
-            processed_entries: 0,
-            total_entries: None,
-            total_files: 0,
-            total_dirs: 0,
-            total_size: 0,
-            top_level_preview: Vec::new(),
-        });
+        self.scan_progress = Some(ScanProgress::default());
+        // Where the user goes in the live tree is kept once the scan is done.
+        self.view_root_path = None;
+        self.expansion_state.collapse_all();
+        self.selected_path = None;
+        self.selected_size = None;
+        self.selected_is_dir = false;
 
         let (tx, rx) = mpsc::channel::<ScanEvent>();
         self.scan_rx = Some(rx);
+        let size_mode = self.size_mode;
+        let options = self.scan_options.clone();
+        let cache = ScanCache::default_location();
+        let remember_scans = self.remember_scans;
+
+        let mut crawler = FileCrawler::with_options(options.clone());
+        self.cancel_token = Some(crawler.cancel_token());
 
         thread::spawn(move || {
-            let mut crawler = FileCrawler::new();
             let progress_tx = tx.clone();
             let reporter = Arc::new(move |progress: ScanProgress| {
                 let _ = progress_tx.send(ScanEvent::Progress(progress));
             });
 
             let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-                let (nodes, stats) = crawler.scan_with_progress(&path, Some(reporter));
-                let mut tree = FileTree::new(&path);
-                for node in nodes {
-                    tree.upsert_node(node.path, node.size, node.is_dir);
+                if let Some(cache) = &cache {
+                    if let Some(estimate) = cache.load_estimate(&paths) {
+                        crawler.set_estimate(estimate);
+                    }
+                    let previous = remember_scans.then(|| cache.load(&paths, &options)).flatten();
+                    if let Some(previous) = previous {
+                        crawler.set_previous_scan(previous);
+                    }
                 }
-                tree.calculate_sizes();
+                let (mut tree, stats) = crawler.scan_paths_with_progress(&paths, Some(reporter));
+                if let Some(cache) = &cache {
+                    // A scan that can't be saved just won't speed up or estimate the next one.
+                    if !stats.cancelled {
+                        let _ = cache.store_estimate(&paths, stats.estimate());
+                    }
+                    if remember_scans {
+                        let _ = cache.store(&tree, &options);
+                    }
+                }
+                tree.set_size_mode(size_mode);
                 ScanResult { tree, stats }
             }));
 
             let event = match result {
-                Ok(scan_result) => ScanEvent::Completed(Ok(scan_result)),
+                Ok(scan_result) => ScanEvent::Completed(Ok(Box::new(scan_result))),
                 Err(_) => ScanEvent::Completed(Err("Scan thread panicked".to_string())),
             };
 
@@ -177,14 +277,96 @@ impl App {
         });
     }
 
+    /// Scan the selected folder (or the folder in view) again and graft it into the tree.
+    fn start_rescan(&mut self) {
+        if self.is_scanning || self.rescan_path.is_some() {
+            return;
+        }
+        let path = match &self.selected_path {
+            Some(path) if self.selected_is_dir => path.clone(),
+            _ => match self.current_view_root_path() {
+                Some(path) if !path.as_os_str().is_empty() => path.to_path_buf(),
+                _ => return,
+            },
+        };
+
+        let (tx, rx) = mpsc::channel::<RescanResult>();
+        self.rescan_rx = Some(rx);
+        self.rescan_path = Some(path.clone());
+        self.status = format!("Rescanning {} ...", path.display());
+        let size_mode = self.size_mode;
+        let mut crawler = FileCrawler::with_options(self.scan_options.clone());
+
+        thread::spawn(move || {
+            let (mut tree, stats) = crawler.scan_with_progress(&path, None);
+            tree.set_size_mode(size_mode);
+            let _ = tx.send(RescanResult { path, tree, stats });
+        });
+    }
+
+    fn poll_rescan(&mut self) {
+        let result = match self.rescan_rx.as_ref().map(|rx| rx.try_recv()) {
+            Some(Ok(result)) => result,
+            Some(Err(TryRecvError::Empty)) | None => return,
+            Some(Err(TryRecvError::Disconnected)) => {
+                self.rescan_rx = None;
+                self.rescan_path = None;
+                self.status = "Rescan channel disconnected".to_string();
+                return;
+            }
+        };
+        self.rescan_rx = None;
+        self.rescan_path = None;
+
+        let Some(tree) = self.file_tree.as_mut() else {
+            return;
+        };
+        if !tree.graft_subtree(&result.path, result.tree) {
+            self.status = format!("{} is no longer in the tree", result.path.display());
+            return;
+        }
+        self.owners = None;
+        self.highlight_sizes = None;
+        self.stale_size = None;
+
+        // Errors from the old scan of that folder are replaced by the fresh ones.
+        let before = self.scan_errors.len() as u64;
+        self.scan_errors.retain(|error| !error.path.starts_with(&result.path));
+        let dropped = before - self.scan_errors.len() as u64;
+        self.scan_error_count = self.scan_error_count.saturating_sub(dropped) + result.stats.error_count;
+        let rescanned_size = result.stats.total_size_in(self.size_mode);
+        self.scan_errors.extend(result.stats.errors);
+        self.scan_errors.sort_by(|a, b| a.path.cmp(&b.path));
+        largest::replace_under(&mut self.largest_files, &result.path, result.stats.largest_files);
+        largest::replace_under(&mut self.largest_dirs, &result.path, result.stats.largest_dirs);
+
+        let tree = self.file_tree.as_ref().expect("checked above");
+        self.expansion_state
+            .retain_paths(|path| tree.get_node(path).is_some());
+        if self.selected_path.as_ref().is_some_and(|path| tree.get_node(path).is_none()) {
+            self.selected_path = None;
+            self.selected_is_dir = false;
+        }
+        self.selected_size = self.selected_node().map(|node| node.cumulative_size);
+        self.status = format!(
+            "Rescanned {}: {} files, {} dirs, {}",
+            result.path.display(),
+            result.stats.total_files,
+            result.stats.total_dirs,
+            format_size(rescanned_size),
+        );
+    }
+
     fn poll_scan_updates(&mut self) {
-        let mut done: Option<Result<ScanResult, String>> = None;
+        let mut done: Option<Result<Box<ScanResult>, String>> = None;
+        let mut live_tree = None;
         let mut disconnected = false;
 
         if let Some(rx) = self.scan_rx.as_ref() {
             loop {
                 match rx.try_recv() {
-                    Ok(ScanEvent::Progress(progress)) => {
+                    Ok(ScanEvent::Progress(mut progress)) => {
+                        live_tree = progress.live_tree.take().or(live_tree);
                         self.scan_progress = Some(progress);
                     }
                     Ok(ScanEvent::Completed(result)) => {
@@ -199,9 +381,14 @@ impl App {
             }
         }
 
+        if let (Some(live), None) = (live_tree, &done) {
+            self.apply_live_tree(live);
+        }
+
         if disconnected {
             self.scan_rx = None;
             self.is_scanning = false;
+            self.cancel_token = None;
             if done.is_none() {
                 self.status = "Scan channel disconnected".to_string();
             }
@@ -210,23 +397,85 @@ impl App {
         if let Some(result) = done {
             self.scan_rx = None;
             self.is_scanning = false;
+            self.cancel_token = None;
             self.scan_progress = None;
 
             match result {
                 Ok(scan_result) => {
+                    let stats = &scan_result.stats;
+                    let outcome = if stats.cancelled {
+                        "Scan cancelled (partial)"
+                    } else {
+                        "Scan complete"
+                    };
+                    let mut status = format!(
+                        "{}: {} files, {} dirs, {} total",
+                        outcome,
+                        stats.total_files,
+                        stats.total_dirs,
+                        format_size(stats.total_size_in(self.size_mode)),
+                    );
+                    if stats.shared_links > 0 {
+                        status.push_str(&format!(
+                            " ({} hardlinks sharing {})",
+                            stats.shared_links,
+                            format_size(stats.shared_size_in(self.size_mode)),
+                        ));
+                    }
+                    if stats.ignored_entries > 0 {
+                        status.push_str(&format!(
+                            " ({} ignored, {})",
+                            stats.ignored_entries,
+                            format_size(stats.ignored_size_in(self.size_mode)),
+                        ));
+                    }
+                    if stats.excluded_entries > 0 || stats.pruned_files > 0 {
+                        status.push_str(&format!(
+                            " ({} excluded, {} small files skipped)",
+                            stats.excluded_entries, stats.pruned_files,
+                        ));
+                    }
+                    let special_files = stats.sockets + stats.fifos + stats.block_devices + stats.char_devices;
+                    if special_files > 0 {
+                        status.push_str(&format!(
+                            " ({} sockets, {} fifos, {} devices)",
+                            stats.sockets,
+                            stats.fifos,
+                            stats.block_devices + stats.char_devices,
+                        ));
+                    }
+                    if stats.virtual_mounts > 0 {
+                        status.push_str(&format!(" ({} virtual filesystems skipped)", stats.virtual_mounts));
+                    }
+                    if stats.symlink_loops > 0 {
+                        status.push_str(&format!(" ({} symlink loops not followed)", stats.symlink_loops));
+                    }
+                    if stats.unresponsive_dirs > 0 {
+                        status.push_str(&format!(" ({} unresponsive dirs left unread)", stats.unresponsive_dirs));
+                    }
+                    if stats.reused_dirs > 0 {
+                        status.push_str(&format!(
+                            " ({} dirs reused from the last scan, {} re-read)",
+                            stats.reused_dirs, stats.reread_dirs
+                        ));
+                    }
+                    if stats.error_count > 0 {
+                        status.push_str(&format!(
+                            " - {} unreadable, sizes are lower bounds (w: errors)",
+                            stats.error_count
+                        ));
+                    }
+                    self.status = status;
+                    self.scan_errors = scan_result.stats.errors;
+                    self.scan_error_count = scan_result.stats.error_count;
+                    self.largest_files = scan_result.stats.largest_files;
+                    self.largest_dirs = scan_result.stats.largest_dirs;
                     self.file_tree = Some(scan_result.tree);
-                    self.view_root_path = None;
-                    self.expansion_state.collapse_all();
-                    self.selected_path = None;
-                    self.selected_size = None;
-                    self.selected_is_dir = false;
+                    self.owners = None;
+                    self.highlight_sizes = None;
+                    self.stale_size = None;
+                    self.retain_view_state();
                     self.last_scan_finished_at = Some(Instant::now());
-                    self.status = format!(
-                        "Scan complete: {} files, {} dirs, {} total",
-                        scan_result.stats.total_files,
-                        scan_result.stats.total_dirs,
-                        format_size(scan_result.stats.total_size),
-                    );
                 }
                 Err(err) => {
                     self.status = format!("Scan failed: {}", err);
@@ -235,12 +484,64 @@ impl App {
         }
     }
 
+    /// Show the tree of the running scan so far in place of the last one.
+    fn apply_live_tree(&mut self, live: Arc<FileTree>) {
+        let mut tree = Arc::try_unwrap(live).unwrap_or_else(|live| (*live).clone());
+        tree.set_size_mode(self.size_mode);
+        self.file_tree = Some(tree);
+        self.owners = None;
+        self.highlight_sizes = None;
+        self.stale_size = None;
+        self.retain_view_state();
+    }
+
+    /// Forget the view root, open folders and selection where the tree has no
+    /// such path, and update the selected size from it.
+    fn retain_view_state(&mut self) {
+        let Some(tree) = self.file_tree.as_ref() else {
+            return;
+        };
+        self.expansion_state.retain_paths(|path| tree.get_node(path).is_some());
+        if self.view_root_path.as_ref().is_some_and(|path| tree.get_node(path).is_none()) {
+            self.view_root_path = None;
+        }
+        match self.selected_path.as_ref().and_then(|path| tree.get_node(path)) {
+            Some(node_id) => self.selected_size = Some(tree.get_arena()[node_id].get().cumulative_size),
+            None => {
+                self.selected_path = None;
+                self.selected_size = None;
+                self.selected_is_dir = false;
+            }
+        }
+    }
+
     fn on_key(&mut self, key: KeyEvent) {
         if key.modifiers.contains(KeyModifiers::CONTROL) && matches!(key.code, KeyCode::Char('c')) {
             self.should_quit = true;
             return;
         }
 
+        if let Some(input) = self.exclude_input.as_mut() {
+            match key.code {
+                KeyCode::Enter => {
+                    let text = std::mem::take(input);
+                    self.exclude_input = None;
+                    self.add_exclude_rule(&text);
+                }
+                KeyCode::Esc => {
+                    self.exclude_input = None;
+                }
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Char(ch) => {
+                    input.push(ch);
+                }
+                _ => {}
+            }
+            return;
+        }
+
         if self.input_mode {
             match key.code {
                 KeyCode::Enter => {
@@ -266,11 +567,129 @@ impl App {
             KeyCode::Char('/') => self.input_mode = true,
             KeyCode::Char('s') => self.start_scan(),
             KeyCode::Char('r') => self.start_scan(),
+            KeyCode::Char('R') => self.start_rescan(),
             KeyCode::Char('e') => self.expand_selected(),
             KeyCode::Char('d') => self.deepen_selected(),
             KeyCode::Char('c') => self.collapse_selected(),
             KeyCode::Char('z') => self.zoom_into_selected(),
+            KeyCode::Char('a') => self.toggle_size_mode(),
+            KeyCode::Char('x') => {
+                self.scan_options.one_file_system = !self.scan_options.one_file_system;
+                self.status = format!(
+                    "One filesystem {} (applies to next scan)",
+                    on_off(self.scan_options.one_file_system)
+                );
+            }
+            KeyCode::Char('i') => self.exclude_input = Some(String::new()),
+            KeyCode::Char('I') => {
+                let count = self.scan_options.exclude.rules().len();
+                if count > 0 {
+                    let removed = self.scan_options.exclude.rules()[count - 1].to_string();
+                    self.scan_options.exclude.remove(count - 1);
+                    self.status = format!("Removed exclude {} (applies to next scan)", removed);
+                }
+            }
+            KeyCode::Char('m') => self.cycle_min_file_size(),
+            KeyCode::Char('p') => {
+                self.scan_options.throttle = self.scan_options.throttle.profile().next().throttle();
+                self.status = format!(
+                    "Scan profile: {} (applies to next scan)",
+                    self.scan_options.throttle.profile().label()
+                );
+            }
+            KeyCode::Char('t') => self.cycle_gentle_threads(),
+            KeyCode::Char('w') => {
+                self.show_errors = !self.show_errors;
+                self.show_largest = false;
+                self.show_file_types = false;
+                self.show_owners = false;
+            }
+            KeyCode::Char('L') => {
+                self.show_largest = !self.show_largest;
+                self.show_errors = false;
+                self.show_file_types = false;
+                self.show_owners = false;
+            }
+            KeyCode::Char('b') => {
+                self.show_file_types = !self.show_file_types;
+                self.show_errors = false;
+                self.show_largest = false;
+                self.show_owners = false;
+            }
+            KeyCode::Char('O') => {
+                self.show_owners = !self.show_owners;
+                self.show_errors = false;
+                self.show_largest = false;
+                self.show_file_types = false;
+            }
+            KeyCode::Char('o') => self.cycle_highlighted_user(),
+            KeyCode::Char('n') => {
+                let position = STALE_DAY_STEPS.iter().position(|days| *days == self.stale_days);
+                self.stale_days = STALE_DAY_STEPS[position.map_or(0, |i| (i + 1) % STALE_DAY_STEPS.len())];
+                self.status = format!("Showing bytes older than {} days", self.stale_days);
+            }
+            KeyCode::Char(digit @ '1'..='9') if self.show_largest => {
+                let index = digit as usize - '1' as usize;
+                if let Some(entry) = self.largest_files.get(index) {
+                    let path = entry.path.clone();
+                    self.jump_to(&path);
+                }
+            }
+            KeyCode::Char('l') => {
+                self.scan_options.symlinks = self.scan_options.symlinks.next();
+                self.status = format!(
+                    "Symlinks: {} (applies to next scan)",
+                    self.scan_options.symlinks.label()
+                );
+            }
+            KeyCode::Char('f') => {
+                self.scan_options.metadata = self.scan_options.metadata.next();
+                self.status = format!(
+                    "Metadata: {} (applies to next scan)",
+                    self.scan_options.metadata.label()
+                );
+            }
+            KeyCode::Char('v') => {
+                self.scan_options.virtual_fs = self.scan_options.virtual_fs.next();
+                self.status = format!(
+                    "Virtual filesystems: {} (applies to next scan)",
+                    self.scan_options.virtual_fs.label()
+                );
+            }
+            KeyCode::Char('B') => {
+                self.scan_options.order = self.scan_options.order.next();
+                self.status = format!(
+                    "Folder order: {} (applies to next scan)",
+                    self.scan_options.order.label()
+                );
+            }
+            KeyCode::Char('D') => {
+                let current = READ_TIMEOUT_CHOICES.iter().position(|t| *t == self.scan_options.read_timeout);
+                self.scan_options.read_timeout =
+                    READ_TIMEOUT_CHOICES[current.map_or(0, |i| (i + 1) % READ_TIMEOUT_CHOICES.len())];
+                self.status = format!(
+                    "Read timeout: {} (applies to next scan)",
+                    read_timeout_label(self.scan_options.read_timeout)
+                );
+            }
+            KeyCode::Char('k') => {
+                self.remember_scans = !self.remember_scans;
+                self.status = format!("Remember scans {} (applies to next scan)", on_off(self.remember_scans));
+            }
+            KeyCode::Char('g') => {
+                self.scan_options.ignore_files = self.scan_options.ignore_files.next();
+                self.status = format!(
+                    "Ignore files: {} (applies to next scan)",
+                    self.scan_options.ignore_files.label()
+                );
+            }
             KeyCode::Char('u') | KeyCode::Backspace => self.zoom_out_one_level(),
+            KeyCode::Esc if self.is_scanning => {
+                if let Some(token) = &self.cancel_token {
+                    token.cancel();
+                    self.status = "Cancelling scan...".to_string();
+                }
+            }
             KeyCode::Esc => {
                 self.selected_path = None;
                 self.selected_size = None;
@@ -288,6 +707,15 @@ impl App {
                     return;
                 }
             }
+            if let Some(list_area) = self.ui_layout.largest_list_area {
+                if point_in_rect(list_area, event.column, event.row) {
+                    let row = (event.row - list_area.y) as usize;
+                    if let Some(Some(path)) = self.ui_layout.largest_rows.get(row).cloned() {
+                        self.jump_to(&path);
+                    }
+                    return;
+                }
+            }
 
             if let Some(tile) = self.tile_at(event.column, event.row).cloned() {
                 if tile.is_aggregate {
@@ -322,6 +750,60 @@ impl App {
         }
     }
 
+    fn add_exclude_rule(&mut self, text: &str) {
+        let Some(rule) = ExcludeRule::parse(text) else {
+            return;
+        };
+        let label = rule.to_string();
+        self.status = match self.scan_options.exclude.push(rule) {
+            Ok(()) => format!("Excluding {} (applies to next scan)", label),
+            Err(err) => format!("Invalid exclude rule: {}", err),
+        };
+    }
+
+    fn cycle_min_file_size(&mut self) {
+        let exclude = &mut self.scan_options.exclude;
+        let position = MIN_FILE_SIZE_STEPS
+            .iter()
+            .position(|size| *size == exclude.min_file_size)
+            .unwrap_or(0);
+        exclude.min_file_size = MIN_FILE_SIZE_STEPS[(position + 1) % MIN_FILE_SIZE_STEPS.len()];
+        self.status = format!(
+            "Skipping files under {} (applies to next scan)",
+            min_size_label(exclude.min_file_size)
+        );
+    }
+
+    fn cycle_gentle_threads(&mut self) {
+        let throttle = &mut self.scan_options.throttle;
+        if throttle.profile() != ScanProfile::Gentle {
+            self.status = "Thread limits apply to the gentle profile (p)".to_string();
+            return;
+        }
+        let position = GENTLE_THREAD_STEPS
+            .iter()
+            .position(|threads| Some(*threads) == throttle.threads)
+            .unwrap_or(0);
+        throttle.threads = Some(GENTLE_THREAD_STEPS[(position + 1) % GENTLE_THREAD_STEPS.len()]);
+        self.status = format!("Gentle scan: {} (applies to next scan)", throttle.summary());
+    }
+
+    fn toggle_size_mode(&mut self) {
+        self.size_mode = self.size_mode.toggled();
+        self.highlight_sizes = None;
+        self.stale_size = None;
+        self.status = format!("Sizing by {}", self.size_mode.label().to_lowercase());
+
+        let Some(tree) = self.file_tree.as_mut() else {
+            return;
+        };
+        tree.set_size_mode(self.size_mode);
+
+        if let Some(node_id) = self.selected_path.as_ref().and_then(|path| tree.get_node(path)) {
+            self.selected_size = tree.get_arena().get(node_id).map(|n| n.get().cumulative_size);
+        }
+    }
+
     fn expand_selected(&mut self) {
         if !self.selected_is_dir {
             return;
@@ -349,6 +831,31 @@ impl App {
         }
     }
 
+    /// Zoom to the folder holding `path` and select it.
+    fn jump_to(&mut self, path: &Path) {
+        if self.is_scanning {
+            return;
+        }
+        let Some(tree) = self.file_tree.as_ref() else {
+            return;
+        };
+        let Some(node) = tree.get_node(path).and_then(|id| tree.get_arena().get(id)) else {
+            self.status = format!("{} is no longer in the tree", path.display());
+            return;
+        };
+        let (size, is_dir) = (node.get().cumulative_size, node.get().is_dir);
+        let root_path = tree.root_path();
+        self.view_root_path = tree
+            .parent_path(path)
+            .filter(|parent| Some(*parent) != root_path)
+            .map(Path::to_path_buf);
+        self.expansion_state.collapse_all();
+        self.selected_path = Some(path.to_path_buf());
+        self.selected_size = Some(size);
+        self.selected_is_dir = is_dir;
+        self.status = format!("Jumped to {} ({})", path.display(), format_size(size));
+    }
+
     fn zoom_into_selected(&mut self) {
         if !self.selected_is_dir {
             return;
@@ -373,19 +880,10 @@ impl App {
             return;
         };
 
-        if current == root_path {
-            self.view_root_path = None;
-            return;
-        }
-
-        let parent = current.parent().map(Path::to_path_buf);
-        match parent {
-            Some(parent_path) if parent_path.starts_with(root_path) => {
-                if parent_path == root_path {
-                    self.view_root_path = None;
-                } else {
-                    self.view_root_path = Some(parent_path);
-                }
+        // Follow the tree's parent link, which also leads from a scanned path to a synthetic root.
+        match tree.parent_path(current) {
+            Some(parent_path) if parent_path != root_path => {
+                self.view_root_path = Some(parent_path.to_path_buf());
             }
             _ => {
                 self.view_root_path = None;
@@ -394,6 +892,69 @@ impl App {
         self.expansion_state.collapse_all();
     }
 
+    /// The selected folder, or the folder in view when a file or nothing is selected.
+    fn breakdown_folder(&self) -> Option<indextree::NodeId> {
+        let tree = self.file_tree.as_ref()?;
+        self.selected_path
+            .as_ref()
+            .filter(|_| self.selected_is_dir)
+            .and_then(|path| tree.get_node(path))
+            .or_else(|| self.current_view_root_node())
+    }
+
+    /// Recompute the per-user totals if the selected folder (or the folder in
+    /// view) is not the one they were computed for.
+    fn refresh_owners(&mut self) {
+        let (Some(tree), Some(folder)) = (self.file_tree.as_ref(), self.breakdown_folder()) else {
+            self.owners = None;
+            return;
+        };
+        self.owner_names.get_or_insert_with(OwnerNames::load);
+        let path = &tree.get_arena()[folder].get().path;
+        if self.owners.as_ref().map(|(cached, _)| cached) != Some(path) {
+            self.owners = Some((path.clone(), tree.owner_usage(folder)));
+        }
+    }
+
+    /// Bytes in the selected folder untouched for `stale_days`, cached per folder and cut-off.
+    fn selected_stale_size(&mut self) -> Option<u64> {
+        let tree = self.file_tree.as_ref()?;
+        let path = self.selected_path.as_ref().filter(|_| self.selected_is_dir)?;
+        let node_id = tree.get_node(path)?;
+        match &self.stale_size {
+            Some((cached, days, size)) if cached == path && *days == self.stale_days => Some(*size),
+            _ => {
+                let size = tree.bytes_older_than(node_id, self.stale_days);
+                self.stale_size = Some((path.clone(), self.stale_days, size));
+                Some(size)
+            }
+        }
+    }
+
+    /// Step the treemap highlight through the users of the selected folder, largest first, then off.
+    fn cycle_highlighted_user(&mut self) {
+        self.refresh_owners();
+        let Some((_, usage)) = &self.owners else {
+            return;
+        };
+        let users: Vec<u32> = usage
+            .users_by_size(self.size_mode)
+            .into_iter()
+            .map(|(uid, _)| uid)
+            .collect();
+        let next = match self.highlight_uid.and_then(|uid| users.iter().position(|u| *u == uid)) {
+            Some(index) => users.get(index + 1).copied(),
+            None => users.first().copied(),
+        };
+        self.highlight_uid = next;
+        self.highlight_sizes = None;
+        let names = self.owner_names.get_or_insert_with(OwnerNames::load);
+        self.status = match next {
+            Some(uid) => format!("Highlighting files of {}", names.user(uid)),
+            None => "Highlight off".to_string(),
+        };
+    }
+
     fn current_view_root_path(&self) -> Option<&Path> {
         let tree = self.file_tree.as_ref()?;
         if let Some(path) = self.view_root_path.as_deref() {
@@ -441,12 +1002,19 @@ impl App {
 
         let mut tiles = Vec::new();
         flatten_render_nodes(&render_nodes, 0, &mut tiles);
+        if let Some(uid) = self.highlight_uid {
+            let sizes = self.highlight_sizes.get_or_insert_with(|| tree.user_sizes(uid));
+            for tile in tiles.iter_mut().filter(|tile| !tile.is_aggregate) {
+                tile.dimmed = tree.get_node(&tile.path).is_none_or(|id| !sizes.contains_key(&id));
+            }
+        }
         self.hit_tiles = tiles
             .iter()
             .filter_map(|tile| {
                 tile_bounds_in_area(tile, area).map(|(x0, y0, x1, y1)| HitTile {
                     path: tile.path.clone(),
-                    is_dir: tile.is_dir,
+                    // Placeholders are directories with nothing to expand or zoom into.
+                    is_dir: tile.is_dir && tile.placeholder.is_none(),
                     is_aggregate: tile.is_aggregate,
                     size: tile.size,
                     depth: tile.depth,
@@ -476,6 +1044,12 @@ impl App {
             })
     }
 
+    fn selected_node(&self) -> Option<&TreeNode> {
+        let tree = self.file_tree.as_ref()?;
+        let node_id = tree.get_node(self.selected_path.as_ref()?)?;
+        tree.get_arena().get(node_id).map(|node| node.get())
+    }
+
     fn select_tile(&mut self, tile: &HitTile) {
         self.selected_path = Some(tile.path.clone());
         self.selected_size = Some(tile.size);
@@ -496,6 +1070,13 @@ fn flatten_render_nodes(nodes: &[RenderNode], depth: u16, out: &mut Vec<VisibleT
             size: node.size,
             is_dir: node.is_dir,
             is_aggregate: node.is_aggregate,
+            is_shared_link: node.is_shared_link,
+            is_ignored: node.is_ignored,
+            partial: node.partial,
+            symlink_target: node.symlink_target.clone(),
+            placeholder: node.placeholder.clone(),
+            special: node.special,
+            dimmed: false,
             depth,
             rect: node.outer_rect,
         });
@@ -541,6 +1122,22 @@ fn point_in_rect(rect: UiRect, x: u16, y: u16) -> bool {
         && y < rect.y.saturating_add(rect.height)
 }
 
+fn on_off(enabled: bool) -> &'static str {
+    if enabled {
+        "on"
+    } else {
+        "off"
+    }
+}
+
+fn min_size_label(size: u64) -> String {
+    if size == 0 {
+        "off".to_string()
+    } else {
+        format_size(size)
+    }
+}
+
 fn format_size(size: u64) -> String {
     const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
     let mut value = size as f64;
@@ -552,27 +1149,40 @@ fn format_size(size: u64) -> String {
     format!("{:.1} {}", value, UNITS[unit_index])
 }
 
-fn progress_status(progress: &ScanProgress) -> String {
+fn progress_status(progress: &ScanProgress, size_mode: SizeMode) -> String {
+    let total_size = progress.total_size_in(size_mode);
+
     match progress.phase {
         ScanPhase::Discovering => format!(
             "Discovering entries... {} found",
             progress.discovered_entries
         ),
         ScanPhase::Processing => {
+            let throughput = format!(
+                "{:.0} entries/s, {}/s",
+                progress.entries_per_sec,
+                format_size(progress.bytes_per_sec as u64)
+            );
             if let Some(frac) = progress.fraction() {
+                let approx = if progress.is_estimated() { "~" } else { "" };
+                let eta = progress.eta_label().map(|eta| format!(", {}", eta)).unwrap_or_default();
                 format!(
-                    "Processing {:.0}% | files: {} dirs: {} | {}",
+                    "Processing {}{:.0}%{} | files: {} dirs: {} | {} | {}",
+                    approx,
                     frac * 100.0,
+                    eta,
                     progress.total_files,
                     progress.total_dirs,
-                    format_size(progress.total_size),
+                    format_size(total_size),
+                    throughput,
                 )
             } else {
                 format!(
-                    "Processing... files: {} dirs: {} | {}",
+                    "Processing... files: {} dirs: {} | {} | {}",
                     progress.total_files,
                     progress.total_dirs,
-                    format_size(progress.total_size),
+                    format_size(total_size),
+                    throughput,
                 )
             }
         }
@@ -583,6 +1193,33 @@ fn tile_color(tile: &VisibleTile, max_size: u64) -> Color {
     if tile.is_aggregate {
         return Color::Rgb(80, 80, 84);
     }
+    if tile.dimmed {
+        return Color::Rgb(34, 36, 40);
+    }
+    if tile.is_shared_link {
+        return Color::Rgb(84, 99, 120);
+    }
+    if tile.symlink_target.is_some() && !tile.is_dir {
+        return Color::Rgb(56, 112, 120);
+    }
+    if tile.is_ignored {
+        return Color::Rgb(88, 80, 104);
+    }
+    match tile.placeholder {
+        Some(Placeholder::MountPoint(_)) => return Color::Rgb(45, 52, 64),
+        Some(Placeholder::Excluded(_)) => return Color::Rgb(58, 46, 52),
+        Some(Placeholder::SymlinkLoop) => return Color::Rgb(40, 66, 72),
+        Some(Placeholder::VirtualFs(_)) => return Color::Rgb(50, 50, 62),
+        Some(Placeholder::Unresponsive) => return Color::Rgb(72, 44, 40),
+        None => {}
+    }
+    match tile.special {
+        Some(FileKind::Socket) => return Color::Rgb(70, 58, 96),
+        Some(FileKind::Fifo) => return Color::Rgb(52, 78, 70),
+        Some(FileKind::BlockDevice) => return Color::Rgb(88, 70, 44),
+        Some(_) => return Color::Rgb(64, 74, 44),
+        None => {}
+    }
 
     let ratio = if max_size == 0 {
         0.0
@@ -645,6 +1282,8 @@ impl Widget for TreemapWidget<'_> {
             let bg = tile_color(tile, max_size);
             let border_color = if is_selected {
                 Color::Rgb(246, 211, 101)
+            } else if tile.partial {
+                Color::Rgb(240, 150, 60)
             } else {
                 Color::Rgb(224, 224, 224)
             };
@@ -696,8 +1335,17 @@ impl Widget for TreemapWidget<'_> {
             if label_width >= 4 {
                 let label = if tile.is_aggregate {
                     tile.name.clone()
+                } else if let Some(placeholder) = tile.placeholder.as_ref() {
+                    format!("{} [{}]", tile.name, placeholder.label())
+                } else if let Some(kind) = tile.special {
+                    format!("{} [{}]", tile.name, kind.label())
                 } else {
-                    format!("{} {}", tile.name, format_size(tile.size))
+                    let lower_bound = if tile.partial { "≥" } else { "" };
+                    format!("{} {}{}", tile.name, lower_bound, format_size(tile.size))
+                };
+                let label = match &tile.symlink_target {
+                    Some(target) => format!("{} → {}", label, target.display()),
+                    None => label,
                 };
                 let mut label = label;
                 if label.len() > label_width {
@@ -735,7 +1383,8 @@ fn draw_ui(frame: &mut Frame, app: &mut App) {
         Constraint::Length(3),
         Constraint::Length(6),
         Constraint::Min(8),
-        Constraint::Length(4),
+        Constraint::Length(13),
+        Constraint::Length(10),
     ])
     .split(left_inner);
 
@@ -758,36 +1407,74 @@ fn draw_ui(frame: &mut Frame, app: &mut App) {
     );
 
     let progress_text = if app.is_scanning {
-        app.scan_progress
+        let progress = app
+            .scan_progress
             .as_ref()
-            .map(progress_status)
-            .unwrap_or_else(|| "Scanning...".to_string())
+            .map(|progress| progress_status(progress, app.size_mode))
+            .unwrap_or_else(|| "Scanning...".to_string());
+        let cancelling = app.cancel_token.as_ref().is_some_and(|t| t.is_cancelled());
+        if cancelling {
+            format!("{} - cancelling...", progress)
+        } else {
+            format!("{} (Esc: cancel)", progress)
+        }
     } else {
         app.status.clone()
     };
     let progress = Paragraph::new(progress_text)
+        .wrap(Wrap { trim: true })
         .block(Block::default().title(" Status ").borders(Borders::ALL));
     frame.render_widget(progress, left_rows[1]);
 
+    let stale_size = app.selected_stale_size();
     let selected_lines = {
         let mut lines = Vec::new();
-        let view_root = app
-            .current_view_root_path()
-            .map(|p| p.display().to_string())
-            .unwrap_or_else(|| "(none)".to_string());
+        let view_root = match app.current_view_root_path() {
+            // The synthetic root over several scanned paths has no path of its own.
+            Some(path) if path.as_os_str().is_empty() => app
+                .file_tree
+                .as_ref()
+                .and_then(|tree| tree.get_arena().get(tree.get_root()))
+                .map(|node| node.get().name.clone())
+                .unwrap_or_default(),
+            Some(path) => path.display().to_string(),
+            None => "(none)".to_string(),
+        };
         lines.push(Line::from(vec![
             Span::styled("View: ", Style::default().fg(Color::Gray)),
             Span::raw(view_root),
         ]));
+        lines.push(Line::from(vec![
+            Span::styled("Sizing: ", Style::default().fg(Color::Gray)),
+            Span::raw(app.size_mode.label()),
+        ]));
 
         if let Some(path) = app.selected_path.as_ref() {
             lines.push(Line::from(vec![
                 Span::styled("Selected: ", Style::default().fg(Color::Gray)),
                 Span::raw(path.display().to_string()),
             ]));
+            if let Some(placeholder) = app.selected_node().and_then(|n| n.placeholder.as_ref()) {
+                lines.push(Line::from(vec![
+                    Span::styled("Not scanned: ", Style::default().fg(Color::Gray)),
+                    Span::raw(placeholder.label()),
+                ]));
+            }
+            let kind = match app.selected_node() {
+                Some(TreeNode {
+                    placeholder: Some(Placeholder::Excluded(_)),
+                    ..
+                }) => "directory (excluded)",
+                Some(node) if node.placeholder.is_some() => "directory (not scanned)",
+                Some(TreeNode {
+                    special: Some(kind), ..
+                }) => kind.label(),
+                _ if app.selected_is_dir => "directory",
+                _ => "file",
+            };
             lines.push(Line::from(vec![
                 Span::styled("Type: ", Style::default().fg(Color::Gray)),
-                Span::raw(if app.selected_is_dir { "directory" } else { "file" }),
+                Span::raw(kind),
                 Span::raw("  "),
                 Span::styled("Size: ", Style::default().fg(Color::Gray)),
                 Span::raw(
@@ -796,6 +1483,99 @@ fn draw_ui(frame: &mut Frame, app: &mut App) {
                         .unwrap_or_else(|| "n/a".to_string()),
                 ),
             ]));
+            if let Some(node) = app.selected_node() {
+                if let Some(target) = &node.symlink_target {
+                    lines.push(Line::from(vec![
+                        Span::styled("Symlink → ", Style::default().fg(Color::Gray)),
+                        Span::raw(target.display().to_string()),
+                    ]));
+                }
+                if node.is_dir {
+                    let total = node.age.total();
+                    let rows: Vec<_> = node.age.rows().collect();
+                    for (index, pair) in rows.chunks(2).enumerate() {
+                        let mut spans = vec![Span::styled(
+                            if index == 0 { "Age: " } else { "     " },
+                            Style::default().fg(Color::Gray),
+                        )];
+                        for (label, bytes) in pair {
+                            spans.push(Span::raw(format!(
+                                "{} {} ({:.0}%)  ",
+                                label,
+                                format_size(*bytes),
+                                percent_of(*bytes, total)
+                            )));
+                        }
+                        lines.push(Line::from(spans));
+                    }
+                    if let Some(stale) = stale_size {
+                        lines.push(Line::from(vec![
+                            Span::styled(
+                                format!("Older than {} days: ", app.stale_days),
+                                Style::default().fg(Color::Gray),
+                            ),
+                            Span::raw(format!(
+                                "{} ({:.0}%)",
+                                format_size(stale),
+                                percent_of(stale, node.cumulative_size)
+                            )),
+                        ]));
+                    }
+                } else if node.meta.is_none() && node.special.is_none() {
+                    lines.push(Line::from(vec![
+                        Span::styled("Modified: ", Style::default().fg(Color::Gray)),
+                        Span::raw(format_timestamp(node.modified)),
+                    ]));
+                }
+                if let Some(meta) = &node.meta {
+                    lines.push(Line::from(vec![
+                        Span::styled("Mode: ", Style::default().fg(Color::Gray)),
+                        Span::raw(meta.mode_string()),
+                        Span::styled("  owner: ", Style::default().fg(Color::Gray)),
+                        Span::raw(format!("{}:{}", meta.uid, meta.gid)),
+                        Span::styled("  inode: ", Style::default().fg(Color::Gray)),
+                        Span::raw(meta.inode.to_string()),
+                    ]));
+                    lines.push(Line::from(vec![
+                        Span::styled("Modified: ", Style::default().fg(Color::Gray)),
+                        Span::raw(format_timestamp(meta.modified)),
+                        Span::styled("  accessed: ", Style::default().fg(Color::Gray)),
+                        Span::raw(format_timestamp(meta.accessed)),
+                    ]));
+                }
+                if node.partial {
+                    lines.push(Line::from(Span::styled(
+                        "Partly unreadable: size is a lower bound",
+                        Style::default().fg(Color::Yellow),
+                    )));
+                }
+                if node.shared_link {
+                    lines.push(Line::from(Span::styled(
+                        "Hardlink: bytes counted at another path",
+                        Style::default().fg(Color::Gray),
+                    )));
+                } else if node.shared_size > 0 {
+                    lines.push(Line::from(vec![
+                        Span::styled("Shared links: ", Style::default().fg(Color::Gray)),
+                        Span::raw(format_size(node.shared_size)),
+                    ]));
+                }
+                if node.ignored {
+                    lines.push(Line::from(Span::styled(
+                        "Ignored by .gitignore/.ignore",
+                        Style::default().fg(Color::Gray),
+                    )));
+                } else if node.ignored_size > 0 {
+                    lines.push(Line::from(vec![
+                        Span::styled("Ignored: ", Style::default().fg(Color::Gray)),
+                        Span::raw(format_size(node.ignored_size)),
+                        Span::styled("  tracked: ", Style::default().fg(Color::Gray)),
+                        Span::raw(format_size(
+                            node.cumulative_size.saturating_sub(node.ignored_size),
+                        )),
+                    ]));
+                }
+            }
         } else {
             lines.push(Line::from("Selected: (none)"));
         }
@@ -809,28 +1589,259 @@ fn draw_ui(frame: &mut Frame, app: &mut App) {
         lines
     };
 
-    frame.render_widget(
-        Paragraph::new(selected_lines)
-            .block(Block::default().title(" Selection ").borders(Borders::ALL)),
-        left_rows[2],
-    );
+    app.ui_layout.largest_list_area = None;
+    app.ui_layout.largest_rows.clear();
+    if app.show_largest {
+        let (files, dirs) = match (&app.scan_progress, app.is_scanning) {
+            (Some(progress), true) => (&progress.largest_files, &progress.largest_dirs),
+            _ => (&app.largest_files, &app.largest_dirs),
+        };
+        let mut lines = Vec::new();
+        let mut rows = Vec::new();
+        let entry_line = |key: String, entry: &LargestEntry| {
+            Line::from(vec![
+                Span::styled(key, Style::default().fg(Color::Yellow)),
+                Span::styled(
+                    format!("{:>9} ", format_size(entry.size_in(app.size_mode))),
+                    Style::default().fg(Color::Gray),
+                ),
+                Span::raw(entry.path.display().to_string()),
+            ])
+        };
+        for (index, entry) in files.iter().enumerate() {
+            let key = if index < 9 { format!("{} ", index + 1) } else { "  ".to_string() };
+            lines.push(entry_line(key, entry));
+            rows.push(Some(entry.path.clone()));
+        }
+        if files.is_empty() {
+            lines.push(Line::from("No files yet"));
+            rows.push(None);
+        }
+        if !dirs.is_empty() {
+            lines.push(Line::from(Span::styled(
+                "Leaf folders",
+                Style::default().fg(Color::Gray).add_modifier(Modifier::BOLD),
+            )));
+            rows.push(None);
+            for entry in dirs {
+                lines.push(entry_line("  ".to_string(), entry));
+                rows.push(Some(entry.path.clone()));
+            }
+        }
+        let block = Block::default()
+            .title(" Largest files - 1-9/click: jump, L: close ")
+            .borders(Borders::ALL);
+        app.ui_layout.largest_list_area = Some(block.inner(left_rows[2]));
+        app.ui_layout.largest_rows = rows;
+        frame.render_widget(Paragraph::new(lines).block(block), left_rows[2]);
+    } else if app.show_file_types {
+        let mut lines = Vec::new();
+        let mut title = " File types - b: close ".to_string();
+        let tree = app.file_tree.as_ref();
+        if let (Some(tree), Some(folder)) = (tree, app.breakdown_folder()) {
+            let path = &tree.get_arena()[folder].get().path;
+            let breakdown = tree.type_breakdown(folder);
+            let total = breakdown.total().size_in(app.size_mode);
+            title = format!(" File types in {} - b: close ", path.display());
+            let share = |bytes: u64| format!("{:>5.1}% ", percent_of(bytes, total));
+            let categories: Vec<Span> = breakdown
+                .categories_by_size(app.size_mode)
+                .into_iter()
+                .flat_map(|(category, totals)| {
+                    [
+                        Span::styled(format!("{} ", category.label()), Style::default().fg(Color::Yellow)),
+                        Span::raw(share(totals.size_in(app.size_mode))),
+                    ]
+                })
+                .collect();
+            lines.push(Line::from(categories));
+            for (key, totals) in breakdown.extensions_by_size(app.size_mode) {
+                lines.push(Line::from(vec![
+                    Span::raw(share(totals.size_in(app.size_mode))),
+                    Span::styled(
+                        format!("{:>9} ", format_size(totals.size_in(app.size_mode))),
+                        Style::default().fg(Color::Gray),
+                    ),
+                    Span::raw(format!("{} ({} files)", extension_label(key), totals.files)),
+                ]));
+            }
+            if breakdown.is_empty() {
+                lines = vec![Line::from("No files here")];
+            }
+        }
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::default().title(title).borders(Borders::ALL)),
+            left_rows[2],
+        );
+    } else if app.show_owners {
+        app.refresh_owners();
+        let mut lines = Vec::new();
+        let mut title = " Owners - o: highlight, O: close ".to_string();
+        if let (Some((path, usage)), Some(names)) = (&app.owners, &app.owner_names) {
+            let total = usage.total().size_in(app.size_mode);
+            title = format!(" Owners of {} - o: highlight, O: close ", path.display());
+            let heading = |text: &'static str| {
+                Line::from(Span::styled(
+                    text,
+                    Style::default().fg(Color::Gray).add_modifier(Modifier::BOLD),
+                ))
+            };
+            let row = |name: String, totals: UsageTotals, lit: bool| {
+                let name_style = if lit {
+                    Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default()
+                };
+                Line::from(vec![
+                    Span::raw(format!("{:>5.1}% ", percent_of(totals.size_in(app.size_mode), total))),
+                    Span::styled(
+                        format!("{:>9} ", format_size(totals.size_in(app.size_mode))),
+                        Style::default().fg(Color::Gray),
+                    ),
+                    Span::styled(name, name_style),
+                    Span::raw(format!(" ({} files)", totals.files)),
+                ])
+            };
+            lines.push(heading("Users"));
+            for (uid, totals) in usage.users_by_size(app.size_mode) {
+                lines.push(row(names.user(uid), totals, app.highlight_uid == Some(uid)));
+            }
+            lines.push(heading("Groups"));
+            for (gid, totals) in usage.groups_by_size(app.size_mode) {
+                lines.push(row(names.group(gid), totals, false));
+            }
+            if usage.is_empty() {
+                lines = vec![Line::from("No files here")];
+            }
+        }
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::default().title(title).borders(Borders::ALL)),
+            left_rows[2],
+        );
+    } else if app.show_errors {
+        let mut lines: Vec<Line> = app
+            .scan_errors
+            .iter()
+            .map(|error| {
+                Line::from(vec![
+                    Span::styled(format!("{:?} ", error.kind), Style::default().fg(Color::Yellow)),
+                    Span::raw(error.path.display().to_string()),
+                ])
+            })
+            .collect();
+        let hidden = app.scan_error_count.saturating_sub(app.scan_errors.len() as u64);
+        if hidden > 0 {
+            lines.push(Line::from(format!("... and {} more", hidden)));
+        }
+        if lines.is_empty() {
+            lines.push(Line::from("No read errors"));
+        }
+        let title = format!(" Errors ({}) - w: close ", app.scan_error_count);
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::default().title(title).borders(Borders::ALL)),
+            left_rows[2],
+        );
+    } else {
+        frame.render_widget(
+            Paragraph::new(selected_lines)
+                .block(Block::default().title(" Selection ").borders(Borders::ALL)),
+            left_rows[2],
+        );
+    }
 
     let help_lines = vec![
-        Line::from("Enter: scan path    /: edit path"),
+        Line::from("Enter: scan   /: edit path   R: rescan dir"),
         Line::from("Left click: select + expand"),
         Line::from("Right click/z: zoom in   u: up"),
         Line::from("e/d/c: expand/deepen/collapse   q: quit"),
+        Line::from("a: apparent size / disk usage   w: errors"),
+        Line::from("L: largest files   b: file types"),
+        Line::from("O: owners   o: highlight a user"),
+        Line::from("n: cut-off for \"older than N days\""),
+    ];
+    let exclude_text = match &app.exclude_input {
+        Some(input) => format!("add: {}_", input),
+        None if app.scan_options.exclude.rules().is_empty() => "none".to_string(),
+        None => app
+            .scan_options
+            .exclude
+            .rules()
+            .iter()
+            .map(|rule| rule.to_string())
+            .collect::<Vec<_>>()
+            .join(", "),
+    };
+    let option_lines = vec![
+        Line::from(vec![
+            Span::styled("x ", Style::default().fg(Color::Yellow)),
+            Span::raw(format!("one filesystem: {}", on_off(app.scan_options.one_file_system))),
+        ]),
+        Line::from(vec![
+            Span::styled("v ", Style::default().fg(Color::Yellow)),
+            Span::raw(format!("virtual filesystems: {}", app.scan_options.virtual_fs.label())),
+        ]),
+        Line::from(vec![
+            Span::styled("l ", Style::default().fg(Color::Yellow)),
+            Span::raw(format!("symlinks: {}", app.scan_options.symlinks.label())),
+        ]),
+        Line::from(vec![
+            Span::styled("g ", Style::default().fg(Color::Yellow)),
+            Span::raw(format!("ignore files: {}", app.scan_options.ignore_files.label())),
+        ]),
+        Line::from(vec![
+            Span::styled("f ", Style::default().fg(Color::Yellow)),
+            Span::raw(format!("metadata: {}", app.scan_options.metadata.label())),
+        ]),
+        Line::from(vec![
+            Span::styled("p/t ", Style::default().fg(Color::Yellow)),
+            Span::raw(match app.scan_options.throttle.profile() {
+                ScanProfile::Fast => "profile: fast".to_string(),
+                ScanProfile::Gentle => format!("profile: gentle ({})", app.scan_options.throttle.summary()),
+            }),
+        ]),
+        Line::from(vec![
+            Span::styled("i/I ", Style::default().fg(Color::Yellow)),
+            Span::raw(format!("exclude: {}", exclude_text)),
+        ]),
+        Line::from(vec![
+            Span::styled("m ", Style::default().fg(Color::Yellow)),
+            Span::raw(format!(
+                "skip files under: {}",
+                min_size_label(app.scan_options.exclude.min_file_size)
+            )),
+        ]),
+        Line::from(vec![
+            Span::styled("B ", Style::default().fg(Color::Yellow)),
+            Span::raw(format!("folder order: {}", app.scan_options.order.label())),
+        ]),
+        Line::from(vec![
+            Span::styled("D ", Style::default().fg(Color::Yellow)),
+            Span::raw(format!("read timeout: {}", read_timeout_label(app.scan_options.read_timeout))),
+        ]),
+        Line::from(vec![
+            Span::styled("k ", Style::default().fg(Color::Yellow)),
+            Span::raw(format!("remember scans: {}", on_off(app.remember_scans))),
+        ]),
     ];
     frame.render_widget(
-        Paragraph::new(help_lines).block(Block::default().title(" Controls ").borders(Borders::ALL)),
+        Paragraph::new(option_lines)
+            .block(Block::default().title(" Scan options ").borders(Borders::ALL)),
         left_rows[3],
     );
 
+    frame.render_widget(
+        Paragraph::new(help_lines).block(Block::default().title(" Controls ").borders(Borders::ALL)),
+        left_rows[4],
+    );
+
     app.ui_layout.path_input_area = Some(path_inner);
 
-    let treemap_block = Block::default()
-        .title(" Treemap (left click: expand/collapse, right click: zoom) ")
-        .borders(Borders::ALL);
+    let treemap_title = if app.is_scanning {
+        " Treemap - scan so far, first levels only (left click: expand/collapse, right click: zoom) "
+    } else {
+        " Treemap (left click: expand/collapse, right click: zoom) "
+    };
+    let treemap_block = Block::default().title(treemap_title).borders(Borders::ALL);
     let treemap_inner = treemap_block.inner(right);
     frame.render_widget(treemap_block, right);
     app.ui_layout.treemap_inner_area = Some(treemap_inner);
@@ -850,11 +1861,22 @@ fn draw_ui(frame: &mut Frame, app: &mut App) {
     }
 }
 
-fn run_app(terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>) -> io::Result<()> {
+fn run_app(
+    terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>,
+    initial_paths: Vec<PathBuf>,
+) -> io::Result<()> {
     let mut app = App::default();
+    if let Ok(joined) = std::env::join_paths(&initial_paths) {
+        if !initial_paths.is_empty() {
+            app.path_input = joined.to_string_lossy().into_owned();
+            app.input_mode = false;
+            app.start_scan();
+        }
+    }
 
     loop {
         app.poll_scan_updates();
+        app.poll_rescan();
 
         terminal.draw(|frame| {
             draw_ui(frame, &mut app);
@@ -878,6 +1900,8 @@ fn run_app(terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>) -> io::Re
 }
 
 fn main() -> io::Result<()> {
+    let initial_paths: Vec<PathBuf> = std::env::args_os().skip(1).map(PathBuf::from).collect();
+
     enable_raw_mode()?;
     crossterm::execute!(stdout(), EnterAlternateScreen, EnableMouseCapture)?;
 
@@ -885,7 +1909,7 @@ fn main() -> io::Result<()> {
     let mut terminal = Terminal::new(backend)?;
     terminal.clear()?;
 
-    let app_result = run_app(&mut terminal);
+    let app_result = run_app(&mut terminal, initial_paths);
 
     disable_raw_mode()?;
     crossterm::execute!(
@@ -1,23 +1,427 @@
-use dashmap::DashMap;
-use jwalk::WalkDir;
-use rayon::iter::ParallelBridge;
-use rayon::prelude::*;
+use dashmap::{DashMap, DashSet};
+use indextree::NodeId;
+use jwalk::WalkDirGeneric;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
 use std::ffi::OsString;
+use std::fs::Metadata;
+use std::io;
 use std::path::{Path, PathBuf};
-use std::sync::Once;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
-use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+use crate::deadline;
+use crate::entry_meta::{modified_secs, DirStamp, EntryMeta, FileKind, MetadataLevel, Owner};
+use crate::exclude::ExcludeRules;
+use crate::file_types::TypeBreakdown;
+use crate::ignore_files::{IgnoreMode, IgnoreStack};
+use crate::largest::{LargestEntry, TopN};
+use crate::mounts::{MountTable, VirtualFsPolicy};
+use crate::owners::OwnerUsage;
+use crate::throttle::{set_idle_io_priority, RateLimiter, ScanThrottle};
+use crate::tree::{FileTree, Placeholder, SizeMode, TreeNode};
 
 const PREVIEW_TOP_LIMIT: usize = 40;
 const PROGRESS_EMIT_INTERVAL_MS: u64 = 100;
-static INIT_RAYON_POOL: Once = Once::new();
+/// Errors beyond this are only counted, so a scan of `/` as a normal user stays small.
+const MAX_RECORDED_ERRORS: usize = 10_000;
+/// Length of the largest files / largest leaf folders lists.
+const LARGEST_ENTRIES_LIMIT: usize = 20;
+/// An estimated fraction stops short of this, since the estimate may be low.
+const MAX_ESTIMATED_FRACTION: f32 = 0.99;
+/// Throughput over less time than this is too noisy to extrapolate an ETA from.
+const ETA_WARMUP_MS: u64 = 1_000;
+/// Levels below each scanned path that live tree snapshots list entry by entry.
+const LIVE_TREE_DEPTH: usize = 3;
+/// Nodes a live tree holds at most, so cloning it for a snapshot stays cheap.
+const LIVE_TREE_MAX_NODES: usize = 20_000;
+/// Folders `ScanOrder::LargestFirst` lists on their own, one level at a time, before
+/// walking the rest whole; each of those listings runs on a single thread.
+const LARGEST_FIRST_SPLITS: usize = 256;
+/// Under `ScanOrder::LargestFirst`, a folder with at least this fraction of the
+/// previous scan's total is split into its subfolders rather than walked whole.
+const LARGEST_FIRST_SHARE: u64 = 16;
+/// Progress updates carry a live tree snapshot at most this often.
+const LIVE_TREE_INTERVAL_MS: u64 = 500;
 
-#[derive(Debug, Clone)]
+/// Read timeouts frontends offer, in the order they cycle through; see
+/// `ScanOptions::read_timeout`.
+pub const READ_TIMEOUT_CHOICES: [Option<Duration>; 4] = [
+    None,
+    Some(Duration::from_secs(5)),
+    Some(Duration::from_secs(10)),
+    Some(Duration::from_secs(30)),
+];
+
+/// `off`, or the timeout in seconds such as `10s`.
+pub fn read_timeout_label(timeout: Option<Duration>) -> String {
+    match timeout {
+        Some(timeout) => format!("{}s", timeout.as_secs()),
+        None => "off".to_string(),
+    }
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct FileNode {
     pub path: PathBuf,
+    /// Apparent size (logical length, `metadata.len()`)
     pub size: u64,
+    /// Bytes actually allocated on disk
+    pub allocated_size: u64,
     pub is_dir: bool,
+    /// Another hardlink to the same inode was already charged for these bytes
+    pub shared_link: bool,
+    /// Set on directories whose contents were deliberately not scanned
+    pub placeholder: Option<Placeholder>,
+    /// Matched by a `.gitignore`/`.ignore` rule (only set in `IgnoreMode::Tag`)
+    pub ignored: bool,
+    /// Where this entry points, if it is a symbolic link
+    pub symlink_target: Option<PathBuf>,
+    /// Times, owner and mode; only recorded under `MetadataLevel::Full`
+    pub meta: Option<Box<EntryMeta>>,
+    /// Set on sockets, FIFOs and device nodes, which are charged zero bytes
+    pub special: Option<FileKind>,
+    /// Owning user and group, recorded at every metadata level
+    pub owner: Owner,
+    /// Last modification time in Unix seconds, recorded at every metadata level
+    pub modified: i64,
+    /// Set on directories, for reusing their listing in a later scan
+    pub dir_stamp: Option<DirStamp>,
+}
+
+impl FileNode {
+    /// Build a node from already-fetched metadata. Directories and special files
+    /// carry no size of their own.
+    pub fn from_metadata(path: PathBuf, metadata: &Metadata) -> Self {
+        let is_dir = metadata.is_dir();
+        let kind = FileKind::from_file_type(metadata.file_type());
+        let special = kind.is_special().then_some(kind);
+        let (size, allocated_size) = if is_dir || special.is_some() {
+            (0, 0)
+        } else {
+            (metadata.len(), allocated_size(metadata))
+        };
+
+        Self {
+            path,
+            size,
+            allocated_size,
+            is_dir,
+            shared_link: false,
+            placeholder: None,
+            ignored: false,
+            symlink_target: None,
+            meta: None,
+            special,
+            owner: Owner::from_metadata(metadata),
+            modified: modified_secs(metadata),
+            dir_stamp: is_dir.then(|| DirStamp::from_metadata(metadata)),
+        }
+    }
+}
+
+/// Knobs that change which parts of the filesystem a scan visits.
+#[derive(Debug, Clone, Default)]
+pub struct ScanOptions {
+    /// Stop at filesystem boundaries instead of descending into other mounts (`du -x`).
+    pub one_file_system: bool,
+    /// Paths left out of the scan; excluded directories still show up as placeholders.
+    pub exclude: ExcludeRules,
+    /// Whether `.gitignore`/`.ignore` rules skip or tag entries.
+    pub ignore_files: IgnoreMode,
+    pub symlinks: SymlinkPolicy,
+    /// Whether times, owner, mode and inode are kept for every entry.
+    pub metadata: MetadataLevel,
+    /// Thread, rate and I/O priority limits; see `ScanProfile`.
+    pub throttle: ScanThrottle,
+    /// Whether proc, sysfs, devtmpfs and similar mounts are walked (Linux only).
+    pub virtual_fs: VirtualFsPolicy,
+    /// Which folders of each listing are read first.
+    pub order: ScanOrder,
+    /// How long the stat of an entry, or the listing of a folder, on a network
+    /// or FUSE mount may take before it is given up as unresponsive. Local
+    /// filesystems are read without a deadline. `None` waits forever.
+    pub read_timeout: Option<Duration>,
+}
+
+impl ScanOptions {
+    /// The node a scan with these options would record for `path`, which a
+    /// file watcher reported below one of `tree`'s scanned paths. `None` when
+    /// the scan would have left it out: excluded or inside an excluded folder,
+    /// under the size cutoff, or ignored under `IgnoreMode::Skip`. A file with
+    /// several links only updates a path the tree already has, keeping how it
+    /// was charged, since which link a scan charges depends on the walk.
+    pub fn watched_node(&self, tree: &FileTree, path: &Path, metadata: &Metadata) -> Option<FileNode> {
+        let scan_root = tree
+            .scan_root_paths()
+            .into_iter()
+            .filter(|root| path.starts_with(root) && path != *root)
+            .max_by_key(|root| root.as_os_str().len())?;
+        let parent = path.parent()?;
+        let excluded_dir = parent
+            .ancestors()
+            .take_while(|dir| *dir != scan_root)
+            .any(|dir| self.exclude.matching_rule(dir).is_some());
+        if excluded_dir || self.exclude.is_excluded(path) {
+            return None;
+        }
+
+        let mut node = FileNode::from_metadata(path.to_path_buf(), metadata);
+        if !node.is_dir && node.special.is_none() && self.exclude.prunes_size(node.size) {
+            return None;
+        }
+        if self.ignore_files != IgnoreMode::Off {
+            let ignored = IgnoreStack::for_dir(scan_root, parent).is_ignored(path, node.is_dir);
+            if ignored && self.ignore_files == IgnoreMode::Skip {
+                return None;
+            }
+            node.ignored = ignored;
+        }
+        if hardlink_key(metadata).is_some() && node.special.is_none() {
+            let known = tree.get_node(path).and_then(|id| tree.get_arena().get(id))?;
+            node.shared_link = known.get().shared_link;
+        }
+        if self.metadata == MetadataLevel::Full {
+            node.meta = Some(Box::new(EntryMeta::from_metadata(metadata)));
+        }
+        Some(node)
+    }
+}
+
+/// Order in which folders are read, and so reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScanOrder {
+    /// Depth-first, each folder as the filesystem lists it
+    #[default]
+    Listing,
+    /// Biggest expected folders first, across all levels, so the dominant
+    /// subtrees fill the live preview early. The scanned paths are listed
+    /// first, then the folders right below them; after that the folders left
+    /// unread wait in one queue, ranked by their size in the previous scan (see
+    /// `FileCrawler::set_previous_scan`) and then by how many entries the
+    /// first-level folder they sit in holds. Big folders are split into their
+    /// subfolders as they come up, smaller ones walked whole.
+    LargestFirst,
+}
+
+impl ScanOrder {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ScanOrder::Listing => "as listed",
+            ScanOrder::LargestFirst => "largest first",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            ScanOrder::Listing => ScanOrder::LargestFirst,
+            ScanOrder::LargestFirst => ScanOrder::Listing,
+        }
+    }
+}
+
+/// What the crawler does with symbolic links.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SymlinkPolicy {
+    /// Leave links out of the tree
+    Ignore,
+    /// Report the link itself, charged with its own (tiny) size
+    #[default]
+    CountLink,
+    /// Charge the target; directories are descended unless that would loop
+    Follow,
+}
+
+impl SymlinkPolicy {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SymlinkPolicy::Ignore => "ignore",
+            SymlinkPolicy::CountLink => "count link",
+            SymlinkPolicy::Follow => "follow",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            SymlinkPolicy::Ignore => SymlinkPolicy::CountLink,
+            SymlinkPolicy::CountLink => SymlinkPolicy::Follow,
+            SymlinkPolicy::Follow => SymlinkPolicy::Ignore,
+        }
+    }
+}
+
+/// Per-directory state handed down the walk.
+#[derive(Debug, Clone, Default)]
+struct DirState {
+    ignore: IgnoreStack,
+    /// `(st_dev, st_ino)` of every directory from the root down, for symlink loop detection
+    ancestors: Vec<(u64, u64)>,
+    /// On a network or FUSE mount, so reads below it go under `ScanOptions::read_timeout`
+    guarded: bool,
+}
+
+/// Shared flag that stops a running scan. Clones observe the same flag.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Per-entry result of `process_read_dir`, read back when the entry is added to the tree.
+#[derive(Debug, Default)]
+struct EntryState {
+    /// `None` when the entry's metadata could not be read
+    node: Option<FileNode>,
+    /// A directory left unread because the previous scan's copy of it is still current
+    reused: bool,
+    /// Read ahead under `ScanOptions::read_timeout`; `None` without a deadline or past it
+    stat: Option<EntryStat>,
+    /// The entry missed `ScanOptions::read_timeout` and is left unread
+    unresponsive: bool,
+    /// State to walk this folder from later, when its listing's walk stops above it
+    queued_state: Option<DirState>,
+}
+
+/// What an entry's stat returned, read on a deadline helper thread.
+#[derive(Debug)]
+struct EntryStat {
+    metadata: io::Result<Metadata>,
+    /// What a followed symlink points at, when it resolves
+    target: Option<Metadata>,
+}
+
+/// Device id an entry lives on, used to detect filesystem boundaries.
+fn device_id(metadata: &Metadata) -> Option<u64> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        Some(metadata.dev())
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = metadata;
+        None
+    }
+}
+
+/// `(st_dev, st_ino)` identifying an entry, where the platform exposes it.
+fn inode_key(metadata: &Metadata) -> Option<(u64, u64)> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        Some((metadata.dev(), metadata.ino()))
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = metadata;
+        None
+    }
+}
+
+/// `(st_dev, st_ino)` of a file that has more than one hardlink.
+///
+/// Singly-linked files return `None` so they never enter the dedup set.
+fn hardlink_key(metadata: &Metadata) -> Option<(u64, u64)> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        if metadata.is_dir() || metadata.nlink() <= 1 {
+            return None;
+        }
+        Some((metadata.dev(), metadata.ino()))
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = metadata;
+        None
+    }
+}
+
+/// Bytes allocated on disk for an entry (blocks × 512 on Unix).
+///
+/// Other platforms don't expose the allocation size through `std`, so the
+/// apparent size is used there.
+pub fn allocated_size(metadata: &Metadata) -> u64 {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        metadata.blocks().saturating_mul(512)
+    }
+
+    #[cfg(not(unix))]
+    {
+        metadata.len()
+    }
+}
+
+/// An entry the crawler could not read; the bytes behind it are missing from the totals.
+#[derive(Debug, Clone)]
+pub struct ScanError {
+    pub path: PathBuf,
+    pub kind: io::ErrorKind,
+    pub message: String,
+    /// Directory whose listing is incomplete because of this error
+    pub dir: PathBuf,
+}
+
+impl ScanError {
+    fn from_io(path: PathBuf, dir: PathBuf, err: &io::Error) -> Self {
+        Self {
+            path,
+            kind: err.kind(),
+            message: err.to_string(),
+            dir,
+        }
+    }
+
+    /// An entry given up after `timeout`; a directory's whole listing is missing.
+    fn timed_out(path: PathBuf, is_dir: bool, timeout: Duration) -> Self {
+        let dir = if is_dir {
+            path.clone()
+        } else {
+            path.parent().unwrap_or(&path).to_path_buf()
+        };
+        Self {
+            kind: io::ErrorKind::TimedOut,
+            message: format!("no answer within {:?}, left unread", timeout),
+            path,
+            dir,
+        }
+    }
+
+    /// `listing_failed` is set when `path` is a directory that could not be listed.
+    fn from_walk(err: &jwalk::Error, fallback: &Path, listing_failed: bool) -> Self {
+        let path = err.path().unwrap_or(fallback).to_path_buf();
+        let dir = if listing_failed {
+            path.clone()
+        } else {
+            path.parent().unwrap_or(&path).to_path_buf()
+        };
+        Self {
+            kind: err.io_error().map(|e| e.kind()).unwrap_or(io::ErrorKind::Other),
+            message: err.to_string(),
+            path,
+            dir,
+        }
+    }
 }
 
 #[allow(dead_code)]
@@ -26,16 +430,105 @@ pub struct ScanStats {
     pub total_files: u64,
     pub total_dirs: u64,
     pub total_size: u64,
+    pub total_allocated_size: u64,
+    /// Hardlinks whose inode was already charged to another path
+    pub shared_links: u64,
+    /// Bytes behind `shared_links`, not included in `total_size`
+    pub shared_size: u64,
+    pub shared_allocated_size: u64,
+    /// Directories left unscanned and files dropped by an exclude rule
+    pub excluded_entries: u64,
+    /// Files dropped for being under `ExcludeRules::min_file_size`
+    pub pruned_files: u64,
+    pub pruned_size: u64,
+    /// Entries matched by ignore files (skipped or tagged, depending on the mode)
+    pub ignored_entries: u64,
+    /// Charged bytes of tagged ignored files
+    pub ignored_size: u64,
+    pub ignored_allocated_size: u64,
+    /// First `MAX_RECORDED_ERRORS` read failures; see `error_count` for the total
+    pub errors: Vec<ScanError>,
+    pub error_count: u64,
+    /// The scan was stopped through its `CancelToken`; the results are partial
+    pub cancelled: bool,
+    /// Symbolic links seen (including ignored ones)
+    pub symlinks: u64,
+    /// Followed links that pointed back at one of their own ancestors
+    pub symlink_loops: u64,
+    /// Virtual filesystem mounts left as placeholders under `ScanOptions::virtual_fs`
+    pub virtual_mounts: u64,
+    /// Directories abandoned as `Placeholder::Unresponsive` under `ScanOptions::read_timeout`
+    pub unresponsive_dirs: u64,
+    /// Special files, counted apart from `total_files` and charged zero bytes
+    pub sockets: u64,
+    pub fifos: u64,
+    pub block_devices: u64,
+    pub char_devices: u64,
+    /// Biggest files by apparent size, largest first; hardlink duplicates are left out
+    pub largest_files: Vec<LargestEntry>,
+    /// Biggest folders without subfolders, by the bytes of the files directly in them
+    pub largest_dirs: Vec<LargestEntry>,
+    /// Charged files totalled per extension and per category
+    pub file_types: TypeBreakdown,
+    /// Charged files totalled per owning uid and gid
+    pub owners: OwnerUsage,
+    /// Entries walked, the scanned paths included; see `ScanEstimate`
+    pub total_entries: u64,
+    /// Directories copied from the previous scan without being read
+    pub reused_dirs: u64,
+    /// Directories listed from disk
+    pub reread_dirs: u64,
     pub duration_ms: u128,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+impl ScanStats {
+    pub fn total_size_in(&self, mode: SizeMode) -> u64 {
+        match mode {
+            SizeMode::Apparent => self.total_size,
+            SizeMode::Allocated => self.total_allocated_size,
+        }
+    }
+
+    pub fn shared_size_in(&self, mode: SizeMode) -> u64 {
+        match mode {
+            SizeMode::Apparent => self.shared_size,
+            SizeMode::Allocated => self.shared_allocated_size,
+        }
+    }
+
+    pub fn ignored_size_in(&self, mode: SizeMode) -> u64 {
+        match mode {
+            SizeMode::Apparent => self.ignored_size,
+            SizeMode::Allocated => self.ignored_allocated_size,
+        }
+    }
+
+    /// What the next scan of the same paths can expect from this one.
+    pub fn estimate(&self) -> ScanEstimate {
+        ScanEstimate {
+            entries: self.total_entries,
+            duration_ms: self.duration_ms as u64,
+        }
+    }
+}
+
+/// Size of an earlier scan of the same paths, from which progress is estimated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct ScanEstimate {
+    /// Entries that scan walked (`ScanStats::total_entries`)
+    pub entries: u64,
+    /// How long it took; zero when unknown
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum ScanPhase {
+    #[default]
     Discovering,
     Processing,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct ScanProgress {
     pub phase: ScanPhase,
     pub discovered_entries: u64,
@@ -44,54 +537,421 @@ pub struct ScanProgress {
     pub total_files: u64,
     pub total_dirs: u64,
     pub total_size: u64,
+    pub total_allocated_size: u64,
+    pub error_count: u64,
     pub top_level_preview: Vec<ScanTopLevelPreview>,
+    /// `ScanStats::largest_files` so far
+    pub largest_files: Vec<LargestEntry>,
+    /// `ScanStats::largest_dirs` among the folders finished so far
+    pub largest_dirs: Vec<LargestEntry>,
+    pub elapsed_ms: u64,
+    /// Entries walked per second since the scan started
+    pub entries_per_sec: f64,
+    /// Apparent bytes counted per second since the scan started
+    pub bytes_per_sec: f64,
+    /// Expected `total_entries`, from an earlier scan of the same paths
+    pub estimated_entries: Option<u64>,
+    /// Time left, extrapolated from the throughput so far (or, early on, from
+    /// the earlier scan's duration); `None` without an estimate or once past it
+    pub eta: Option<Duration>,
+    /// The tree found so far, with cumulative sizes, for frontends to lay out
+    /// while the scan runs. It lists entries down to `LIVE_TREE_DEPTH` levels
+    /// below each scanned path and holds at most `LIVE_TREE_MAX_NODES` nodes;
+    /// anything else is counted in the size of the deepest folder it does list.
+    /// Only about one update per `LIVE_TREE_INTERVAL_MS` carries one.
+    pub live_tree: Option<Arc<FileTree>>,
 }
 
 #[derive(Debug, Clone)]
 pub struct ScanTopLevelPreview {
     pub name: String,
     pub size: u64,
+    pub allocated_size: u64,
     pub is_dir: bool,
 }
 
+impl ScanTopLevelPreview {
+    pub fn size_in(&self, mode: SizeMode) -> u64 {
+        match mode {
+            SizeMode::Apparent => self.size,
+            SizeMode::Allocated => self.allocated_size,
+        }
+    }
+}
+
+/// State shared by the walks of every path in one scan.
+struct ScanContext {
+    reporter: Option<Arc<dyn Fn(ScanProgress) + Send + Sync>>,
+    preview: DashMap<OsString, PreviewBucket>,
+    /// Inodes with several links; the first path to claim one is charged for it.
+    seen_inodes: Arc<DashSet<(u64, u64)>>,
+    mounts: Arc<MountTable>,
+    /// Mount points `ScanOptions::virtual_fs` leaves unscanned, with their labels
+    skipped_mounts: HashMap<PathBuf, String>,
+    discovered_entries: u64,
+    last_emit_ms: AtomicU64,
+    started: Instant,
+    /// Preview buckets are the scanned paths themselves rather than their children
+    multi_root: bool,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    largest_files: TopN,
+    largest_dirs: TopN,
+    file_types: TypeBreakdown,
+    owners: OwnerUsage,
+    /// Earlier scan of the same paths whose unchanged directories are copied
+    previous: Option<Arc<FileTree>>,
+    /// Directories whose listing matches `previous` but which have changed subfolders
+    unchanged_listings: Arc<DashSet<PathBuf>>,
+    /// Whether everything below a directory of `previous` is unchanged, per directory checked
+    unchanged_subtrees: Arc<DashMap<NodeId, bool>>,
+    /// Set once a check of `previous` misses the read timeout; later listings are read from disk
+    reuse_checks_timed_out: Arc<AtomicBool>,
+    estimate: Option<ScanEstimate>,
+    /// Only kept while someone is listening for progress
+    live: Option<LiveTree>,
+}
+
+/// A directory whose entries are still arriving from the walk.
+struct OpenDir {
+    id: NodeId,
+    /// Node of the live tree its entries go under: its own, or the deepest listed ancestor's
+    live: NodeId,
+    /// Charged bytes of the files directly inside it
+    size: u64,
+    allocated_size: u64,
+    /// Has subfolders, or wasn't scanned at all; either way it is no leaf folder
+    skip_as_leaf: bool,
+}
+
+impl OpenDir {
+    fn new(id: NodeId, live: NodeId, scanned: bool) -> Self {
+        Self {
+            id,
+            live,
+            size: 0,
+            allocated_size: 0,
+            skip_as_leaf: !scanned,
+        }
+    }
+}
+
+/// What the walks below one scanned path share.
+#[derive(Clone)]
+struct RootScope {
+    path: PathBuf,
+    /// Device of the scanned path under `ScanOptions::one_file_system`
+    boundary_dev: Option<u64>,
+    /// `ScanContext::skipped_mounts` below the scanned path, spelled as the walk does
+    skipped_mounts: HashMap<PathBuf, String>,
+    /// Network and FUSE mount points below the scanned path, under a read timeout
+    guarded_mounts: HashSet<PathBuf>,
+    /// The scanned path itself is on one of those mounts
+    guarded: bool,
+}
+
+/// Folder a walk starts from: a scanned path, or one an earlier walk left unread.
+struct WalkStart {
+    id: NodeId,
+    /// Node of the live tree its entries go under
+    live: NodeId,
+    /// Levels below its scanned path
+    depth: usize,
+    path: PathBuf,
+    /// Handed down from its parent; `None` for a scanned path
+    state: Option<DirState>,
+}
+
+impl WalkStart {
+    fn root(id: NodeId, path: &Path) -> Self {
+        Self {
+            id,
+            live: id,
+            depth: 0,
+            path: path.to_path_buf(),
+            state: None,
+        }
+    }
+}
+
+/// Folder waiting in a `FolderQueue`.
+struct QueuedDir {
+    start: WalkStart,
+    /// Index of its `RootScope`
+    scope: usize,
+    /// Entries of the first-level folder it sits in, when that was counted
+    entries: u64,
+    previous_size: u64,
+}
+
+/// Folders left unread by `ScanOrder::LargestFirst`, biggest estimate first and,
+/// among equal ones, in the order they were found.
+#[derive(Default)]
+struct FolderQueue {
+    order: BinaryHeap<((u64, u64), Reverse<usize>)>,
+    dirs: Vec<Option<QueuedDir>>,
+}
+
+impl FolderQueue {
+    fn push(&mut self, dir: QueuedDir) {
+        self.order.push(((dir.previous_size, dir.entries), Reverse(self.dirs.len())));
+        self.dirs.push(Some(dir));
+    }
+
+    fn pop(&mut self) -> Option<QueuedDir> {
+        let (_, Reverse(index)) = self.order.pop()?;
+        self.dirs[index].take()
+    }
+}
+
+/// Bounded copy of the tree a scan is building; see `ScanProgress::live_tree`.
+struct LiveTree {
+    tree: FileTree,
+    nodes: usize,
+    published_ms: Option<u64>,
+}
+
+impl LiveTree {
+    /// Starts from the freshly created tree of the scan, so both share node ids
+    /// for the scanned paths.
+    fn new(tree: FileTree) -> Self {
+        Self {
+            tree,
+            nodes: 0,
+            published_ms: None,
+        }
+    }
+
+    /// Mirror `node`, found `depth` levels below its scanned path, under the live
+    /// node `parent`. Returns the live node that its own entries go under: a new
+    /// one, or `parent` when `node` is too deep or the tree is full, in which
+    /// case its bytes are counted towards `parent`.
+    fn add(&mut self, parent: NodeId, depth: usize, node: &FileNode) -> NodeId {
+        if depth > LIVE_TREE_DEPTH || self.nodes >= LIVE_TREE_MAX_NODES {
+            if !node.is_dir && !node.shared_link {
+                self.tree.add_unlisted_bytes(parent, node.size, node.allocated_size);
+            }
+            return parent;
+        }
+        self.nodes += 1;
+        self.tree.append_file_node(parent, node.clone())
+    }
+
+    /// A copy with cumulative sizes, unless one went out less than
+    /// `LIVE_TREE_INTERVAL_MS` before `elapsed_ms`.
+    fn snapshot(&mut self, elapsed_ms: u64) -> Option<Arc<FileTree>> {
+        if self
+            .published_ms
+            .is_some_and(|published| elapsed_ms.saturating_sub(published) < LIVE_TREE_INTERVAL_MS)
+        {
+            return None;
+        }
+        self.published_ms = Some(elapsed_ms);
+        let mut tree = self.tree.clone();
+        tree.calculate_sizes();
+        Some(Arc::new(tree))
+    }
+}
+
 #[derive(Debug, Clone)]
 struct PreviewBucket {
     size: u64,
+    allocated_size: u64,
     is_dir: bool,
 }
 
 impl ScanProgress {
+    pub fn total_size_in(&self, mode: SizeMode) -> u64 {
+        match mode {
+            SizeMode::Apparent => self.total_size,
+            SizeMode::Allocated => self.total_allocated_size,
+        }
+    }
+
+    /// Share of the scan done: exact once the total is known, estimated
+    /// (and below `MAX_ESTIMATED_FRACTION`) while only `estimated_entries` is.
     pub fn fraction(&self) -> Option<f32> {
         match self.phase {
             ScanPhase::Discovering => None,
-            ScanPhase::Processing => {
-                let total = self.total_entries?;
-                if total == 0 {
-                    Some(1.0)
+            ScanPhase::Processing => match (self.total_entries, self.estimated_entries) {
+                (Some(0), _) => Some(1.0),
+                (Some(total), _) => Some((self.processed_entries as f32 / total as f32).clamp(0.0, 1.0)),
+                (None, Some(estimate)) => {
+                    let fraction = self.processed_entries as f32 / estimate.max(1) as f32;
+                    Some(fraction.clamp(0.0, MAX_ESTIMATED_FRACTION))
+                }
+                (None, None) => None,
+            },
+        }
+    }
+
+    /// Whether `fraction` is a guess based on an earlier scan
+    pub fn is_estimated(&self) -> bool {
+        self.total_entries.is_none() && self.estimated_entries.is_some()
+    }
+
+    /// `eta` as a short label such as `1m 20s left`.
+    pub fn eta_label(&self) -> Option<String> {
+        let secs = self.eta?.as_secs();
+        Some(match secs {
+            0..=59 => format!("{}s left", secs),
+            60..=3599 => format!("{}m {:02}s left", secs / 60, secs % 60),
+            _ => format!("{}h {:02}m left", secs / 3600, (secs % 3600) / 60),
+        })
+    }
+
+    /// Fill in throughput and the ETA from the counts so far.
+    fn with_rates(mut self, elapsed: Duration, estimate: Option<ScanEstimate>) -> Self {
+        let secs = elapsed.as_secs_f64();
+        self.elapsed_ms = elapsed.as_millis() as u64;
+        if secs > 0.0 {
+            self.entries_per_sec = self.processed_entries as f64 / secs;
+            self.bytes_per_sec = self.total_size as f64 / secs;
+        }
+        self.estimated_entries = estimate.map(|estimate| estimate.entries);
+        self.eta = match (self.total_entries, estimate) {
+            (Some(_), _) => Some(Duration::ZERO),
+            (None, Some(estimate)) if self.processed_entries < estimate.entries => {
+                let remaining = (estimate.entries - self.processed_entries) as f64;
+                if self.elapsed_ms >= ETA_WARMUP_MS && self.entries_per_sec > 0.0 {
+                    Some(Duration::from_secs_f64(remaining / self.entries_per_sec))
+                } else if estimate.duration_ms > 0 {
+                    Some(Duration::from_millis(estimate.duration_ms.saturating_sub(self.elapsed_ms)))
                 } else {
-                    Some((self.processed_entries as f32 / total as f32).clamp(0.0, 1.0))
+                    None
                 }
             }
-        }
+            _ => None,
+        };
+        self
     }
 }
 
 /// High-performance parallel file system crawler
+///
+/// Directories are read on a thread pool owned by the crawler, so embedding
+/// the library never touches the host's global rayon pool.
 pub struct FileCrawler {
+    options: ScanOptions,
+    /// `None` only if the pool's threads could not be spawned; the walk then runs serially.
+    pool: Option<Arc<rayon::ThreadPool>>,
+    cancel: CancelToken,
     file_count: Arc<AtomicU64>,
     dir_count: Arc<AtomicU64>,
     total_size: Arc<AtomicU64>,
+    total_allocated_size: Arc<AtomicU64>,
+    shared_links: Arc<AtomicU64>,
+    shared_size: Arc<AtomicU64>,
+    shared_allocated_size: Arc<AtomicU64>,
+    excluded_entries: Arc<AtomicU64>,
+    pruned_files: Arc<AtomicU64>,
+    pruned_size: Arc<AtomicU64>,
+    ignored_entries: Arc<AtomicU64>,
+    ignored_size: Arc<AtomicU64>,
+    ignored_allocated_size: Arc<AtomicU64>,
+    errors: Arc<Mutex<Vec<ScanError>>>,
+    error_count: Arc<AtomicU64>,
+    symlinks: Arc<AtomicU64>,
+    symlink_loops: Arc<AtomicU64>,
+    virtual_mounts: Arc<AtomicU64>,
+    unresponsive_dirs: Arc<AtomicU64>,
+    sockets: Arc<AtomicU64>,
+    fifos: Arc<AtomicU64>,
+    block_devices: Arc<AtomicU64>,
+    char_devices: Arc<AtomicU64>,
+    previous: Option<Arc<FileTree>>,
+    estimate: Option<ScanEstimate>,
+    reused_dirs: Arc<AtomicU64>,
+    reread_dirs: Arc<AtomicU64>,
+    /// Lists folders ahead of the walk under `ScanOptions::read_timeout`
+    dir_lister: DirLister,
+    /// Used instead of the system's mounts when set
+    mount_table: Option<MountTable>,
 }
 
 impl FileCrawler {
     pub fn new() -> Self {
+        Self::with_options(ScanOptions::default())
+    }
+
+    pub fn with_options(options: ScanOptions) -> Self {
+        Self::builder().options(options).build()
+    }
+
+    pub fn builder() -> FileCrawlerBuilder {
+        FileCrawlerBuilder::new()
+    }
+
+    fn from_builder(options: ScanOptions, pool: Option<Arc<rayon::ThreadPool>>) -> Self {
         Self {
+            options,
+            pool,
+            cancel: CancelToken::new(),
             file_count: Arc::new(AtomicU64::new(0)),
             dir_count: Arc::new(AtomicU64::new(0)),
             total_size: Arc::new(AtomicU64::new(0)),
+            total_allocated_size: Arc::new(AtomicU64::new(0)),
+            shared_links: Arc::new(AtomicU64::new(0)),
+            shared_size: Arc::new(AtomicU64::new(0)),
+            shared_allocated_size: Arc::new(AtomicU64::new(0)),
+            excluded_entries: Arc::new(AtomicU64::new(0)),
+            pruned_files: Arc::new(AtomicU64::new(0)),
+            pruned_size: Arc::new(AtomicU64::new(0)),
+            ignored_entries: Arc::new(AtomicU64::new(0)),
+            ignored_size: Arc::new(AtomicU64::new(0)),
+            ignored_allocated_size: Arc::new(AtomicU64::new(0)),
+            errors: Arc::new(Mutex::new(Vec::new())),
+            error_count: Arc::new(AtomicU64::new(0)),
+            symlinks: Arc::new(AtomicU64::new(0)),
+            symlink_loops: Arc::new(AtomicU64::new(0)),
+            virtual_mounts: Arc::new(AtomicU64::new(0)),
+            unresponsive_dirs: Arc::new(AtomicU64::new(0)),
+            sockets: Arc::new(AtomicU64::new(0)),
+            fifos: Arc::new(AtomicU64::new(0)),
+            block_devices: Arc::new(AtomicU64::new(0)),
+            char_devices: Arc::new(AtomicU64::new(0)),
+            previous: None,
+            estimate: None,
+            reused_dirs: Arc::new(AtomicU64::new(0)),
+            reread_dirs: Arc::new(AtomicU64::new(0)),
+            dir_lister: Self::list_dir,
+            mount_table: None,
         }
     }
 
+    /// Number of threads reading directories for this crawler.
+    pub fn threads(&self) -> usize {
+        self.pool.as_ref().map_or(1, |pool| pool.current_num_threads())
+    }
+
+    /// Handle that stops this crawler's scan from another thread.
+    pub fn cancel_token(&self) -> CancelToken {
+        self.cancel.clone()
+    }
+
+    /// Let the next scan reuse `previous`, an earlier scan of the same paths with
+    /// the same options (see `ScanCache`).
+    ///
+    /// A directory whose mtime and ctime, and those of every folder below it,
+    /// still match `previous` is copied from it without being read. One whose own
+    /// times match but that has changed subfolders is listed again, but its files
+    /// keep their recorded sizes instead of being stat'ed. A file rewritten in
+    /// place doesn't touch its folder's times, so its new size only shows once
+    /// something is added, removed or renamed beside it; the same goes for
+    /// in-place edits to ignore files. Entries that never reach the tree
+    /// (excluded or pruned files, skipped ignored entries) are only counted in
+    /// folders that were read, and hardlinks are only deduplicated against
+    /// other read entries.
+    pub fn set_previous_scan(&mut self, previous: FileTree) {
+        self.previous = Some(Arc::new(previous));
+    }
+
+    /// Expect the next scan to be about as big as `estimate`, so its progress
+    /// can show a percentage and an ETA. Without one, the previous scan given
+    /// to `set_previous_scan` (if any) supplies the entry count.
+    pub fn set_estimate(&mut self, estimate: ScanEstimate) {
+        self.estimate = Some(estimate);
+    }
+
     fn emit_progress(
         reporter: &Option<Arc<dyn Fn(ScanProgress) + Send + Sync>>,
         progress: ScanProgress,
@@ -109,17 +969,46 @@ impl FileCrawler {
                 ScanTopLevelPreview {
                     name: entry.key().to_string_lossy().to_string(),
                     size: bucket.size,
+                    allocated_size: bucket.allocated_size,
                     is_dir: bucket.is_dir,
                 }
             })
             .collect();
-        preview_items.sort_by(|a, b| b.size.cmp(&a.size));
+        preview_items.sort_by_key(|item| std::cmp::Reverse(item.size));
         if preview_items.len() > PREVIEW_TOP_LIMIT {
             preview_items.truncate(PREVIEW_TOP_LIMIT);
         }
         preview_items
     }
 
+    /// Counters so far; `total_entries` is only known once the walk is over.
+    fn progress_snapshot(&self, context: &ScanContext, total_entries: Option<u64>) -> ScanProgress {
+        ScanProgress {
+            phase: ScanPhase::Processing,
+            discovered_entries: context.discovered_entries,
+            processed_entries: context.discovered_entries,
+            total_entries,
+            total_files: self.file_count.load(Ordering::Relaxed),
+            total_dirs: self.dir_count.load(Ordering::Relaxed),
+            total_size: self.total_size.load(Ordering::Relaxed),
+            total_allocated_size: self.total_allocated_size.load(Ordering::Relaxed),
+            error_count: self.error_count.load(Ordering::Relaxed),
+            top_level_preview: Self::preview_snapshot(&context.preview),
+            largest_files: context.largest_files.sorted(),
+            largest_dirs: context.largest_dirs.sorted(),
+            ..ScanProgress::default()
+        }
+        .with_rates(context.started.elapsed(), context.estimate)
+    }
+
+    fn record_error(errors: &Mutex<Vec<ScanError>>, error_count: &AtomicU64, error: ScanError) {
+        error_count.fetch_add(1, Ordering::Relaxed);
+        let mut errors = errors.lock().unwrap();
+        if errors.len() < MAX_RECORDED_ERRORS {
+            errors.push(error);
+        }
+    }
+
     fn should_emit_progress(last_emit_ms: &AtomicU64, elapsed_ms: u64) -> bool {
         let previous = last_emit_ms.load(Ordering::Relaxed);
         if elapsed_ms.saturating_sub(previous) < PROGRESS_EMIT_INTERVAL_MS {
@@ -131,22 +1020,6 @@ impl FileCrawler {
             .is_ok()
     }
 
-    fn processing_parallelism() -> usize {
-        let cores = std::thread::available_parallelism()
-            .map(|n| n.get())
-            .unwrap_or(4);
-        (cores * 3).clamp(6, 64)
-    }
-
-    fn ensure_high_parallelism() {
-        let threads = Self::processing_parallelism();
-        INIT_RAYON_POOL.call_once(move || {
-            let _ = rayon::ThreadPoolBuilder::new()
-                .num_threads(threads)
-                .build_global();
-        });
-    }
-
     fn top_level_name(root_path: &Path, path: &Path) -> Option<(OsString, bool)> {
         let rel = path.strip_prefix(root_path).ok()?;
         let mut components = rel.components();
@@ -155,174 +1028,1137 @@ impl FileCrawler {
         Some((first.as_os_str().to_os_string(), has_more))
     }
 
-    /// Scan a directory and build a flat list of all files/directories.
+    /// Scan a directory into a `FileTree` with cumulative sizes already calculated.
     pub fn scan_with_progress<P: AsRef<Path>>(
         &mut self,
         root: P,
         reporter: Option<Arc<dyn Fn(ScanProgress) + Send + Sync>>,
-    ) -> (Vec<FileNode>, ScanStats) {
-        let root_path = root.as_ref().to_path_buf();
-        let reporting_enabled = reporter.is_some();
+    ) -> (FileTree, ScanStats) {
+        self.scan_paths_with_progress(&[root.as_ref()], reporter)
+    }
+
+    /// Scan several paths into one tree; see `FileTree::with_roots` for its shape.
+    ///
+    /// Metadata is read in parallel, one directory listing at a time, inside
+    /// jwalk's `process_read_dir`; the finished batches arrive in walk order
+    /// and are appended straight into the arena. The paths are walked one
+    /// after another and share counters, so hardlinks between them are still
+    /// charged once.
+    pub fn scan_paths_with_progress<P: AsRef<Path>>(
+        &mut self,
+        roots: &[P],
+        reporter: Option<Arc<dyn Fn(ScanProgress) + Send + Sync>>,
+    ) -> (FileTree, ScanStats) {
         let start = Instant::now();
-        let top_level_preview: Arc<DashMap<OsString, PreviewBucket>> = Arc::new(DashMap::new());
 
         // Reset counters
         self.file_count.store(0, Ordering::Relaxed);
         self.dir_count.store(0, Ordering::Relaxed);
         self.total_size.store(0, Ordering::Relaxed);
+        self.total_allocated_size.store(0, Ordering::Relaxed);
+        self.shared_links.store(0, Ordering::Relaxed);
+        self.shared_size.store(0, Ordering::Relaxed);
+        self.shared_allocated_size.store(0, Ordering::Relaxed);
+        self.excluded_entries.store(0, Ordering::Relaxed);
+        self.pruned_files.store(0, Ordering::Relaxed);
+        self.pruned_size.store(0, Ordering::Relaxed);
+        self.ignored_entries.store(0, Ordering::Relaxed);
+        self.ignored_size.store(0, Ordering::Relaxed);
+        self.ignored_allocated_size.store(0, Ordering::Relaxed);
+        self.error_count.store(0, Ordering::Relaxed);
+        self.symlinks.store(0, Ordering::Relaxed);
+        self.symlink_loops.store(0, Ordering::Relaxed);
+        self.virtual_mounts.store(0, Ordering::Relaxed);
+        self.unresponsive_dirs.store(0, Ordering::Relaxed);
+        self.sockets.store(0, Ordering::Relaxed);
+        self.fifos.store(0, Ordering::Relaxed);
+        self.block_devices.store(0, Ordering::Relaxed);
+        self.char_devices.store(0, Ordering::Relaxed);
+        self.reused_dirs.store(0, Ordering::Relaxed);
+        self.reread_dirs.store(0, Ordering::Relaxed);
+        self.errors.lock().unwrap().clear();
 
-        Self::ensure_high_parallelism();
+        let mounts = if self.options.one_file_system
+            || self.options.virtual_fs != VirtualFsPolicy::Scan
+            || self.options.read_timeout.is_some()
+        {
+            self.mount_table.clone().unwrap_or_else(MountTable::load)
+        } else {
+            MountTable::default()
+        };
+        let skipped_mounts = mounts.skipped_mounts(self.options.virtual_fs);
 
-        let walker = WalkDir::new(root.as_ref())
-            .skip_hidden(false)
-            .parallelism(jwalk::Parallelism::RayonDefaultPool {
-                busy_timeout: std::time::Duration::from_secs(1),
-            })
-            .process_read_dir(|_, _, _, children| {
-                children.retain(|entry| {
-                    entry
-                        .as_ref()
-                        .map(|dir_entry| !Self::should_skip_path(&dir_entry.path()))
-                        .unwrap_or(true)
-                });
-            })
-            .into_iter();
+        let mut tree = FileTree::with_roots(roots);
+        let previous = self.previous.take();
+        let estimate = self.estimate.take().or_else(|| {
+            let previous = previous.as_deref()?;
+            let arena = previous.get_arena();
+            let entries = previous
+                .scan_root_ids()
+                .iter()
+                .map(|id| id.descendants(arena).count() as u64)
+                .sum();
+            Some(ScanEstimate { entries, duration_ms: 0 })
+        });
+        let live = reporter.is_some().then(|| LiveTree::new(tree.clone()));
+        let mut context = ScanContext {
+            reporter,
+            preview: DashMap::new(),
+            seen_inodes: Arc::new(DashSet::new()),
+            mounts: Arc::new(mounts),
+            skipped_mounts,
+            discovered_entries: 0,
+            last_emit_ms: AtomicU64::new(0),
+            started: Instant::now(),
+            multi_root: tree.is_multi_root(),
+            rate_limiter: self
+                .options
+                .throttle
+                .max_entries_per_sec
+                .map(|rate| Arc::new(RateLimiter::new(rate))),
+            largest_files: TopN::new(LARGEST_ENTRIES_LIMIT),
+            largest_dirs: TopN::new(LARGEST_ENTRIES_LIMIT),
+            file_types: TypeBreakdown::default(),
+            owners: OwnerUsage::default(),
+            previous,
+            unchanged_listings: Arc::new(DashSet::new()),
+            unchanged_subtrees: Arc::new(DashMap::new()),
+            reuse_checks_timed_out: Arc::new(AtomicBool::new(false)),
+            estimate,
+            live,
+        };
 
         Self::emit_progress(
-            &reporter,
+            &context.reporter,
             ScanProgress {
                 phase: ScanPhase::Processing,
-                discovered_entries: 0,
-                processed_entries: 0,
-                total_entries: None,
-                total_files: 0,
-                total_dirs: 0,
-                total_size: 0,
-                top_level_preview: Vec::new(),
-            },
+                ..ScanProgress::default()
+            }
+            .with_rates(Duration::ZERO, context.estimate),
         );
 
-        let discovered_entries = Arc::new(AtomicU64::new(0));
-        let processing_last_emit_ms = Arc::new(AtomicU64::new(0));
-        let processing_started = Instant::now();
-        let reporter_parallel = reporter.clone();
-        let discovered_parallel = discovered_entries.clone();
-        let file_count = self.file_count.clone();
-        let dir_count = self.dir_count.clone();
-        let total_size = self.total_size.clone();
-        let preview_map = top_level_preview.clone();
-        let root_for_workers = root_path.clone();
-
-        let nodes = walker
-            .par_bridge()
-            .filter_map(|entry| {
-                let entry = entry.ok()?;
-                let discovered_now = discovered_parallel.fetch_add(1, Ordering::Relaxed) + 1;
-
-                let path = entry.path();
-                let metadata = entry.metadata().ok()?;
-
-                let size = metadata.len();
-                let is_dir = metadata.is_dir();
-
-                if is_dir {
-                    dir_count.fetch_add(1, Ordering::Relaxed);
-                } else {
-                    file_count.fetch_add(1, Ordering::Relaxed);
-                    total_size.fetch_add(size, Ordering::Relaxed);
-                }
-
-                if reporting_enabled {
-                    if let Some((name, nested)) = Self::top_level_name(&root_for_workers, &path) {
-                        let bucket_is_dir = nested || is_dir;
-                        let mut preview = preview_map
-                            .entry(name.clone())
-                            .or_insert_with(|| PreviewBucket {
-                                size: 0,
-                                is_dir: bucket_is_dir,
-                            });
-
-                        if bucket_is_dir {
-                            preview.is_dir = true;
-                        }
-
-                        if !is_dir {
-                            preview.size = preview.size.saturating_add(size.max(1));
-                        }
-                    }
-
-                    if let Some(cb) = reporter_parallel.as_ref() {
-                        let elapsed_ms = processing_started.elapsed().as_millis() as u64;
-                        if Self::should_emit_progress(&processing_last_emit_ms, elapsed_ms) {
-                            cb(ScanProgress {
-                                phase: ScanPhase::Processing,
-                                discovered_entries: discovered_now,
-                                processed_entries: discovered_now,
-                                total_entries: None,
-                                total_files: file_count.load(Ordering::Relaxed),
-                                total_dirs: dir_count.load(Ordering::Relaxed),
-                                total_size: total_size.load(Ordering::Relaxed),
-                                top_level_preview: Self::preview_snapshot(&preview_map),
-                            });
-                        }
-                    }
+        let scan_roots: Vec<(NodeId, PathBuf)> = tree
+            .scan_root_ids()
+            .iter()
+            .copied()
+            .zip(tree.scan_root_paths().into_iter().map(Path::to_path_buf))
+            .collect();
+        match self.options.order {
+            ScanOrder::Listing => {
+                for (root_id, root_path) in scan_roots {
+                    self.walk_root(&mut tree, root_id, &root_path, &mut context);
                 }
+            }
+            ScanOrder::LargestFirst => self.walk_largest_first(&mut tree, scan_roots, &mut context),
+        }
 
-                Some(FileNode {
-                    path: path.to_path_buf(),
-                    size,
-                    is_dir,
-                })
-            })
-            .collect::<Vec<_>>();
+        let total_entries = context.discovered_entries;
 
-        let total_entries = discovered_entries.load(Ordering::Relaxed);
+        Self::emit_progress(&context.reporter, self.progress_snapshot(&context, Some(total_entries)));
 
-        Self::emit_progress(
-            &reporter,
-            ScanProgress {
-                phase: ScanPhase::Processing,
-                discovered_entries: total_entries,
-                processed_entries: total_entries,
-                total_entries: Some(total_entries),
-                total_files: self.file_count.load(Ordering::Relaxed),
-                total_dirs: self.dir_count.load(Ordering::Relaxed),
-                total_size: self.total_size.load(Ordering::Relaxed),
-                top_level_preview: Self::preview_snapshot(&top_level_preview),
-            },
-        );
+        let cancelled = self.cancel.is_cancelled();
+        tree.set_incomplete(cancelled);
+        tree.calculate_sizes();
 
         let duration = start.elapsed();
+        let mut errors = std::mem::take(&mut *self.errors.lock().unwrap());
+        errors.sort_by(|a, b| a.path.cmp(&b.path));
 
         let stats = ScanStats {
             total_files: self.file_count.load(Ordering::Relaxed),
             total_dirs: self.dir_count.load(Ordering::Relaxed),
             total_size: self.total_size.load(Ordering::Relaxed),
+            total_allocated_size: self.total_allocated_size.load(Ordering::Relaxed),
+            shared_links: self.shared_links.load(Ordering::Relaxed),
+            shared_size: self.shared_size.load(Ordering::Relaxed),
+            shared_allocated_size: self.shared_allocated_size.load(Ordering::Relaxed),
+            excluded_entries: self.excluded_entries.load(Ordering::Relaxed),
+            pruned_files: self.pruned_files.load(Ordering::Relaxed),
+            pruned_size: self.pruned_size.load(Ordering::Relaxed),
+            ignored_entries: self.ignored_entries.load(Ordering::Relaxed),
+            ignored_size: self.ignored_size.load(Ordering::Relaxed),
+            ignored_allocated_size: self.ignored_allocated_size.load(Ordering::Relaxed),
+            errors,
+            error_count: self.error_count.load(Ordering::Relaxed),
+            cancelled,
+            symlinks: self.symlinks.load(Ordering::Relaxed),
+            symlink_loops: self.symlink_loops.load(Ordering::Relaxed),
+            virtual_mounts: self.virtual_mounts.load(Ordering::Relaxed),
+            unresponsive_dirs: self.unresponsive_dirs.load(Ordering::Relaxed),
+            sockets: self.sockets.load(Ordering::Relaxed),
+            fifos: self.fifos.load(Ordering::Relaxed),
+            block_devices: self.block_devices.load(Ordering::Relaxed),
+            char_devices: self.char_devices.load(Ordering::Relaxed),
+            largest_files: context.largest_files.sorted(),
+            largest_dirs: context.largest_dirs.sorted(),
+            file_types: context.file_types,
+            owners: context.owners,
+            total_entries,
+            reused_dirs: self.reused_dirs.load(Ordering::Relaxed),
+            reread_dirs: self.reread_dirs.load(Ordering::Relaxed),
             duration_ms: duration.as_millis(),
         };
 
-        (nodes, stats)
+        (tree, stats)
     }
 
-    fn should_skip_path(path: &Path) -> bool {
-        let mut matched = 0usize;
-        const DOCKER_VM_PATH: [&str; 5] = ["Library", "Containers", "com.docker.docker", "Data", "vms"];
-
-        for component in path.components() {
-            let Some(part) = component.as_os_str().to_str() else {
-                continue;
-            };
+    /// Walk one scanned path, appending everything below it under `root_id`.
+    fn walk_root(&self, tree: &mut FileTree, root_id: NodeId, root_path: &Path, context: &mut ScanContext) {
+        if let Some(scope) = self.root_scope(tree, root_id, root_path, context) {
+            self.walk_dir(tree, &scope, WalkStart::root(root_id, root_path), false, context);
+        }
+    }
 
-            if part == DOCKER_VM_PATH[matched] {
-                matched += 1;
-                if matched == DOCKER_VM_PATH.len() {
-                    return true;
-                }
+    /// Walk the scanned paths biggest folder first, for `ScanOrder::LargestFirst`.
+    ///
+    /// Each scanned path is listed, then each folder right below it that the
+    /// previous scan doesn't know, which counts its entries. The folders left
+    /// unread go in a `FolderQueue`; the biggest is taken next, and split into
+    /// its own listing and queued subfolders while it holds at least a
+    /// `LARGEST_FIRST_SHARE`th of the previous total, or else walked whole.
+    fn walk_largest_first(&self, tree: &mut FileTree, scan_roots: Vec<(NodeId, PathBuf)>, context: &mut ScanContext) {
+        let mut scopes = Vec::new();
+        let mut first_level = Vec::new();
+        for (root_id, root_path) in scan_roots {
+            if let Some(scope) = self.root_scope(tree, root_id, &root_path, context) {
+                let unread = self.walk_dir(tree, &scope, WalkStart::root(root_id, &root_path), true, context);
+                first_level.extend(unread.into_iter().map(|start| (scopes.len(), start)));
+                scopes.push(scope);
             }
         }
+        let previous = context.previous.clone();
+        let previous_size = |path: &Path| previous.as_deref().map_or(0, |previous| Self::previous_size(previous, path));
+        let previous_total: u64 = scopes.iter().map(|scope| previous_size(&scope.path)).sum();
 
-        false
-    }
+        let mut queue = FolderQueue::default();
+        let mut splits = 0;
+        for (scope, start) in first_level {
+            let size = previous_size(&start.path);
+            if size > 0 || splits >= LARGEST_FIRST_SPLITS || self.cancel.is_cancelled() {
+                queue.push(QueuedDir {
+                    start,
+                    scope,
+                    entries: 0,
+                    previous_size: size,
+                });
+                continue;
+            }
+            splits += 1;
+            let listed = context.discovered_entries;
+            let unread = self.walk_dir(tree, &scopes[scope], start, true, context);
+            let entries = context.discovered_entries - listed;
+            for start in unread {
+                let previous_size = previous_size(&start.path);
+                queue.push(QueuedDir {
+                    start,
+                    scope,
+                    entries,
+                    previous_size,
+                });
+            }
+        }
+
+        while let Some(dir) = queue.pop() {
+            if self.cancel.is_cancelled() {
+                break;
+            }
+            let split = splits < LARGEST_FIRST_SPLITS
+                && dir.previous_size > 0
+                && dir.previous_size >= previous_total / LARGEST_FIRST_SHARE;
+            splits += usize::from(split);
+            let (scope, entries) = (dir.scope, dir.entries);
+            for start in self.walk_dir(tree, &scopes[scope], dir.start, split, context) {
+                let previous_size = previous_size(&start.path);
+                queue.push(QueuedDir {
+                    start,
+                    scope,
+                    entries,
+                    previous_size,
+                });
+            }
+        }
+    }
+
+    /// What the walks below `root_path` share. `None` when the scanned path
+    /// itself missed the read timeout and was left unread.
+    fn root_scope(
+        &self,
+        tree: &mut FileTree,
+        root_id: NodeId,
+        root_path: &Path,
+        context: &ScanContext,
+    ) -> Option<RootScope> {
+        // With `one_file_system`, directories on any other device become mount placeholders.
+        let boundary_dev = if self.options.one_file_system {
+            std::fs::metadata(root_path).ok().and_then(|m| device_id(&m))
+        } else {
+            None
+        };
+
+        let mounts = &context.mounts;
+        let read_timeout = self.options.read_timeout;
+        let on_remote = |path: &Path| mounts.mount_containing(path).is_some_and(|mount| mount.is_remote());
+        // Reads only get a deadline on network and FUSE mounts, starting with the scanned
+        // path itself: resolving it already waits on the server.
+        let lexical_root = std::path::absolute(root_path).unwrap_or_else(|_| root_path.to_path_buf());
+        let canonical_root = match read_timeout.filter(|_| on_remote(&lexical_root)) {
+            Some(timeout) => {
+                let path = root_path.to_path_buf();
+                let resolved = deadline::run_with_deadline(timeout, move || {
+                    let canonical = std::fs::canonicalize(&path).unwrap_or(path);
+                    let _ = std::fs::symlink_metadata(&canonical);
+                    canonical
+                });
+                let Some(canonical) = resolved else {
+                    self.give_up_root(tree, root_id, root_path, timeout);
+                    return None;
+                };
+                canonical
+            }
+            None => std::fs::canonicalize(root_path).unwrap_or_else(|_| root_path.to_path_buf()),
+        };
+        let root_guarded = read_timeout.is_some() && (on_remote(&lexical_root) || on_remote(&canonical_root));
+        // Mount points are canonical; match them against paths as this walk spells them.
+        // A virtual filesystem that is itself the scanned path is still walked, as asked.
+        let below_root = |mount_point: &PathBuf| {
+            let rel = mount_point.strip_prefix(&canonical_root).ok()?;
+            (!rel.as_os_str().is_empty()).then(|| root_path.join(rel))
+        };
+        let skipped_mounts: HashMap<PathBuf, String> = context
+            .skipped_mounts
+            .iter()
+            .filter_map(|(mount_point, label)| Some((below_root(mount_point)?, label.clone())))
+            .collect();
+        let guarded_mounts: HashSet<PathBuf> = match read_timeout {
+            Some(_) => mounts.remote_mounts().iter().filter_map(below_root).collect(),
+            None => HashSet::new(),
+        };
+        Some(RootScope {
+            path: root_path.to_path_buf(),
+            boundary_dev,
+            skipped_mounts,
+            guarded_mounts,
+            guarded: root_guarded,
+        })
+    }
+
+    /// Walk `start`, below the scanned path of `scope`, appending what it holds
+    /// under `start.id`. With `expand` only its own listing is read, and the
+    /// subfolders the walk would have gone on to are returned unread instead.
+    fn walk_dir(
+        &self,
+        tree: &mut FileTree,
+        scope: &RootScope,
+        start: WalkStart,
+        expand: bool,
+        context: &mut ScanContext,
+    ) -> Vec<WalkStart> {
+        let reporting_enabled = context.reporter.is_some();
+        let RootScope {
+            path: scan_root,
+            boundary_dev,
+            skipped_mounts,
+            guarded_mounts,
+            guarded: root_guarded,
+        } = scope.clone();
+        let WalkStart {
+            id: start_id,
+            live: start_live,
+            depth: start_depth,
+            path: start_path,
+            state,
+        } = start;
+        // A folder queued by an earlier walk was already read as an entry of its parent.
+        let resumed = state.is_some().then(|| Arc::<Path>::from(start_path.as_path()));
+        let resuming = resumed.is_some();
+        let mounts = context.mounts.clone();
+        let read_timeout = self.options.read_timeout;
+        let dir_lister = self.dir_lister;
+        let unresponsive_dirs = self.unresponsive_dirs.clone();
+        let reuse_checks_timed_out = context.reuse_checks_timed_out.clone();
+        let virtual_mounts = self.virtual_mounts.clone();
+        let special_counts = [
+            (FileKind::Socket, self.sockets.clone()),
+            (FileKind::Fifo, self.fifos.clone()),
+            (FileKind::BlockDevice, self.block_devices.clone()),
+            (FileKind::CharDevice, self.char_devices.clone()),
+        ];
+        let exclude = self.options.exclude.clone();
+        let excluded_entries = self.excluded_entries.clone();
+        let pruned_files = self.pruned_files.clone();
+        let pruned_size = self.pruned_size.clone();
+        let ignore_mode = self.options.ignore_files;
+        let ignored_entries = self.ignored_entries.clone();
+        let cancel = self.cancel.clone();
+        let symlink_policy = self.options.symlinks;
+        let scan_order = self.options.order;
+        let symlinks = self.symlinks.clone();
+        let symlink_loops = self.symlink_loops.clone();
+        let metadata_level = self.options.metadata;
+        let file_count = self.file_count.clone();
+        let dir_count = self.dir_count.clone();
+        let total_size = self.total_size.clone();
+        let total_allocated_size = self.total_allocated_size.clone();
+        let shared_links = self.shared_links.clone();
+        let shared_size = self.shared_size.clone();
+        let shared_allocated_size = self.shared_allocated_size.clone();
+        let ignored_size = self.ignored_size.clone();
+        let ignored_allocated_size = self.ignored_allocated_size.clone();
+        let errors = self.errors.clone();
+        let error_count = self.error_count.clone();
+        let seen_inodes = context.seen_inodes.clone();
+        let rate_limiter = context.rate_limiter.clone();
+        let previous = context.previous.clone();
+        let unchanged_listings = context.unchanged_listings.clone();
+        let unchanged_subtrees = context.unchanged_subtrees.clone();
+        let reread_dirs = self.reread_dirs.clone();
+        let parallelism = match &self.pool {
+            // Nothing else runs on the crawler's own pool, so jwalk's busy check isn't needed.
+            Some(pool) => jwalk::Parallelism::RayonExistingPool {
+                pool: pool.clone(),
+                busy_timeout: None,
+            },
+            None => jwalk::Parallelism::Serial,
+        };
+
+        let mut walker = WalkDirGeneric::<(DirState, EntryState)>::new(&start_path)
+            .skip_hidden(false)
+            .parallelism(parallelism)
+            .root_read_dir_state(state.unwrap_or_default());
+        if expand {
+            walker = walker.max_depth(1);
+        }
+
+        // Every surviving child leaves `process_read_dir` with its finished `FileNode`.
+        let walker = walker
+            .process_read_dir(move |depth, dir_path, dir_state, children| {
+                if let (None, Some(path)) = (depth, &resumed) {
+                    for dir_entry in children.iter_mut().flatten() {
+                        dir_entry.read_children_path = Some(path.clone());
+                    }
+                    return;
+                }
+                if let Some(limiter) = &rate_limiter {
+                    limiter.throttle(children.len() as u64, || cancel.is_cancelled());
+                }
+                if ignore_mode != IgnoreMode::Off {
+                    match depth {
+                        None => dir_state.ignore = IgnoreStack::for_scan_root(&scan_root),
+                        Some(_) => dir_state.ignore.enter(
+                            dir_path,
+                            children
+                                .iter()
+                                .flatten()
+                                .filter_map(|entry| entry.file_name.to_str()),
+                        ),
+                    }
+                }
+                match depth {
+                    None => dir_state.guarded = root_guarded,
+                    Some(_) => dir_state.guarded |= guarded_mounts.contains(dir_path),
+                }
+                let guarded = |path: &Path| dir_state.guarded || guarded_mounts.contains(path);
+                if symlink_policy == SymlinkPolicy::Follow && depth.is_some() {
+                    if let Some(key) = std::fs::metadata(dir_path).ok().as_ref().and_then(inode_key) {
+                        dir_state.ancestors.push(key);
+                    }
+                }
+                if depth.is_some() {
+                    reread_dirs.fetch_add(1, Ordering::Relaxed);
+                }
+                // Files in a listing the previous scan already saw keep their recorded metadata.
+                let listing = previous
+                    .as_deref()
+                    .filter(|_| depth.is_some() && unchanged_listings.remove(dir_path).is_some());
+                let cached_file = |path: &Path, file_type: std::fs::FileType| {
+                    let previous = listing?;
+                    if file_type.is_dir() || file_type.is_symlink() {
+                        return None;
+                    }
+                    let data = previous.get_arena().get(previous.get_node(path)?)?.get();
+                    (!data.is_dir).then_some(data)
+                };
+
+                // Under a read timeout, entries on network and FUSE mounts are stat'ed ahead on
+                // a helper thread that can be abandoned, so a hung mount costs a timeout per
+                // entry instead of the whole scan.
+                if let Some(timeout) = read_timeout {
+                    let mut indexes = Vec::new();
+                    let mut pending = Vec::new();
+                    for (index, entry) in children.iter().enumerate() {
+                        let Ok(dir_entry) = entry else {
+                            continue;
+                        };
+                        let path = dir_entry.path();
+                        if !guarded(&path) || cached_file(&path, dir_entry.file_type).is_some() {
+                            continue;
+                        }
+                        let follow = symlink_policy == SymlinkPolicy::Follow && dir_entry.file_type.is_symlink();
+                        indexes.push(index);
+                        pending.push((path, follow));
+                    }
+                    let stats = deadline::map_with_deadline(pending, timeout, Self::stat_entry);
+                    for (index, stat) in indexes.into_iter().zip(stats) {
+                        if let Some(Ok(dir_entry)) = children.get_mut(index) {
+                            dir_entry.client_state.unresponsive = stat.is_none();
+                            dir_entry.client_state.stat = stat;
+                        }
+                    }
+                }
+
+                children.retain(|entry| {
+                    let Ok(dir_entry) = entry else {
+                        return true;
+                    };
+                    if dir_entry.file_type.is_symlink() {
+                        symlinks.fetch_add(1, Ordering::Relaxed);
+                        if symlink_policy == SymlinkPolicy::Ignore {
+                            return false;
+                        }
+                    }
+                    if ignore_mode == IgnoreMode::Skip
+                        && dir_state.ignore.is_ignored(&dir_entry.path(), dir_entry.file_type.is_dir())
+                    {
+                        ignored_entries.fetch_add(1, Ordering::Relaxed);
+                        return false;
+                    }
+                    if dir_entry.file_type.is_dir() {
+                        return true;
+                    }
+
+                    if exclude.is_excluded(&dir_entry.path()) {
+                        excluded_entries.fetch_add(1, Ordering::Relaxed);
+                        return false;
+                    }
+                    // Special files are never sized, so the small-file cutoff doesn't apply.
+                    if exclude.min_file_size > 0
+                        && !dir_entry.client_state.unresponsive
+                        && !FileKind::from_file_type(dir_entry.file_type).is_special()
+                    {
+                        let cached = cached_file(&dir_entry.path(), dir_entry.file_type);
+                        let size = match (cached, &dir_entry.client_state.stat) {
+                            (Some(data), _) => Some(data.size),
+                            (None, Some(stat)) => stat.metadata.as_ref().ok().map(|metadata| metadata.len()),
+                            (None, None) => dir_entry.metadata().ok().map(|metadata| metadata.len()),
+                        };
+                        if let Some(size) = size.filter(|size| exclude.prunes_size(*size)) {
+                            pruned_files.fetch_add(1, Ordering::Relaxed);
+                            pruned_size.fetch_add(size, Ordering::Relaxed);
+                            return false;
+                        }
+                    }
+                    true
+                });
+
+                let count_node = |node: &FileNode| {
+                    if node.is_dir {
+                        dir_count.fetch_add(1, Ordering::Relaxed);
+                    } else if let Some(kind) = node.special {
+                        if let Some((_, counter)) = special_counts.iter().find(|(k, _)| *k == kind) {
+                            counter.fetch_add(1, Ordering::Relaxed);
+                        }
+                    } else if node.shared_link {
+                        file_count.fetch_add(1, Ordering::Relaxed);
+                        shared_links.fetch_add(1, Ordering::Relaxed);
+                        shared_size.fetch_add(node.size, Ordering::Relaxed);
+                        shared_allocated_size.fetch_add(node.allocated_size, Ordering::Relaxed);
+                    } else {
+                        file_count.fetch_add(1, Ordering::Relaxed);
+                        total_size.fetch_add(node.size, Ordering::Relaxed);
+                        total_allocated_size.fetch_add(node.allocated_size, Ordering::Relaxed);
+                        if node.ignored {
+                            ignored_size.fetch_add(node.size, Ordering::Relaxed);
+                            ignored_allocated_size.fetch_add(node.allocated_size, Ordering::Relaxed);
+                        }
+                    }
+                };
+
+                for dir_entry in children.iter_mut().flatten() {
+                    if let (true, Some(timeout)) = (dir_entry.client_state.unresponsive, read_timeout) {
+                        Self::give_up_unresponsive(dir_entry, timeout, &errors, &error_count, &unresponsive_dirs);
+                        if let Some(node) = &dir_entry.client_state.node {
+                            count_node(node);
+                        }
+                        continue;
+                    }
+                    let path = dir_entry.path();
+                    let mut is_dir = dir_entry.file_type.is_dir();
+                    let mut placeholder = None;
+                    let mut followed = None;
+                    if symlink_policy == SymlinkPolicy::Follow && dir_entry.file_type.is_symlink() {
+                        let target = match dir_entry.client_state.stat.as_mut() {
+                            Some(stat) => stat.target.take(),
+                            None => std::fs::metadata(&path).ok(),
+                        };
+                        // Dangling links keep being reported as the link itself.
+                        if let Some(target) = target {
+                            if target.is_dir() {
+                                match inode_key(&target) {
+                                    Some(key) if dir_state.ancestors.contains(&key) => {
+                                        symlink_loops.fetch_add(1, Ordering::Relaxed);
+                                        placeholder = Some(Placeholder::SymlinkLoop);
+                                    }
+                                    // Without inode numbers a loop can't be ruled out.
+                                    None => {}
+                                    Some(_) => {
+                                        is_dir = true;
+                                        dir_entry.read_children_path = Some(Arc::from(path.as_path()));
+                                    }
+                                }
+                            }
+                            followed = Some(target);
+                        }
+                    }
+                    let ignored = ignore_mode == IgnoreMode::Tag
+                        && dir_state.ignore.is_ignored(&path, is_dir);
+                    if ignored {
+                        ignored_entries.fetch_add(1, Ordering::Relaxed);
+                    }
+                    if let Some(mut node) = cached_file(&path, dir_entry.file_type).map(TreeNode::to_file_node) {
+                        node.ignored = ignored;
+                        count_node(&node);
+                        dir_entry.client_state.node = Some(node);
+                        continue;
+                    }
+
+                    let metadata = match (followed, dir_entry.client_state.stat.take()) {
+                        (Some(target), _) => Ok(target),
+                        (None, Some(stat)) => stat.metadata.map_err(|err| {
+                            let dir = path.parent().unwrap_or(&path).to_path_buf();
+                            ScanError::from_io(path.clone(), dir, &err)
+                        }),
+                        (None, None) => dir_entry.metadata().map_err(|err| {
+                            let dir = path.parent().unwrap_or(&path).to_path_buf();
+                            match err.io_error() {
+                                Some(io_err) => ScanError::from_io(path.clone(), dir, io_err),
+                                None => ScanError::from_walk(&err, &path, false),
+                            }
+                        }),
+                    };
+                    if is_dir {
+                        if let Some(rule) = exclude.matching_rule(&path) {
+                            excluded_entries.fetch_add(1, Ordering::Relaxed);
+                            placeholder = Some(Placeholder::Excluded(rule.to_string()));
+                            dir_entry.read_children_path = None;
+                        } else if let Some(label) = skipped_mounts.get(&path) {
+                            virtual_mounts.fetch_add(1, Ordering::Relaxed);
+                            placeholder = Some(Placeholder::VirtualFs(label.clone()));
+                            dir_entry.read_children_path = None;
+                        } else if let Some(root_dev) = boundary_dev {
+                            if Self::crosses_filesystem(metadata.as_ref().ok(), root_dev) {
+                                let label = mounts
+                                    .mount_at(&path)
+                                    .map(|mount| mount.label())
+                                    .unwrap_or_else(|| "other filesystem".to_string());
+                                placeholder = Some(Placeholder::MountPoint(label));
+                                dir_entry.read_children_path = None;
+                            }
+                        }
+                    }
+
+                    let mut node = match metadata {
+                        Ok(metadata) => {
+                            let mut node = FileNode::from_metadata(path.clone(), &metadata);
+                            // Special files hold no bytes, so there is nothing to deduplicate.
+                            if let Some(key) = hardlink_key(&metadata).filter(|_| node.special.is_none()) {
+                                node.shared_link = !seen_inodes.insert(key);
+                            }
+                            if metadata_level == MetadataLevel::Full {
+                                node.meta = Some(Box::new(EntryMeta::from_metadata(&metadata)));
+                            }
+                            node
+                        }
+                        Err(error) => {
+                            Self::record_error(&errors, &error_count, error);
+                            // A directory keeps its place in the tree; its listing may still work.
+                            if !is_dir {
+                                continue;
+                            }
+                            FileNode {
+                                path: path.clone(),
+                                is_dir: true,
+                                ..FileNode::default()
+                            }
+                        }
+                    };
+                    node.ignored = ignored;
+                    if node.is_dir {
+                        node.placeholder = placeholder;
+                    }
+                    if dir_entry.file_type.is_symlink() {
+                        node.symlink_target = std::fs::read_link(&path).ok();
+                    }
+
+                    let listed = node.placeholder.is_none() && dir_entry.read_children_path.is_some();
+                    if let (Some(previous), true) = (previous.as_ref(), node.is_dir && listed) {
+                        if let Some(source) = Self::unchanged_listing(previous, &node) {
+                            let unchanged = match read_timeout {
+                                // A check that hangs only costs the reuse; the folder is then read as usual.
+                                Some(timeout) => {
+                                    let (previous, checked) = (previous.clone(), unchanged_subtrees.clone());
+                                    !reuse_checks_timed_out.load(Ordering::Relaxed)
+                                        && deadline::run_with_deadline(timeout, move || {
+                                            Self::subtree_unchanged(&previous, source, &checked)
+                                        })
+                                        .unwrap_or_else(|| {
+                                            reuse_checks_timed_out.store(true, Ordering::Relaxed);
+                                            false
+                                        })
+                                }
+                                None => Self::subtree_unchanged(previous, source, &unchanged_subtrees),
+                            };
+                            if unchanged {
+                                dir_entry.read_children_path = None;
+                                dir_entry.client_state.reused = true;
+                            } else {
+                                unchanged_listings.insert(path.clone());
+                            }
+                        }
+                    }
+
+                    count_node(&node);
+                    dir_entry.client_state.node = Some(node);
+                }
+
+                // jwalk's own listing of a folder can't be interrupted, so each folder on a
+                // network or FUSE mount is listed here first under the deadline. A folder that
+                // hangs is left unread; the listing jwalk then repeats of the others is
+                // normally answered from the cache this one warmed.
+                if let Some(timeout) = read_timeout {
+                    let (indexes, pending): (Vec<usize>, Vec<PathBuf>) = children
+                        .iter()
+                        .enumerate()
+                        .filter_map(|(index, entry)| {
+                            let path = entry.as_ref().ok()?.read_children_path.as_deref()?;
+                            guarded(path).then(|| (index, path.to_path_buf()))
+                        })
+                        .unzip();
+                    let listed = deadline::map_with_deadline(pending, timeout, move |path: &PathBuf| dir_lister(path));
+                    for (index, listed) in indexes.into_iter().zip(listed) {
+                        if let (Some(Ok(dir_entry)), None) = (children.get_mut(index), listed) {
+                            Self::give_up_unresponsive(dir_entry, timeout, &errors, &error_count, &unresponsive_dirs);
+                        }
+                    }
+                }
+
+                if scan_order == ScanOrder::LargestFirst {
+                    children.sort_by_cached_key(|entry| Reverse(Self::expected_size(previous.as_deref(), entry)));
+                }
+
+                // Once cancelled, entries already listed are still reported but nothing new is read.
+                if cancel.is_cancelled() {
+                    for dir_entry in children.iter_mut().flatten() {
+                        dir_entry.read_children_path = None;
+                    }
+                }
+                // Folders an expanding walk stops above are walked later from their parent's state.
+                if expand && depth == Some(0) {
+                    for dir_entry in children.iter_mut().flatten() {
+                        if dir_entry.read_children_path.is_some() {
+                            dir_entry.client_state.queued_state = Some(dir_state.clone());
+                        }
+                    }
+                }
+            })
+            .into_iter();
+
+        // The most recent directory at each depth; results arrive depth-first, so a
+        // directory is complete once the walk moves back above its depth.
+        let mut dir_stack: Vec<OpenDir> = Vec::new();
+        let mut unread = Vec::new();
+
+        for entry in walker {
+            let mut entry = match entry {
+                Ok(entry) => entry,
+                Err(err) => {
+                    let error = ScanError::from_walk(&err, &start_path, false);
+                    tree.mark_read_error(&error.dir);
+                    Self::record_error(&self.errors, &self.error_count, error);
+                    continue;
+                }
+            };
+            let resumed_root = resuming && entry.depth == 0;
+            if !resumed_root {
+                context.discovered_entries += 1;
+            }
+
+            let path = entry.path();
+            if let Some(err) = &entry.read_children_error {
+                let error = ScanError::from_walk(err, &path, true);
+                Self::record_error(&self.errors, &self.error_count, error);
+            }
+            // The walk's own root maps onto the node the tree already has for it.
+            if entry.depth == 0 {
+                Self::close_dirs(tree, dir_stack.drain(..), &mut context.largest_dirs);
+                if resumed_root {
+                    dir_stack.push(OpenDir::new(start_id, start_live, true));
+                    if entry.read_children_error.is_some() {
+                        tree.mark_read_error(&path);
+                    }
+                    continue;
+                }
+                let scanned = entry.read_children_path.is_some();
+                dir_stack.push(OpenDir::new(start_id, start_live, scanned));
+                let unresponsive = entry.client_state.unresponsive;
+                if let Some(node) = entry.client_state.node.take() {
+                    tree.apply_root_entry(start_id, node);
+                }
+                if entry.client_state.reused {
+                    self.copy_previous_subtree(tree, (start_id, start_live, 0), &path, &scope.path, context);
+                }
+                if entry.read_children_error.is_some() || unresponsive {
+                    tree.mark_read_error(&path);
+                }
+                continue;
+            }
+
+            if dir_stack.len() > entry.depth {
+                Self::close_dirs(tree, dir_stack.drain(entry.depth..), &mut context.largest_dirs);
+            }
+            let Some(parent) = dir_stack.get_mut(entry.depth - 1) else {
+                continue;
+            };
+            let Some(node) = entry.client_state.node.take() else {
+                // Its metadata could not be read, so the parent's total is short.
+                if let Some(dir) = path.parent() {
+                    tree.mark_read_error(dir);
+                }
+                continue;
+            };
+            let is_dir = node.is_dir;
+            let (size, allocated_size, shared_link) = (node.size, node.allocated_size, node.shared_link);
+            let reused = entry.client_state.reused;
+            let unresponsive = entry.client_state.unresponsive;
+            let scanned_dir = is_dir && node.placeholder.is_none() && !reused;
+            let depth = start_depth + entry.depth;
+            let parent_id = parent.id;
+            let live_id = match context.live.as_mut() {
+                Some(live) => live.add(parent.live, depth, &node),
+                None => parent.live,
+            };
+            if is_dir {
+                parent.skip_as_leaf = true;
+            } else if !shared_link {
+                parent.size += size;
+                parent.allocated_size += allocated_size;
+                if context.largest_files.would_keep(size) {
+                    context.largest_files.offer(path.clone(), size, allocated_size);
+                }
+                if node.special.is_none() {
+                    context.file_types.add(&path, size, allocated_size);
+                    context.owners.add(node.owner, size, allocated_size);
+                }
+            }
+            let node_id = tree.append_file_node(parent_id, node);
+            if expand && scanned_dir && entry.read_children_path.is_some() {
+                unread.push(WalkStart {
+                    id: node_id,
+                    live: live_id,
+                    depth,
+                    path: path.clone(),
+                    state: Some(entry.client_state.queued_state.take().unwrap_or_default()),
+                });
+            } else if is_dir {
+                dir_stack.push(OpenDir::new(node_id, live_id, scanned_dir));
+            }
+            if entry.read_children_error.is_some() || unresponsive {
+                tree.mark_read_error(&path);
+            }
+
+            if reporting_enabled {
+                Self::add_to_preview(context, &scope.path, &path, is_dir, (size, allocated_size), shared_link);
+            }
+            if reused {
+                self.copy_previous_subtree(tree, (node_id, live_id, depth), &path, &scope.path, context);
+            }
+
+            if reporting_enabled {
+                let elapsed_ms = context.started.elapsed().as_millis() as u64;
+                if Self::should_emit_progress(&context.last_emit_ms, elapsed_ms) {
+                    let mut progress = self.progress_snapshot(context, None);
+                    progress.live_tree = context.live.as_mut().and_then(|live| live.snapshot(elapsed_ms));
+                    Self::emit_progress(&context.reporter, progress);
+                }
+            }
+        }
+        Self::close_dirs(tree, dir_stack.drain(..), &mut context.largest_dirs);
+        unread
+    }
+
+    /// Count an entry towards the live preview bucket it falls in.
+    fn add_to_preview(
+        context: &ScanContext,
+        root_path: &Path,
+        path: &Path,
+        is_dir: bool,
+        (size, allocated_size): (u64, u64),
+        shared_link: bool,
+    ) {
+        // Several roots are previewed as themselves rather than by their children.
+        let bucket = if context.multi_root {
+            Some((root_path.as_os_str().to_os_string(), true))
+        } else {
+            Self::top_level_name(root_path, path)
+        };
+        let Some((name, nested)) = bucket else {
+            return;
+        };
+        let bucket_is_dir = nested || is_dir;
+        let mut preview = context
+            .preview
+            .entry(name)
+            .or_insert_with(|| PreviewBucket {
+                size: 0,
+                allocated_size: 0,
+                is_dir: bucket_is_dir,
+            });
+
+        if bucket_is_dir {
+            preview.is_dir = true;
+        }
+
+        if !is_dir && !shared_link {
+            preview.size = preview.size.saturating_add(size.max(1));
+            preview.allocated_size = preview.allocated_size.saturating_add(allocated_size.max(1));
+        }
+    }
+
+    /// How big a listed entry is expected to be, for `ScanOrder::LargestFirst`:
+    /// whether it is a folder, and its size in the previous scan.
+    fn expected_size(
+        previous: Option<&FileTree>,
+        entry: &jwalk::Result<jwalk::DirEntry<(DirState, EntryState)>>,
+    ) -> (bool, u64) {
+        let Ok(dir_entry) = entry else {
+            return (false, 0);
+        };
+        let is_dir = dir_entry.client_state.node.as_ref().is_some_and(|node| node.is_dir);
+        if !is_dir {
+            return (false, 0);
+        }
+        (true, previous.map_or(0, |previous| Self::previous_size(previous, &dir_entry.path())))
+    }
+
+    /// Size of `path` in the previous scan; zero if it wasn't there.
+    fn previous_size(previous: &FileTree, path: &Path) -> u64 {
+        previous
+            .get_node(path)
+            .and_then(|node_id| previous.get_arena().get(node_id))
+            .map_or(0, |node| node.get().cumulative_size)
+    }
+
+    /// Previous-scan node for a directory whose listing can be reused: same
+    /// times as the fresh `node`, fully read and not a placeholder back then.
+    fn unchanged_listing(previous: &FileTree, node: &FileNode) -> Option<NodeId> {
+        let source = previous.get_node(&node.path)?;
+        let data = previous.get_arena().get(source)?.get();
+        let reusable = data.is_dir
+            && data.placeholder.is_none()
+            && !data.partial
+            && data.dir_stamp.is_some()
+            && data.dir_stamp == node.dir_stamp;
+        reusable.then_some(source)
+    }
+
+    /// What `process_read_dir` needs from disk for one entry, read on a deadline helper.
+    fn stat_entry((path, follow): &(PathBuf, bool)) -> EntryStat {
+        let metadata = std::fs::symlink_metadata(path);
+        let target = if *follow { std::fs::metadata(path).ok() } else { None };
+        EntryStat { metadata, target }
+    }
+
+    /// The default `DirLister`: read the listing to the end and count it.
+    fn list_dir(path: &Path) -> io::Result<u64> {
+        std::fs::read_dir(path).map(|listing| listing.count() as u64)
+    }
+
+    /// Leave a scanned path whose own stat missed the read timeout unread, as
+    /// a `Placeholder::Unresponsive`.
+    fn give_up_root(&self, tree: &mut FileTree, root_id: NodeId, root_path: &Path, timeout: Duration) {
+        let error = ScanError::timed_out(root_path.to_path_buf(), true, timeout);
+        Self::record_error(&self.errors, &self.error_count, error);
+        self.unresponsive_dirs.fetch_add(1, Ordering::Relaxed);
+        self.dir_count.fetch_add(1, Ordering::Relaxed);
+        let node = FileNode {
+            path: root_path.to_path_buf(),
+            is_dir: true,
+            placeholder: Some(Placeholder::Unresponsive),
+            ..FileNode::default()
+        };
+        tree.apply_root_entry(root_id, node);
+        tree.mark_read_error(root_path);
+    }
+
+    /// Leave an entry that missed the read timeout unread. A file is dropped like
+    /// one whose metadata failed; a directory keeps its place as a
+    /// `Placeholder::Unresponsive`.
+    fn give_up_unresponsive(
+        dir_entry: &mut jwalk::DirEntry<(DirState, EntryState)>,
+        timeout: Duration,
+        errors: &Mutex<Vec<ScanError>>,
+        error_count: &AtomicU64,
+        unresponsive_dirs: &AtomicU64,
+    ) {
+        let path = dir_entry.path();
+        let is_dir = dir_entry.read_children_path.take().is_some();
+        dir_entry.client_state.unresponsive = true;
+        Self::record_error(errors, error_count, ScanError::timed_out(path.clone(), is_dir, timeout));
+        if is_dir {
+            unresponsive_dirs.fetch_add(1, Ordering::Relaxed);
+            let node = dir_entry.client_state.node.get_or_insert_with(|| FileNode {
+                path,
+                is_dir: true,
+                ..FileNode::default()
+            });
+            node.placeholder = Some(Placeholder::Unresponsive);
+        }
+    }
+
+    /// Whether every folder below `dir` in the previous scan still has the times
+    /// recorded for it. Each folder is checked at most once per scan.
+    fn subtree_unchanged(previous: &FileTree, dir: NodeId, checked: &DashMap<NodeId, bool>) -> bool {
+        if let Some(known) = checked.get(&dir) {
+            return *known;
+        }
+        let arena = previous.get_arena();
+        let unchanged = dir.children(arena).all(|child| {
+            let data = arena[child].get();
+            if !data.is_dir || data.placeholder.is_some() {
+                return true;
+            }
+            // Followed links were recorded with their target's times.
+            let metadata = if data.symlink_target.is_some() {
+                std::fs::metadata(&data.path)
+            } else {
+                std::fs::symlink_metadata(&data.path)
+            };
+            let stamp = metadata.ok().map(|metadata| DirStamp::from_metadata(&metadata));
+            stamp.is_some() && stamp == data.dir_stamp && Self::subtree_unchanged(previous, child, checked)
+        });
+        checked.insert(dir, unchanged);
+        unchanged
+    }
+
+    /// Copy everything below `path` from the previous scan under `target`,
+    /// counting it as if it had just been read. `target` comes with its live
+    /// tree node and its depth below the scanned path.
+    fn copy_previous_subtree(
+        &self,
+        tree: &mut FileTree,
+        target: (NodeId, NodeId, usize),
+        path: &Path,
+        root_path: &Path,
+        context: &mut ScanContext,
+    ) {
+        let Some(previous) = context.previous.clone() else {
+            return;
+        };
+        let Some(source) = previous.get_node(path) else {
+            return;
+        };
+        let arena = previous.get_arena();
+        let reporting_enabled = context.reporter.is_some();
+
+        let (target, live_target, depth) = target;
+        let mut pending = vec![(source, target, live_target, depth)];
+        while let Some((source_dir, target_dir, live_dir, depth)) = pending.pop() {
+            self.reused_dirs.fetch_add(1, Ordering::Relaxed);
+            let mut leaf = OpenDir::new(target_dir, live_dir, true);
+            for child in source_dir.children(arena) {
+                let data = arena[child].get();
+                context.discovered_entries += 1;
+                if data.symlink_target.is_some() {
+                    self.symlinks.fetch_add(1, Ordering::Relaxed);
+                }
+                if data.ignored {
+                    self.ignored_entries.fetch_add(1, Ordering::Relaxed);
+                }
+                let (size, allocated_size) = (data.size, data.allocated_size);
+                if data.is_dir {
+                    self.dir_count.fetch_add(1, Ordering::Relaxed);
+                    leaf.skip_as_leaf = true;
+                    match &data.placeholder {
+                        Some(Placeholder::Excluded(_)) => {
+                            self.excluded_entries.fetch_add(1, Ordering::Relaxed);
+                        }
+                        Some(Placeholder::VirtualFs(_)) => {
+                            self.virtual_mounts.fetch_add(1, Ordering::Relaxed);
+                        }
+                        Some(Placeholder::SymlinkLoop) => {
+                            self.symlink_loops.fetch_add(1, Ordering::Relaxed);
+                        }
+                        Some(Placeholder::MountPoint(_) | Placeholder::Unresponsive) | None => {}
+                    }
+                } else if let Some(kind) = data.special {
+                    let counter = match kind {
+                        FileKind::Socket => &self.sockets,
+                        FileKind::Fifo => &self.fifos,
+                        FileKind::BlockDevice => &self.block_devices,
+                        _ => &self.char_devices,
+                    };
+                    counter.fetch_add(1, Ordering::Relaxed);
+                } else if data.shared_link {
+                    self.file_count.fetch_add(1, Ordering::Relaxed);
+                    self.shared_links.fetch_add(1, Ordering::Relaxed);
+                    self.shared_size.fetch_add(size, Ordering::Relaxed);
+                    self.shared_allocated_size.fetch_add(allocated_size, Ordering::Relaxed);
+                } else {
+                    self.file_count.fetch_add(1, Ordering::Relaxed);
+                    self.total_size.fetch_add(size, Ordering::Relaxed);
+                    self.total_allocated_size.fetch_add(allocated_size, Ordering::Relaxed);
+                    if data.ignored {
+                        self.ignored_size.fetch_add(size, Ordering::Relaxed);
+                        self.ignored_allocated_size.fetch_add(allocated_size, Ordering::Relaxed);
+                    }
+                }
+                if !data.is_dir && !data.shared_link {
+                    leaf.size += size;
+                    leaf.allocated_size += allocated_size;
+                    if context.largest_files.would_keep(size) {
+                        context.largest_files.offer(data.path.clone(), size, allocated_size);
+                    }
+                    if data.special.is_none() {
+                        context.file_types.add(&data.path, size, allocated_size);
+                        context.owners.add(data.owner, size, allocated_size);
+                    }
+                }
+                if reporting_enabled {
+                    let sizes = (size, allocated_size);
+                    Self::add_to_preview(context, root_path, &data.path, data.is_dir, sizes, data.shared_link);
+                }
+
+                let node = data.to_file_node();
+                let live_id = match context.live.as_mut() {
+                    Some(live) => live.add(live_dir, depth + 1, &node),
+                    None => live_dir,
+                };
+                let node_id = tree.append_file_node(target_dir, node);
+                if data.is_dir && data.placeholder.is_none() {
+                    pending.push((child, node_id, live_id, depth + 1));
+                }
+            }
+            Self::close_dirs(tree, std::iter::once(leaf), &mut context.largest_dirs);
+        }
+    }
+
+    /// Offer finished directories without subfolders to the largest leaf folders list.
+    fn close_dirs(tree: &FileTree, dirs: impl Iterator<Item = OpenDir>, largest_dirs: &mut TopN) {
+        for dir in dirs {
+            if dir.skip_as_leaf || dir.size == 0 || !largest_dirs.would_keep(dir.size) {
+                continue;
+            }
+            if let Some(node) = tree.get_arena().get(dir.id) {
+                largest_dirs.offer(node.get().path.clone(), dir.size, dir.allocated_size);
+            }
+        }
+    }
+
+    fn crosses_filesystem(metadata: Option<&Metadata>, root_dev: u64) -> bool {
+        metadata
+            .and_then(device_id)
+            .map(|dev| dev != root_dev)
+            .unwrap_or(false)
+    }
 }
 
 impl Default for FileCrawler {
@@ -331,29 +2167,965 @@ impl Default for FileCrawler {
     }
 }
 
+/// Configures a `FileCrawler` and sizes the thread pool it owns.
+#[derive(Debug, Clone, Default)]
+pub struct FileCrawlerBuilder {
+    options: ScanOptions,
+    threads: Option<usize>,
+    dir_lister: Option<DirLister>,
+    mount_table: Option<MountTable>,
+}
+
+impl FileCrawlerBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn options(mut self, options: ScanOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Worker threads for the crawler's pool. Defaults to three per core
+    /// (6 to 64), since most of their time is spent waiting on the disk.
+    /// A thread cap in `ScanOptions::throttle` takes precedence.
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = Some(threads);
+        self
+    }
+
+    /// How folders on network and FUSE mounts are listed ahead of the walk under
+    /// `ScanOptions::read_timeout`. Defaults to reading the listing from disk.
+    pub fn dir_lister(mut self, dir_lister: DirLister) -> Self {
+        self.dir_lister = Some(dir_lister);
+        self
+    }
+
+    /// Mounts to go by instead of the system's own, read when a scan starts.
+    pub fn mount_table(mut self, mount_table: MountTable) -> Self {
+        self.mount_table = Some(mount_table);
+        self
+    }
+
+    pub fn build(self) -> FileCrawler {
+        let threads = self
+            .options
+            .throttle
+            .threads
+            .or(self.threads)
+            .unwrap_or_else(default_parallelism)
+            .max(1);
+        let idle_io = self.options.throttle.idle_io;
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .thread_name(|index| format!("spaceinsight-scan-{}", index))
+            .start_handler(move |_| {
+                if idle_io {
+                    set_idle_io_priority();
+                }
+            })
+            .build()
+            .ok()
+            .map(Arc::new);
+        let crawler = FileCrawler::from_builder(self.options, pool);
+        FileCrawler {
+            dir_lister: self.dir_lister.unwrap_or(crawler.dir_lister),
+            mount_table: self.mount_table,
+            ..crawler
+        }
+    }
+}
+
+/// Lists a folder ahead of the walk and counts its entries; see
+/// `FileCrawlerBuilder::dir_lister`.
+pub type DirLister = fn(&Path) -> io::Result<u64>;
+
+fn default_parallelism() -> usize {
+    let cores = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(4);
+    (cores * 3).clamp(6, 64)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::tree::TreeNode;
+
+    /// Mounts with `path` (canonical) on NFS, so reads below it get a deadline.
+    fn nfs_mount_at(path: &Path) -> MountTable {
+        MountTable::parse_mountinfo(&format!(
+            "28 1 254:0 / / rw - ext4 /dev/vda rw\n41 28 0:40 / {} rw - nfs4 server:/export rw\n",
+            path.display()
+        ))
+    }
+
+    /// Lists folders like the default lister, except that the first call after the
+    /// scanned path's own hangs, as on a dead mount.
+    fn hang_first_child_listing(path: &Path) -> io::Result<u64> {
+        static CALLS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+        if CALLS.fetch_add(1, Ordering::SeqCst) == 1 {
+            std::thread::sleep(Duration::from_secs(2));
+        }
+        FileCrawler::list_dir(path)
+    }
+
+    fn hang_every_listing(_path: &Path) -> io::Result<u64> {
+        std::thread::sleep(Duration::from_secs(2));
+        Ok(0)
+    }
+
+    /// Every node below the root, in arena order.
+    fn entries(tree: &FileTree) -> Vec<&TreeNode> {
+        let arena = tree.get_arena();
+        tree.get_root()
+            .descendants(arena)
+            .skip(1)
+            .filter_map(|id| arena.get(id).map(|n| n.get()))
+            .collect()
+    }
 
     #[test]
     fn test_crawler_basic() {
         let mut crawler = FileCrawler::new();
-        let (nodes, stats) = crawler.scan_with_progress(".", None);
+        let (tree, stats) = crawler.scan_with_progress(".", None);
+        let nodes = entries(&tree);
         
         assert!(stats.total_files > 0 || stats.total_dirs > 0);
         assert!(!nodes.is_empty());
+        assert_eq!(tree.total_size(), stats.total_size);
         println!("Scanned {} files and {} dirs in {}ms", 
                  stats.total_files, stats.total_dirs, stats.duration_ms);
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn test_hardlinks_charged_once() {
+        let root = std::env::temp_dir().join(format!("spaceinsight-hardlinks-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("a")).unwrap();
+        std::fs::write(root.join("a/data.bin"), vec![7u8; 4096]).unwrap();
+        std::fs::hard_link(root.join("a/data.bin"), root.join("b.bin")).unwrap();
+        std::fs::hard_link(root.join("a/data.bin"), root.join("c.bin")).unwrap();
+
+        let mut crawler = FileCrawler::new();
+        let (tree, stats) = crawler.scan_with_progress(&root, None);
+        let nodes = entries(&tree);
+        let _ = std::fs::remove_dir_all(&root);
+
+        assert_eq!(stats.total_files, 3);
+        assert_eq!(stats.total_size, 4096);
+        assert_eq!(stats.shared_links, 2);
+        assert_eq!(stats.shared_size, 8192);
+        assert_eq!(nodes.iter().filter(|n| n.shared_link).count(), 2);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_watched_node_follows_scan_filters() {
+        use crate::exclude::ExcludeRule;
+
+        let root = std::env::temp_dir().join(format!("spaceinsight-watch-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("node_modules/pkg")).unwrap();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join(".ignore"), "*.log\n").unwrap();
+        std::fs::write(root.join("src/data.bin"), vec![0u8; 100]).unwrap();
+        std::fs::hard_link(root.join("src/data.bin"), root.join("src/link.bin")).unwrap();
+
+        let mut options = ScanOptions {
+            ignore_files: IgnoreMode::Skip,
+            ..ScanOptions::default()
+        };
+        options.exclude.push(ExcludeRule::Name("node_modules".to_string())).unwrap();
+        options.exclude.min_file_size = 16;
+        let (tree, _) = FileCrawler::with_options(options.clone()).scan_with_progress(&root, None);
+
+        let watched = |name: &str, size: usize| {
+            let path = root.join(name);
+            std::fs::write(&path, vec![1u8; size]).unwrap();
+            options.watched_node(&tree, &path, &std::fs::metadata(&path).unwrap())
+        };
+        let kept = watched("src/new.bin", 100).expect("a plain new file is kept");
+        let excluded = watched("node_modules/pkg/index.js", 100);
+        let small = watched("src/small.txt", 5);
+        let ignored = watched("src/debug.log", 100);
+        let known_link = watched("src/link.bin", 200).expect("a known link is updated");
+        std::fs::hard_link(root.join("src/data.bin"), root.join("new-link.bin")).unwrap();
+        let new_link = std::fs::metadata(root.join("new-link.bin")).unwrap();
+        let new_link = options.watched_node(&tree, &root.join("new-link.bin"), &new_link);
+        let _ = std::fs::remove_dir_all(&root);
+
+        assert_eq!(kept.size, 100);
+        assert!(excluded.is_none() && small.is_none() && ignored.is_none());
+        let link = tree.get_arena()[tree.get_node(&root.join("src/link.bin")).unwrap()].get();
+        assert_eq!(known_link.shared_link, link.shared_link);
+        assert!(new_link.is_none());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_one_file_system_leaves_mount_placeholders() {
+        use std::os::unix::fs::MetadataExt;
+
+        // /dev/pts is its own devpts mount on any normal Linux system.
+        let (Ok(dev), Ok(pts)) = (std::fs::metadata("/dev"), std::fs::metadata("/dev/pts")) else {
+            return;
+        };
+        if dev.dev() == pts.dev() {
+            return;
+        }
+
+        // devpts is also a virtual filesystem; walk it so only the device boundary applies.
+        let mut crawler = FileCrawler::with_options(ScanOptions {
+            one_file_system: true,
+            virtual_fs: VirtualFsPolicy::Scan,
+            ..ScanOptions::default()
+        });
+        let (tree, _) = crawler.scan_with_progress("/dev", None);
+        let nodes = entries(&tree);
+
+        let pts_node = nodes
+            .iter()
+            .find(|n| n.path == Path::new("/dev/pts"))
+            .expect("mount point itself is still reported");
+        assert!(matches!(pts_node.placeholder, Some(Placeholder::MountPoint(_))));
+        assert!(!nodes
+            .iter()
+            .any(|n| n.path != pts_node.path && n.path.starts_with("/dev/pts")));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_virtual_filesystems_are_skipped_by_default() {
+        let table = MountTable::load();
+        if table.mount_at(Path::new("/dev/pts")).map(|m| m.fs_type.as_str()) != Some("devpts") {
+            return;
+        }
+
+        let mut crawler = FileCrawler::new();
+        let (tree, stats) = crawler.scan_with_progress("/dev", None);
+        let nodes = entries(&tree);
+
+        let pts_node = nodes
+            .iter()
+            .find(|n| n.path == Path::new("/dev/pts"))
+            .expect("virtual mount is still reported");
+        assert_eq!(pts_node.placeholder, Some(Placeholder::VirtualFs("devpts".to_string())));
+        assert!(stats.virtual_mounts >= 1);
+        assert!(!nodes
+            .iter()
+            .any(|n| n.path != pts_node.path && n.path.starts_with("/dev/pts")));
+    }
+
+    #[test]
+    fn test_exclude_rules_leave_placeholders() {
+        use crate::exclude::ExcludeRule;
+
+        let root = std::env::temp_dir().join(format!("spaceinsight-exclude-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("app/node_modules/pkg")).unwrap();
+        std::fs::write(root.join("app/node_modules/pkg/index.js"), vec![1u8; 2048]).unwrap();
+        std::fs::write(root.join("app/main.rs"), vec![1u8; 2048]).unwrap();
+        std::fs::write(root.join("app/tiny.txt"), b"hi").unwrap();
+        std::fs::write(root.join("disk.iso"), vec![1u8; 2048]).unwrap();
+
+        let mut exclude = ExcludeRules::new(vec![
+            ExcludeRule::Name("node_modules".to_string()),
+            ExcludeRule::Glob("**/*.iso".to_string()),
+        ])
+        .unwrap();
+        exclude.min_file_size = 16;
+        let mut crawler = FileCrawler::with_options(ScanOptions {
+            exclude,
+            ..ScanOptions::default()
+        });
+        let (tree, stats) = crawler.scan_with_progress(&root, None);
+        let nodes = entries(&tree);
+        let _ = std::fs::remove_dir_all(&root);
+
+        let excluded = nodes
+            .iter()
+            .find(|n| n.path.ends_with("node_modules"))
+            .expect("excluded directory is still reported");
+        assert_eq!(
+            excluded.placeholder,
+            Some(Placeholder::Excluded("node_modules".to_string()))
+        );
+        assert!(!nodes.iter().any(|n| n.path.ends_with("index.js")));
+        assert!(!nodes.iter().any(|n| n.path.ends_with("disk.iso")));
+        assert_eq!(stats.excluded_entries, 2);
+        assert_eq!(stats.pruned_files, 1);
+        assert_eq!(stats.pruned_size, 2);
+        assert_eq!(stats.total_size, 2048);
+    }
+
+    #[test]
+    fn test_ignore_files_skip_and_tag() {
+        let root = std::env::temp_dir().join(format!("spaceinsight-ignore-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join(".git")).unwrap();
+        std::fs::create_dir_all(root.join("target/debug")).unwrap();
+        std::fs::create_dir_all(root.join("sub/scratch")).unwrap();
+        std::fs::write(root.join(".gitignore"), "target/\n*.log\n!keep.log\n").unwrap();
+        std::fs::write(root.join("sub/.ignore"), "scratch\n").unwrap();
+        std::fs::write(root.join("main.rs"), vec![1u8; 100]).unwrap();
+        std::fs::write(root.join("target/debug/app"), vec![1u8; 1000]).unwrap();
+        std::fs::write(root.join("build.log"), vec![1u8; 10]).unwrap();
+        std::fs::write(root.join("keep.log"), vec![1u8; 10]).unwrap();
+        std::fs::write(root.join("sub/scratch/tmp.bin"), vec![1u8; 50]).unwrap();
+        std::fs::write(root.join("sub/notes.log"), vec![1u8; 5]).unwrap();
+
+        let scan = |mode: IgnoreMode, path: &Path| {
+            let mut crawler = FileCrawler::with_options(ScanOptions {
+                ignore_files: mode,
+                ..ScanOptions::default()
+            });
+            crawler.scan_with_progress(path, None)
+        };
+        let (tagged_tree, tagged_stats) = scan(IgnoreMode::Tag, &root);
+        let (skipped_tree, _) = scan(IgnoreMode::Skip, &root);
+        let (sub_only_tree, _) = scan(IgnoreMode::Skip, &root.join("sub"));
+        let (tagged, skipped, sub_only) =
+            (entries(&tagged_tree), entries(&skipped_tree), entries(&sub_only_tree));
+        let _ = std::fs::remove_dir_all(&root);
+
+        let is_ignored = |name: &str| {
+            tagged
+                .iter()
+                .find(|n| n.path.ends_with(name))
+                .map(|n| n.ignored)
+                .unwrap_or_else(|| panic!("{} missing", name))
+        };
+        assert!(is_ignored("target/debug/app"));
+        assert!(is_ignored("build.log"));
+        assert!(is_ignored("sub/scratch/tmp.bin"));
+        assert!(is_ignored("sub/notes.log"));
+        assert!(!is_ignored("keep.log"));
+        assert!(!is_ignored("main.rs"));
+        assert_eq!(tagged_stats.ignored_size, 1000 + 10 + 50 + 5);
+
+        let has = |nodes: &[&TreeNode], name: &str| nodes.iter().any(|n| n.path.ends_with(name));
+        assert!(!has(&skipped, "target"));
+        assert!(!has(&skipped, "build.log"));
+        assert!(has(&skipped, "keep.log"));
+        assert!(has(&skipped, "main.rs"));
+
+        // Rules above the scan root still apply.
+        assert!(!has(&sub_only, "notes.log"));
+        assert!(!has(&sub_only, "scratch"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_unreadable_directory_is_reported() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let root = std::env::temp_dir().join(format!("spaceinsight-errors-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("locked")).unwrap();
+        std::fs::write(root.join("locked/secret.bin"), vec![1u8; 64]).unwrap();
+        std::fs::set_permissions(root.join("locked"), std::fs::Permissions::from_mode(0o000)).unwrap();
+        let readable = std::fs::read_dir(root.join("locked")).is_ok();
+
+        let mut crawler = FileCrawler::new();
+        let (tree, stats) = crawler.scan_with_progress(&root, None);
+        let nodes = entries(&tree);
+        std::fs::set_permissions(root.join("locked"), std::fs::Permissions::from_mode(0o755)).unwrap();
+        let _ = std::fs::remove_dir_all(&root);
+
+        // Running as root bypasses the permission check.
+        if readable {
+            return;
+        }
+        assert!(nodes.iter().any(|n| n.path.ends_with("locked") && n.partial));
+        assert!(tree.is_partial());
+        assert_eq!(stats.error_count, 1);
+        let error = &stats.errors[0];
+        assert_eq!(error.kind, io::ErrorKind::PermissionDenied);
+        assert!(error.dir.ends_with("locked"));
+    }
+
+    #[test]
+    fn test_cancelled_scan_returns_partial_results() {
+        let mut crawler = FileCrawler::new();
+        crawler.cancel_token().cancel();
+        let (tree, stats) = crawler.scan_with_progress(".", None);
+        let nodes = entries(&tree);
+
+        assert!(stats.cancelled);
+        assert!(tree.is_incomplete());
+        // Only the root itself is listed; nothing below it is read.
+        assert!(nodes.is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_scan_several_roots() {
+        let base = std::env::temp_dir().join(format!("spaceinsight-roots-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&base);
+        std::fs::create_dir_all(base.join("disk1/photos")).unwrap();
+        std::fs::create_dir_all(base.join("disk2")).unwrap();
+        std::fs::write(base.join("disk1/photos/a.jpg"), vec![1u8; 3000]).unwrap();
+        std::fs::write(base.join("disk2/b.bin"), vec![1u8; 1000]).unwrap();
+        std::fs::hard_link(base.join("disk2/b.bin"), base.join("disk1/b-link.bin")).unwrap();
+
+        let mut crawler = FileCrawler::new();
+        let (tree, stats) =
+            crawler.scan_paths_with_progress(&[base.join("disk1"), base.join("disk2")], None);
+        let _ = std::fs::remove_dir_all(&base);
+
+        assert!(tree.is_multi_root());
+        // The hardlink between the roots is charged once.
+        assert_eq!(stats.total_size, 4000);
+        assert_eq!(tree.total_size(), 4000);
+        let arena = tree.get_arena();
+        let roots: Vec<_> = tree
+            .get_root()
+            .children(arena)
+            .map(|id| arena[id].get())
+            .collect();
+        assert_eq!(roots.len(), 2);
+        assert!(roots[0].path.ends_with("disk1") && roots[1].path.ends_with("disk2"));
+        let photos = tree.get_node(&roots[0].path.join("photos")).unwrap();
+        assert_eq!(arena[photos].get().cumulative_size, 3000);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_full_metadata_level() {
+        use crate::entry_meta::FileKind;
+        use std::os::unix::fs::PermissionsExt;
+
+        let root = std::env::temp_dir().join(format!("spaceinsight-meta-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("sub")).unwrap();
+        std::fs::write(root.join("sub/run.sh"), b"#!/bin/sh\n").unwrap();
+        std::fs::set_permissions(root.join("sub/run.sh"), std::fs::Permissions::from_mode(0o750)).unwrap();
+
+        let scan = |metadata: MetadataLevel| {
+            let mut crawler = FileCrawler::with_options(ScanOptions {
+                metadata,
+                ..ScanOptions::default()
+            });
+            crawler.scan_with_progress(&root, None).0
+        };
+        let basic = scan(MetadataLevel::Basic);
+        let full = scan(MetadataLevel::Full);
+        let _ = std::fs::remove_dir_all(&root);
+
+        assert!(entries(&basic).iter().all(|n| n.meta.is_none()));
+        let full_nodes = entries(&full);
+        let script = full_nodes.iter().find(|n| n.path.ends_with("run.sh")).unwrap();
+        let meta = script.meta.as_ref().expect("metadata recorded");
+        assert_eq!(meta.kind, FileKind::File);
+        assert_eq!(meta.mode_string(), "-rwxr-x---");
+        assert!(meta.inode > 0);
+        assert!(meta.modified > 0);
+        let sub = full_nodes.iter().find(|n| n.path.ends_with("sub")).unwrap();
+        assert_eq!(sub.meta.as_ref().map(|m| m.kind), Some(FileKind::Dir));
+        let root_node = full.get_arena().get(full.get_root()).unwrap().get();
+        assert!(root_node.meta.is_some());
+    }
+
+    #[test]
+    fn test_crawler_owns_its_pool() {
+        let global_threads = rayon::current_num_threads();
+        let crawler = FileCrawler::builder().threads(3).build();
+        assert_eq!(crawler.threads(), 3);
+
+        let capped = FileCrawler::builder()
+            .threads(8)
+            .options(ScanOptions {
+                throttle: ScanThrottle {
+                    threads: Some(1),
+                    ..ScanThrottle::default()
+                },
+                ..ScanOptions::default()
+            })
+            .build();
+        assert_eq!(capped.threads(), 1);
+
+        let mut crawler = crawler;
+        let (_, stats) = crawler.scan_with_progress(concat!(env!("CARGO_MANIFEST_DIR"), "/src"), None);
+        assert!(stats.total_files > 0);
+        assert_eq!(rayon::current_num_threads(), global_threads);
+    }
+
+    #[test]
+    fn test_largest_files_and_leaf_dirs() {
+        let root = std::env::temp_dir().join(format!("spaceinsight-largest-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("media/photos")).unwrap();
+        std::fs::create_dir_all(root.join("docs")).unwrap();
+        std::fs::write(root.join("media/movie.mkv"), vec![0u8; 9000]).unwrap();
+        std::fs::write(root.join("media/photos/a.jpg"), vec![0u8; 3000]).unwrap();
+        std::fs::write(root.join("media/photos/b.jpg"), vec![0u8; 2000]).unwrap();
+        std::fs::write(root.join("docs/notes.txt"), vec![0u8; 100]).unwrap();
+        std::fs::write(root.join("top.bin"), vec![0u8; 500]).unwrap();
+
+        let progress = Arc::new(Mutex::new(Vec::new()));
+        let sink = progress.clone();
+        let mut crawler = FileCrawler::new();
+        let (_, stats) = crawler.scan_with_progress(
+            &root,
+            Some(Arc::new(move |p: ScanProgress| sink.lock().unwrap().push(p.largest_files.len()))),
+        );
+        let _ = std::fs::remove_dir_all(&root);
+
+        let files: Vec<_> = stats.largest_files.iter().map(|e| e.path.strip_prefix(&root).unwrap()).collect();
+        assert_eq!(
+            files,
+            ["media/movie.mkv", "media/photos/a.jpg", "media/photos/b.jpg", "top.bin", "docs/notes.txt"]
+                .map(Path::new)
+        );
+        assert_eq!(stats.largest_files[0].size, 9000);
+
+        // `media` holds a folder, so only the folders without subfolders are listed.
+        let dirs: Vec<_> = stats.largest_dirs.iter().map(|e| e.path.strip_prefix(&root).unwrap()).collect();
+        assert_eq!(dirs, [Path::new("media/photos"), Path::new("docs")]);
+        assert_eq!(stats.largest_dirs[0].size, 5000);
+
+        assert_eq!(progress.lock().unwrap().last(), Some(&5));
+    }
+
+    #[test]
+    fn test_file_type_breakdown() {
+        use crate::file_types::FileCategory;
+
+        let root = std::env::temp_dir().join(format!("spaceinsight-types-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("videos")).unwrap();
+        std::fs::create_dir_all(root.join("logs")).unwrap();
+        std::fs::write(root.join("videos/a.mp4"), vec![0u8; 4000]).unwrap();
+        std::fs::write(root.join("videos/b.MP4"), vec![0u8; 2000]).unwrap();
+        std::fs::write(root.join("logs/app.log"), vec![0u8; 3000]).unwrap();
+        std::fs::write(root.join("logs/notes.txt"), vec![0u8; 1000]).unwrap();
+        std::fs::hard_link(root.join("videos/a.mp4"), root.join("logs/a-link.mp4")).unwrap();
+
+        let mut crawler = FileCrawler::new();
+        let (tree, stats) = crawler.scan_with_progress(&root, None);
+        let _ = std::fs::remove_dir_all(&root);
+
+        let mp4 = stats.file_types.by_extension["mp4"];
+        assert_eq!((mp4.files, mp4.size), (2, 6000), "the extra hardlink is not counted");
+        assert_eq!(stats.file_types.by_extension["log"].size, 3000);
+        assert_eq!(stats.file_types.by_category[&FileCategory::Media].size, 6000);
+        assert_eq!(stats.file_types.by_category[&FileCategory::Document].size, 1000);
+        assert_eq!(stats.file_types.total().size, stats.total_size);
+
+        // The tree gives the same totals for the whole scan and narrows them per folder.
+        let canonical_root = tree.root_path().unwrap().to_path_buf();
+        assert_eq!(*tree.type_breakdown(tree.get_root()), stats.file_types);
+        let logs = tree.get_node(&canonical_root.join("logs")).unwrap();
+        let in_logs = tree.type_breakdown(logs);
+        // Either link to `a.mp4` may be the one charged, depending on which folder is read first.
+        let ranked: Vec<_> = in_logs
+            .extensions_by_size(SizeMode::Apparent)
+            .into_iter()
+            .map(|(key, _)| key)
+            .filter(|key| *key != "mp4")
+            .collect();
+        assert_eq!(ranked, ["log", "txt"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_usage_per_owner() {
+        use std::os::unix::fs::MetadataExt;
+
+        let root = std::env::temp_dir().join(format!("spaceinsight-owners-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("build")).unwrap();
+        std::fs::write(root.join("build/out.o"), vec![0u8; 3000]).unwrap();
+        std::fs::write(root.join("notes.txt"), vec![0u8; 200]).unwrap();
+        let uid = std::fs::metadata(&root).unwrap().uid();
+
+        let mut crawler = FileCrawler::new();
+        let (tree, stats) = crawler.scan_with_progress(&root, None);
+        let _ = std::fs::remove_dir_all(&root);
+
+        assert_eq!(stats.owners.by_user.len(), 1);
+        assert_eq!(stats.owners.by_user[&uid].size, 3200);
+        assert_eq!(stats.owners.by_user[&uid].files, 2);
+        assert_eq!(tree.owner_usage(tree.get_root()), stats.owners);
+
+        let build = tree.get_node(&tree.root_path().unwrap().join("build")).unwrap();
+        assert_eq!(tree.owner_usage(build).total().size, 3000);
+        let sizes = tree.user_sizes(uid);
+        assert_eq!(sizes[&tree.get_root()], 3200);
+        assert_eq!(sizes[&build], 3000);
+        assert!(tree.user_sizes(uid.wrapping_add(1)).is_empty());
+        // Freshly written files land in the newest age bucket.
+        assert_eq!(tree.get_arena()[build].get().age.buckets[0], 3000);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_special_files_are_counted_apart() {
+        use crate::entry_meta::FileKind;
+
+        let root = std::env::temp_dir().join(format!("spaceinsight-special-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join("data.bin"), vec![0u8; 4096]).unwrap();
+        let _listener = std::os::unix::net::UnixListener::bind(root.join("app.sock")).unwrap();
+
+        let mut exclude = ExcludeRules::default();
+        exclude.min_file_size = 1024;
+        let mut crawler = FileCrawler::with_options(ScanOptions {
+            exclude,
+            ..ScanOptions::default()
+        });
+        let (tree, stats) = crawler.scan_with_progress(&root, None);
+        let _ = std::fs::remove_dir_all(&root);
+
+        assert_eq!(stats.total_files, 1);
+        assert_eq!(stats.sockets, 1);
+        assert_eq!(stats.fifos + stats.block_devices + stats.char_devices, 0);
+        assert_eq!(stats.total_size, 4096);
+        let nodes = entries(&tree);
+        let socket = nodes.iter().find(|n| n.name == "app.sock").expect("kept despite the size cutoff");
+        assert_eq!(socket.special, Some(FileKind::Socket));
+        assert_eq!(socket.cumulative_size, 0);
+    }
+
+    #[test]
+    fn test_gentle_scan_matches_fast_scan() {
+        use crate::throttle::ScanProfile;
+
+        let root = std::env::temp_dir().join(format!("spaceinsight-gentle-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        for dir in ["a", "b/c"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+            for i in 0..5 {
+                std::fs::write(root.join(dir).join(format!("f{}", i)), vec![0u8; 100 * (i + 1)]).unwrap();
+            }
+        }
+
+        let scan = |profile: ScanProfile| {
+            let mut crawler = FileCrawler::with_options(ScanOptions {
+                throttle: profile.throttle(),
+                ..ScanOptions::default()
+            });
+            crawler.scan_with_progress(&root, None).1
+        };
+        let fast = scan(ScanProfile::Fast);
+        let gentle = scan(ScanProfile::Gentle);
+        let _ = std::fs::remove_dir_all(&root);
+
+        assert_eq!(gentle.total_files, 10);
+        assert_eq!(gentle.total_files, fast.total_files);
+        assert_eq!(gentle.total_dirs, fast.total_dirs);
+        assert_eq!(gentle.total_size, fast.total_size);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_read_timeout_matches_plain_scan() {
+        let root = std::env::temp_dir().join(format!("spaceinsight-deadline-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("a/deep")).unwrap();
+        std::fs::create_dir_all(root.join("b")).unwrap();
+        std::fs::write(root.join("a/deep/big.bin"), vec![0u8; 5000]).unwrap();
+        std::fs::write(root.join("b/tiny.txt"), vec![0u8; 10]).unwrap();
+        std::fs::write(root.join("b/mid.txt"), vec![0u8; 2000]).unwrap();
+        std::os::unix::fs::symlink(root.join("a"), root.join("b/to-a")).unwrap();
+
+        let options = |read_timeout| {
+            let mut options = ScanOptions {
+                symlinks: SymlinkPolicy::Follow,
+                read_timeout,
+                ..ScanOptions::default()
+            };
+            options.exclude.min_file_size = 16;
+            options
+        };
+        let (plain_tree, plain) = FileCrawler::with_options(options(None)).scan_with_progress(&root, None);
+        let mut crawler = FileCrawler::builder()
+            .options(options(Some(Duration::from_secs(10))))
+            .mount_table(nfs_mount_at(&std::fs::canonicalize(&root).unwrap()))
+            .build();
+        let (tree, stats) = crawler.scan_with_progress(&root, None);
+
+        assert_eq!(stats.unresponsive_dirs, 0);
+        assert_eq!((stats.total_files, stats.pruned_files), (plain.total_files, 1));
+        assert_eq!((stats.total_dirs, stats.shared_links), (plain.total_dirs, plain.shared_links));
+        assert_eq!(tree.total_size(), plain_tree.total_size());
+        assert!(tree.get_node(&root.join("b/to-a/deep/big.bin")).is_some());
+
+        crawler.set_previous_scan(tree);
+        let (_, rescan) = crawler.scan_with_progress(&root, None);
+        let _ = std::fs::remove_dir_all(&root);
+        assert_eq!(rescan.reused_dirs, plain.total_dirs);
+        assert_eq!(rescan.total_size, plain.total_size);
+    }
+
+    #[test]
+    fn test_hung_listing_is_given_up() {
+        let root = std::env::temp_dir().join(format!("spaceinsight-hung-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        for dir in ["d1", "d2", "d3"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+            std::fs::write(root.join(dir).join("file.bin"), vec![0u8; 100]).unwrap();
+        }
+        std::fs::write(root.join("top.bin"), vec![0u8; 200]).unwrap();
+        let canonical_root = std::fs::canonicalize(&root).unwrap();
+
+        let timeout = Duration::from_millis(300);
+        let scan = |mount_point: &Path, dir_lister: DirLister| {
+            FileCrawler::builder()
+                .options(ScanOptions {
+                    read_timeout: Some(timeout),
+                    ..ScanOptions::default()
+                })
+                .mount_table(nfs_mount_at(mount_point))
+                .dir_lister(dir_lister)
+                .build()
+                .scan_with_progress(&root, None)
+        };
+        let unresponsive = |tree: &FileTree| -> Vec<String> {
+            let arena = tree.get_arena();
+            tree.get_root()
+                .children(arena)
+                .map(|id| arena[id].get())
+                .filter(|data| data.placeholder == Some(Placeholder::Unresponsive))
+                .map(|data| data.name.clone())
+                .collect()
+        };
+
+        // The first folder listed hangs; it costs one timeout and the siblings after it are still read.
+        let started = std::time::Instant::now();
+        let (tree, stats) = scan(&canonical_root, hang_first_child_listing);
+        let elapsed = started.elapsed();
+        assert!(elapsed < timeout * 3, "scan took {:?}", elapsed);
+        assert_eq!(stats.unresponsive_dirs, 1);
+        assert_eq!((stats.total_files, stats.total_size), (3, 400));
+        let hung = unresponsive(&tree);
+        assert_eq!(hung.len(), 1);
+        let hung = tree.get_arena()[tree.get_node(&root.join(&hung[0])).unwrap()].get();
+        assert!(hung.partial);
+        assert_eq!(stats.errors[0].kind, io::ErrorKind::TimedOut);
+
+        // Only folders on the network mount get a deadline; the local ones beside it are read as usual.
+        let (tree, stats) = scan(&canonical_root.join("d2"), hang_every_listing);
+        let _ = std::fs::remove_dir_all(&root);
+        assert_eq!(unresponsive(&tree), ["d2"]);
+        assert_eq!((stats.total_files, stats.total_size), (3, 400));
+    }
+
+    #[test]
+    fn test_largest_first_order() {
+        let root = std::env::temp_dir().join(format!("spaceinsight-order-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let dirs = [("big/deep", 1, 9000), ("big/small", 1, 100), ("mid", 1, 3000), ("many", 5, 1), ("many/x", 1, 1)];
+        for (dir, files, size) in dirs {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+            for i in 0..files {
+                std::fs::write(root.join(dir).join(format!("f{}", i)), vec![0u8; size]).unwrap();
+            }
+        }
+        std::fs::write(root.join("top.bin"), vec![0u8; 50]).unwrap();
+        // Files land in the tree's arena in the order they are read.
+        let read_at = |tree: &FileTree, dir: &str| {
+            let file = root.join(dir).join("f0");
+            tree.get_arena().iter().position(|node| node.get().path == file).unwrap()
+        };
+        let mut crawler = FileCrawler::with_options(ScanOptions {
+            order: ScanOrder::LargestFirst,
+            ..ScanOptions::default()
+        });
+
+        // Nothing to go by but how many entries each first-level folder holds.
+        let (tree, _) = crawler.scan_with_progress(&root, None);
+        assert!(read_at(&tree, "many/x") < read_at(&tree, "big/deep"));
+        assert!(read_at(&tree, "many/x") < read_at(&tree, "big/small"));
+
+        // With the previous scan's sizes the biggest folders go first at any depth: all of
+        // `mid` before the small folder inside `big`, which a depth-first walk reads first.
+        for dir in ["big/deep", "big/small", "mid", "many/x"] {
+            std::fs::write(root.join(dir).join("new.bin"), vec![0u8; 5]).unwrap();
+        }
+        crawler.set_previous_scan(tree);
+        let (tree, stats) = crawler.scan_with_progress(&root, None);
+        let _ = std::fs::remove_dir_all(&root);
+        assert!(read_at(&tree, "big/deep") < read_at(&tree, "mid"));
+        assert!(read_at(&tree, "mid") < read_at(&tree, "big/small"));
+        assert!(read_at(&tree, "big/small") < read_at(&tree, "many"));
+        assert_eq!(stats.total_files, 14);
+        assert_eq!(stats.total_dirs, 7);
+        assert_eq!(tree.total_size(), 9000 + 100 + 3000 + 5 + 1 + 50 + 4 * 5);
+    }
+
+    #[test]
+    fn test_live_tree_folds_deep_entries() {
+        let root = PathBuf::from("/data");
+        let mut live = LiveTree::new(FileTree::new(&root));
+        let mut parent = live.tree.get_root();
+        let mut dir = root.clone();
+        for (depth, name) in ["a", "b", "c", "d"].into_iter().enumerate() {
+            dir = dir.join(name);
+            let node = FileNode {
+                path: dir.clone(),
+                is_dir: true,
+                ..FileNode::default()
+            };
+            parent = live.add(parent, depth + 1, &node);
+        }
+        let file = FileNode {
+            path: dir.join("deep.bin"),
+            size: 300,
+            allocated_size: 4096,
+            ..FileNode::default()
+        };
+        live.add(parent, LIVE_TREE_DEPTH + 2, &file);
+        let top = FileNode {
+            path: root.join("top.bin"),
+            size: 50,
+            allocated_size: 50,
+            ..FileNode::default()
+        };
+        live.add(live.tree.get_root(), 1, &top);
+
+        let mut snapshot = live.snapshot(0).unwrap();
+        assert!(live.snapshot(LIVE_TREE_INTERVAL_MS - 1).is_none());
+        assert!(snapshot.get_node(&root.join("a/b/c/d")).is_none());
+        let size_of = |tree: &FileTree, path: &str| {
+            let id = tree.get_node(&root.join(path)).unwrap();
+            tree.get_arena()[id].get().cumulative_size
+        };
+        assert_eq!(size_of(&snapshot, "a/b/c"), 300);
+        assert_eq!(size_of(&snapshot, "a"), 300);
+        assert_eq!(snapshot.total_size(), 350);
+
+        Arc::make_mut(&mut snapshot).set_size_mode(SizeMode::Allocated);
+        assert_eq!(size_of(&snapshot, "a/b/c"), 4096);
+        assert_eq!(live.snapshot(LIVE_TREE_INTERVAL_MS).map(|tree| tree.total_size()), Some(350));
+    }
+
     #[test]
-    fn test_skip_abnormal_docker_vm_path() {
-        let docker_vm_file = Path::new(
-            "/Users/demo/Library/Containers/com.docker.docker/Data/vms/0/data/Docker.raw",
+    fn test_progress_estimate_and_eta() {
+        let estimate = ScanEstimate {
+            entries: 1000,
+            duration_ms: 8000,
+        };
+        let progress = |processed: u64, elapsed_ms: u64| {
+            ScanProgress {
+                phase: ScanPhase::Processing,
+                processed_entries: processed,
+                total_size: processed * 10,
+                ..ScanProgress::default()
+            }
+            .with_rates(Duration::from_millis(elapsed_ms), Some(estimate))
+        };
+
+        // Early on the last scan's duration is all there is to go by.
+        let early = progress(10, 500);
+        assert_eq!(early.eta, Some(Duration::from_millis(7500)));
+        assert!(early.is_estimated());
+
+        let halfway = progress(500, 2000);
+        assert_eq!(halfway.fraction(), Some(0.5));
+        assert_eq!(halfway.entries_per_sec, 250.0);
+        assert_eq!(halfway.bytes_per_sec, 2500.0);
+        assert_eq!(halfway.eta, Some(Duration::from_secs(2)));
+        assert_eq!(halfway.eta_label().as_deref(), Some("2s left"));
+
+        let beyond = progress(1500, 4000);
+        assert_eq!(beyond.fraction(), Some(MAX_ESTIMATED_FRACTION));
+        assert_eq!(beyond.eta, None);
+
+        let unknown = ScanProgress {
+            phase: ScanPhase::Processing,
+            ..ScanProgress::default()
+        }
+        .with_rates(Duration::from_secs(1), None);
+        assert_eq!(unknown.fraction(), None);
+    }
+
+    #[test]
+    fn test_rescan_reuses_unchanged_dirs() {
+        let root = std::env::temp_dir().join(format!("spaceinsight-rescan-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("a")).unwrap();
+        std::fs::create_dir_all(root.join("b/c")).unwrap();
+        std::fs::write(root.join("a/x.log"), vec![0u8; 300]).unwrap();
+        std::fs::write(root.join("b/c/y.txt"), vec![0u8; 200]).unwrap();
+        std::fs::write(root.join("b/z.txt"), vec![0u8; 100]).unwrap();
+
+        let mut crawler = FileCrawler::new();
+        let (first, first_stats) = crawler.scan_with_progress(&root, None);
+        assert_eq!((first_stats.reused_dirs, first_stats.reread_dirs), (0, 4));
+
+        assert_eq!(first_stats.estimate().entries, 7);
+
+        let estimates = Arc::new(Mutex::new(Vec::new()));
+        let sink = estimates.clone();
+        crawler.set_previous_scan(first);
+        let (second, second_stats) = crawler.scan_with_progress(
+            &root,
+            Some(Arc::new(move |p: ScanProgress| sink.lock().unwrap().push(p.estimated_entries))),
         );
-        let normal_file = Path::new("/Users/demo/Documents/test.txt");
+        assert_eq!(estimates.lock().unwrap().first(), Some(&Some(7)));
+        assert_eq!((second_stats.reused_dirs, second_stats.reread_dirs), (4, 0));
+        assert_eq!(second_stats.total_files, 3);
+        assert_eq!(second_stats.total_dirs, first_stats.total_dirs);
+        assert_eq!(second.total_size(), 600);
+        assert_eq!(second_stats.largest_files, first_stats.largest_files);
+        assert_eq!(second_stats.largest_dirs, first_stats.largest_dirs);
+        assert_eq!(second_stats.file_types, first_stats.file_types);
+
+        // Only `c` changed, so `a` is copied and the path down to `c` is listed again.
+        std::fs::write(root.join("b/c/new.txt"), vec![0u8; 50]).unwrap();
+        crawler.set_previous_scan(second);
+        let (third, third_stats) = crawler.scan_with_progress(&root, None);
+        let _ = std::fs::remove_dir_all(&root);
+
+        assert_eq!((third_stats.reused_dirs, third_stats.reread_dirs), (1, 3));
+        assert_eq!(third_stats.total_files, 4);
+        assert_eq!(third.total_size(), 650);
+        assert!(third.get_node(&root.join("a/x.log")).is_some());
+        assert_eq!(third_stats.largest_dirs[0].path, root.join("a"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_policies() {
+        let root = std::env::temp_dir().join(format!("spaceinsight-symlinks-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("data")).unwrap();
+        std::fs::write(root.join("data/blob.bin"), vec![1u8; 4096]).unwrap();
+        std::os::unix::fs::symlink(root.join("data"), root.join("data-link")).unwrap();
+        std::os::unix::fs::symlink(&root, root.join("data/up")).unwrap();
+
+        let scan = |symlinks: SymlinkPolicy| {
+            let mut crawler = FileCrawler::with_options(ScanOptions {
+                symlinks,
+                ..ScanOptions::default()
+            });
+            crawler.scan_with_progress(&root, None)
+        };
+        let (ignored_tree, _) = scan(SymlinkPolicy::Ignore);
+        let (counted_tree, counted_stats) = scan(SymlinkPolicy::CountLink);
+        let (followed_tree, followed_stats) = scan(SymlinkPolicy::Follow);
+        let (ignored, counted, followed) =
+            (entries(&ignored_tree), entries(&counted_tree), entries(&followed_tree));
+        let _ = std::fs::remove_dir_all(&root);
+
+        assert!(!ignored.iter().any(|n| n.symlink_target.is_some()));
+
+        let link = counted.iter().find(|n| n.path.ends_with("data-link")).unwrap();
+        assert!(!link.is_dir);
+        assert_eq!(link.symlink_target.as_deref(), Some(root.join("data").as_path()));
+        assert_eq!(counted_stats.symlinks, 2);
+        let link_bytes: u64 = counted.iter().filter(|n| n.symlink_target.is_some()).map(|n| n.size).sum();
+        assert_eq!(counted_stats.total_size, 4096 + link_bytes);
 
-        assert!(FileCrawler::should_skip_path(docker_vm_file));
-        assert!(!FileCrawler::should_skip_path(normal_file));
+        // `data-link` is followed once; `up` (and `data-link/up`) point at the root and loop.
+        assert!(followed.iter().any(|n| n.path.ends_with("data-link/blob.bin")));
+        let up = followed.iter().find(|n| n.path.ends_with("data/up")).unwrap();
+        assert_eq!(up.placeholder, Some(Placeholder::SymlinkLoop));
+        assert_eq!(followed_stats.symlink_loops, 2);
+        assert_eq!(followed_stats.total_size, 8192);
     }
 }
@@ -0,0 +1,134 @@
+use std::cell::RefCell;
+use std::sync::mpsc::{self, RecvTimeoutError, SendError, Sender};
+use std::sync::Arc;
+use std::time::Duration;
+
+type Job = Box<dyn FnOnce() + Send>;
+
+thread_local! {
+    /// Queue of the helper thread that runs this thread's calls under a deadline.
+    static HELPER: RefCell<Option<Sender<Job>>> = const { RefCell::new(None) };
+}
+
+/// Run `job` on this thread's helper and wait at most `timeout` for it.
+///
+/// `None` means the deadline passed. A call blocked in the kernel (a stat on a
+/// hung NFS or FUSE mount) can't be interrupted, so the job is left running on
+/// a helper that is abandoned, and the next call starts a fresh one.
+pub fn run_with_deadline<T: Send + 'static>(timeout: Duration, job: impl FnOnce() -> T + Send + 'static) -> Option<T> {
+    let (sender, receiver) = mpsc::channel();
+    submit(Box::new(move || {
+        let _ = sender.send(job());
+    }));
+    receive(&receiver, timeout)
+}
+
+/// Apply `f` to every item in order on this thread's helper, giving each call
+/// at most `timeout`. A call that misses it yields `None` and is abandoned as in
+/// `run_with_deadline`; the items after it continue on a fresh helper, so one hung
+/// item costs one timeout and the rest are still read.
+pub fn map_with_deadline<I, T, F>(items: Vec<I>, timeout: Duration, f: F) -> Vec<Option<T>>
+where
+    I: Send + Sync + 'static,
+    T: Send + 'static,
+    F: Fn(&I) -> T + Send + Sync + 'static,
+{
+    let items: Arc<[I]> = items.into();
+    let f = Arc::new(f);
+    let mut results = Vec::with_capacity(items.len());
+    while results.len() < items.len() {
+        let start = results.len();
+        let (sender, receiver) = mpsc::channel();
+        let (batch, f) = (items.clone(), f.clone());
+        submit(Box::new(move || {
+            for item in &batch[start..] {
+                // The caller stops listening once it has given up on an item.
+                if sender.send(f(item)).is_err() {
+                    break;
+                }
+            }
+        }));
+        while results.len() < items.len() {
+            let result = receive(&receiver, timeout);
+            let timed_out = result.is_none();
+            results.push(result);
+            if timed_out {
+                break;
+            }
+        }
+    }
+    results
+}
+
+/// Hand `job` to the helper, starting one if needed. Runs it inline when no
+/// thread can be started, which only loses the deadline.
+fn submit(job: Job) {
+    let unsent = HELPER.with(|helper| {
+        let mut helper = helper.borrow_mut();
+        if helper.is_none() {
+            *helper = spawn_helper();
+        }
+        let sent = match helper.as_ref() {
+            Some(queue) => queue.send(job),
+            None => return Some(job),
+        };
+        sent.err().map(|SendError(job)| {
+            *helper = None;
+            job
+        })
+    });
+    if let Some(job) = unsent {
+        job();
+    }
+}
+
+/// Wait for the next result; on timeout (or a panicking job) the helper is let go.
+fn receive<T>(receiver: &mpsc::Receiver<T>, timeout: Duration) -> Option<T> {
+    match receiver.recv_timeout(timeout) {
+        Ok(value) => Some(value),
+        Err(RecvTimeoutError::Timeout | RecvTimeoutError::Disconnected) => {
+            // Dropping the queue lets the helper exit once its blocked call returns, if ever.
+            HELPER.with(|helper| helper.borrow_mut().take());
+            None
+        }
+    }
+}
+
+fn spawn_helper() -> Option<Sender<Job>> {
+    let (queue, jobs) = mpsc::channel::<Job>();
+    std::thread::Builder::new()
+        .name("spaceinsight-stat".to_string())
+        .spawn(move || {
+            for job in jobs {
+                job();
+            }
+        })
+        .ok()?;
+    Some(queue)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slow_calls_are_abandoned() {
+        let timeout = Duration::from_millis(50);
+        assert_eq!(run_with_deadline(timeout, || 7), Some(7));
+        assert_eq!(run_with_deadline(timeout, || std::thread::sleep(Duration::from_secs(2))), None);
+        assert_eq!(run_with_deadline(timeout, || 8), Some(8));
+
+        let hang = |item: &u64| {
+            if *item == 0 {
+                std::thread::sleep(Duration::from_secs(2));
+            }
+            item * 10
+        };
+        // Only the hung item is given up; the ones after it still get their answer.
+        let timeout = Duration::from_millis(300);
+        let started = std::time::Instant::now();
+        let results = map_with_deadline(vec![1u64, 0, 3, 4], timeout, hang);
+        assert_eq!(results, [Some(10), None, Some(30), Some(40)]);
+        assert!(started.elapsed() < timeout * 2);
+    }
+}
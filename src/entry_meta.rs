@@ -0,0 +1,323 @@
+use serde::{Deserialize, Serialize};
+use std::fs::Metadata;
+
+/// How much per-entry metadata a scan keeps beyond sizes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MetadataLevel {
+    /// Sizes and the directory flag only
+    #[default]
+    Basic,
+    /// Also times, owner, permission bits, inode and file type
+    Full,
+}
+
+impl MetadataLevel {
+    pub fn label(&self) -> &'static str {
+        match self {
+            MetadataLevel::Basic => "basic",
+            MetadataLevel::Full => "full",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            MetadataLevel::Basic => MetadataLevel::Full,
+            MetadataLevel::Full => MetadataLevel::Basic,
+        }
+    }
+}
+
+/// What kind of filesystem object an entry is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FileKind {
+    File,
+    Dir,
+    Symlink,
+    Socket,
+    Fifo,
+    BlockDevice,
+    CharDevice,
+    Other,
+}
+
+impl FileKind {
+    pub fn from_file_type(file_type: std::fs::FileType) -> Self {
+        if file_type.is_dir() {
+            return FileKind::Dir;
+        } else if file_type.is_symlink() {
+            return FileKind::Symlink;
+        } else if file_type.is_file() {
+            return FileKind::File;
+        }
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::FileTypeExt;
+            if file_type.is_socket() {
+                return FileKind::Socket;
+            } else if file_type.is_fifo() {
+                return FileKind::Fifo;
+            } else if file_type.is_block_device() {
+                return FileKind::BlockDevice;
+            } else if file_type.is_char_device() {
+                return FileKind::CharDevice;
+            }
+        }
+
+        FileKind::Other
+    }
+
+    /// Sockets, FIFOs and device nodes: entries with no stored data of their own.
+    pub fn is_special(&self) -> bool {
+        matches!(
+            self,
+            FileKind::Socket | FileKind::Fifo | FileKind::BlockDevice | FileKind::CharDevice
+        )
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            FileKind::File => "file",
+            FileKind::Dir => "directory",
+            FileKind::Symlink => "symlink",
+            FileKind::Socket => "socket",
+            FileKind::Fifo => "fifo",
+            FileKind::BlockDevice => "block device",
+            FileKind::CharDevice => "char device",
+            FileKind::Other => "other",
+        }
+    }
+
+    /// Leading character of `ls -l` output
+    fn mode_char(&self) -> char {
+        match self {
+            FileKind::File => '-',
+            FileKind::Dir => 'd',
+            FileKind::Symlink => 'l',
+            FileKind::Socket => 's',
+            FileKind::Fifo => 'p',
+            FileKind::BlockDevice => 'b',
+            FileKind::CharDevice => 'c',
+            FileKind::Other => '?',
+        }
+    }
+}
+
+/// Last modification time in Unix seconds; zero where the platform can't tell.
+pub fn modified_secs(metadata: &Metadata) -> i64 {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        metadata.mtime()
+    }
+
+    #[cfg(not(unix))]
+    {
+        metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0)
+    }
+}
+
+/// User and group ids that own an entry; zero where the platform has none.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub struct Owner {
+    pub uid: u32,
+    pub gid: u32,
+}
+
+impl Owner {
+    pub fn from_metadata(metadata: &Metadata) -> Self {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            Self {
+                uid: metadata.uid(),
+                gid: metadata.gid(),
+            }
+        }
+
+        #[cfg(not(unix))]
+        {
+            let _ = metadata;
+            Self::default()
+        }
+    }
+}
+
+/// Modification and status-change times of a directory, in nanoseconds since
+/// the Unix epoch. Adding, removing or renaming an entry moves both, so an
+/// unchanged stamp means the listing is the one seen last time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DirStamp {
+    pub modified_ns: i64,
+    /// Zero where the platform has no change time
+    pub changed_ns: i64,
+}
+
+impl DirStamp {
+    pub fn from_metadata(metadata: &Metadata) -> Self {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            let nanos = |secs: i64, nsec: i64| secs.saturating_mul(1_000_000_000).saturating_add(nsec);
+            Self {
+                modified_ns: nanos(metadata.mtime(), metadata.mtime_nsec()),
+                changed_ns: nanos(metadata.ctime(), metadata.ctime_nsec()),
+            }
+        }
+
+        #[cfg(not(unix))]
+        {
+            let modified_ns = metadata
+                .modified()
+                .ok()
+                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|d| d.as_nanos() as i64)
+                .unwrap_or(0);
+            Self {
+                modified_ns,
+                changed_ns: 0,
+            }
+        }
+    }
+}
+
+/// Metadata recorded under `MetadataLevel::Full`.
+///
+/// Times are whole seconds since the Unix epoch. Fields the platform doesn't
+/// expose (owner, mode and inode outside Unix) are zero.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EntryMeta {
+    pub modified: i64,
+    pub accessed: i64,
+    pub inode: u64,
+    pub uid: u32,
+    pub gid: u32,
+    /// Permission bits (`st_mode & 0o7777`)
+    pub mode: u32,
+    pub kind: FileKind,
+}
+
+impl EntryMeta {
+    pub fn from_metadata(metadata: &Metadata) -> Self {
+        let kind = FileKind::from_file_type(metadata.file_type());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            Self {
+                modified: metadata.mtime(),
+                accessed: metadata.atime(),
+                inode: metadata.ino(),
+                uid: metadata.uid(),
+                gid: metadata.gid(),
+                mode: metadata.mode() & 0o7777,
+                kind,
+            }
+        }
+
+        #[cfg(not(unix))]
+        {
+            let seconds = |time: std::io::Result<std::time::SystemTime>| {
+                time.ok()
+                    .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                    .map(|d| d.as_secs() as i64)
+                    .unwrap_or(0)
+            };
+            Self {
+                modified: seconds(metadata.modified()),
+                accessed: seconds(metadata.accessed()),
+                inode: 0,
+                uid: 0,
+                gid: 0,
+                mode: 0,
+                kind,
+            }
+        }
+    }
+
+    /// Permissions in `ls -l` form, e.g. `drwxr-xr-x`.
+    pub fn mode_string(&self) -> String {
+        let mut text = String::with_capacity(10);
+        text.push(self.kind.mode_char());
+        let special = [(0o4000, 's'), (0o2000, 's'), (0o1000, 't')];
+        for (shift, (special_bit, special_char)) in [6u32, 3, 0].into_iter().zip(special) {
+            let bits = (self.mode >> shift) & 0o7;
+            text.push(if bits & 0o4 != 0 { 'r' } else { '-' });
+            text.push(if bits & 0o2 != 0 { 'w' } else { '-' });
+            let exec = bits & 0o1 != 0;
+            text.push(match (self.mode & special_bit != 0, exec) {
+                (true, true) => special_char,
+                (true, false) => special_char.to_ascii_uppercase(),
+                (false, true) => 'x',
+                (false, false) => '-',
+            });
+        }
+        text
+    }
+}
+
+/// `YYYY-MM-DD HH:MM` in UTC.
+pub fn format_timestamp(seconds: i64) -> String {
+    let days = seconds.div_euclid(86_400);
+    let secs_of_day = seconds.rem_euclid(86_400);
+
+    // Civil-from-days (Howard Hinnant's algorithm), valid for the proleptic Gregorian calendar.
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        year,
+        month,
+        day,
+        secs_of_day / 3600,
+        (secs_of_day % 3600) / 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meta(mode: u32, kind: FileKind) -> EntryMeta {
+        EntryMeta {
+            modified: 0,
+            accessed: 0,
+            inode: 1,
+            uid: 0,
+            gid: 0,
+            mode,
+            kind,
+        }
+    }
+
+    #[test]
+    fn test_mode_string() {
+        assert_eq!(meta(0o755, FileKind::Dir).mode_string(), "drwxr-xr-x");
+        assert_eq!(meta(0o644, FileKind::File).mode_string(), "-rw-r--r--");
+        assert_eq!(meta(0o4755, FileKind::File).mode_string(), "-rwsr-xr-x");
+        assert_eq!(meta(0o1777, FileKind::Dir).mode_string(), "drwxrwxrwt");
+        assert_eq!(meta(0o2640, FileKind::File).mode_string(), "-rw-r-S---");
+        assert_eq!(meta(0o666, FileKind::CharDevice).mode_string(), "crw-rw-rw-");
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00");
+        assert_eq!(format_timestamp(951_782_400), "2000-02-29 00:00");
+        assert_eq!(format_timestamp(1_700_000_000), "2023-11-14 22:13");
+        assert_eq!(format_timestamp(-86_400), "1969-12-31 00:00");
+    }
+}
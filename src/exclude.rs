@@ -0,0 +1,211 @@
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use std::ffi::OsStr;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Docker Desktop's VM disk image: a sparse file that reports a huge bogus size.
+const DOCKER_VM_GLOB: &str = "**/Library/Containers/com.docker.docker/Data/vms";
+
+/// A single reason to leave a path out of a scan.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExcludeRule {
+    /// Glob matched against the full path, e.g. `**/*.iso`
+    Glob(String),
+    /// Absolute path; it and everything below it is excluded
+    PathPrefix(PathBuf),
+    /// Exact file or directory name anywhere in the tree, e.g. `node_modules`
+    Name(String),
+}
+
+impl ExcludeRule {
+    /// Interpret user input: absolute paths become prefixes, anything with glob
+    /// metacharacters a glob, and everything else a plain name.
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        if text.is_empty() {
+            return None;
+        }
+
+        let rule = if text.contains(['*', '?', '[', '{']) {
+            ExcludeRule::Glob(text.to_string())
+        } else if Path::new(text).is_absolute() {
+            ExcludeRule::PathPrefix(PathBuf::from(text))
+        } else {
+            ExcludeRule::Name(text.to_string())
+        };
+        Some(rule)
+    }
+}
+
+impl fmt::Display for ExcludeRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExcludeRule::Glob(pattern) => write!(f, "{}", pattern),
+            ExcludeRule::PathPrefix(path) => write!(f, "{}", path.display()),
+            ExcludeRule::Name(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// The set of exclude rules a scan honours, with globs compiled once up front.
+#[derive(Debug, Clone)]
+pub struct ExcludeRules {
+    rules: Vec<ExcludeRule>,
+    globs: GlobSet,
+    /// Index into `rules` for each compiled glob
+    glob_rules: Vec<usize>,
+    /// Files smaller than this many bytes are dropped from the tree (0 keeps everything)
+    pub min_file_size: u64,
+}
+
+impl ExcludeRules {
+    pub fn new(rules: Vec<ExcludeRule>) -> Result<Self, globset::Error> {
+        let mut builder = GlobSetBuilder::new();
+        let mut glob_rules = Vec::new();
+        for (index, rule) in rules.iter().enumerate() {
+            if let ExcludeRule::Glob(pattern) = rule {
+                builder.add(Glob::new(pattern)?);
+                glob_rules.push(index);
+            }
+        }
+
+        Ok(Self {
+            rules,
+            globs: builder.build()?,
+            glob_rules,
+            min_file_size: 0,
+        })
+    }
+
+    /// No rules at all, not even the built-in defaults.
+    pub fn empty() -> Self {
+        Self {
+            rules: Vec::new(),
+            globs: GlobSet::empty(),
+            glob_rules: Vec::new(),
+            min_file_size: 0,
+        }
+    }
+
+    pub fn rules(&self) -> &[ExcludeRule] {
+        &self.rules
+    }
+
+    /// Add a rule, rejecting globs that don't compile.
+    pub fn push(&mut self, rule: ExcludeRule) -> Result<(), globset::Error> {
+        let mut rules = self.rules.clone();
+        rules.push(rule);
+        self.replace_rules(rules)
+    }
+
+    pub fn remove(&mut self, index: usize) {
+        if index < self.rules.len() {
+            let mut rules = self.rules.clone();
+            rules.remove(index);
+            // Every remaining glob already compiled once.
+            let _ = self.replace_rules(rules);
+        }
+    }
+
+    fn replace_rules(&mut self, rules: Vec<ExcludeRule>) -> Result<(), globset::Error> {
+        let min_file_size = self.min_file_size;
+        *self = Self::new(rules)?;
+        self.min_file_size = min_file_size;
+        Ok(())
+    }
+
+    /// The first rule excluding `path`, if any.
+    pub fn matching_rule(&self, path: &Path) -> Option<&ExcludeRule> {
+        let name = path.file_name();
+        let direct = self.rules.iter().find(|rule| match rule {
+            ExcludeRule::PathPrefix(prefix) => path.starts_with(prefix),
+            ExcludeRule::Name(expected) => name == Some(OsStr::new(expected)),
+            ExcludeRule::Glob(_) => false,
+        });
+        if direct.is_some() {
+            return direct;
+        }
+
+        if self.glob_rules.is_empty() {
+            return None;
+        }
+        let matched = self.globs.matches(path);
+        matched
+            .first()
+            .map(|glob_index| &self.rules[self.glob_rules[*glob_index]])
+    }
+
+    pub fn is_excluded(&self, path: &Path) -> bool {
+        self.matching_rule(path).is_some()
+    }
+
+    /// Whether a file of `size` bytes falls under the size cutoff.
+    pub fn prunes_size(&self, size: u64) -> bool {
+        size < self.min_file_size
+    }
+}
+
+impl Default for ExcludeRules {
+    fn default() -> Self {
+        Self::new(vec![ExcludeRule::Glob(DOCKER_VM_GLOB.to_string())])
+            .expect("built-in exclude globs compile")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rule_kinds() {
+        assert_eq!(
+            ExcludeRule::parse("node_modules"),
+            Some(ExcludeRule::Name("node_modules".to_string()))
+        );
+        assert_eq!(
+            ExcludeRule::parse(" /var/cache "),
+            Some(ExcludeRule::PathPrefix(PathBuf::from("/var/cache")))
+        );
+        assert_eq!(
+            ExcludeRule::parse("**/*.iso"),
+            Some(ExcludeRule::Glob("**/*.iso".to_string()))
+        );
+        assert_eq!(ExcludeRule::parse("   "), None);
+    }
+
+    #[test]
+    fn test_matching_rules() {
+        let rules = ExcludeRules::new(vec![
+            ExcludeRule::Name(".git".to_string()),
+            ExcludeRule::PathPrefix(PathBuf::from("/data/scratch")),
+            ExcludeRule::Glob("**/*.iso".to_string()),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            rules.matching_rule(Path::new("/home/me/project/.git")),
+            Some(&ExcludeRule::Name(".git".to_string()))
+        );
+        assert!(rules.is_excluded(Path::new("/data/scratch/tmp")));
+        assert!(!rules.is_excluded(Path::new("/data/scratchpad")));
+        assert!(rules.is_excluded(Path::new("/downloads/debian.iso")));
+        assert!(!rules.is_excluded(Path::new("/home/me/project/.github")));
+    }
+
+    #[test]
+    fn test_invalid_glob_is_rejected() {
+        let mut rules = ExcludeRules::empty();
+        assert!(rules.push(ExcludeRule::Glob("[".to_string())).is_err());
+        assert!(rules.rules().is_empty());
+    }
+
+    #[test]
+    fn test_skip_abnormal_docker_vm_path() {
+        let docker_vm_dir = Path::new("/Users/demo/Library/Containers/com.docker.docker/Data/vms");
+        let normal_file = Path::new("/Users/demo/Documents/test.txt");
+
+        let rules = ExcludeRules::default();
+        assert!(rules.is_excluded(docker_vm_dir));
+        assert!(!rules.is_excluded(normal_file));
+    }
+}
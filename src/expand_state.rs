@@ -2,18 +2,11 @@ use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
 /// Tracks which folders are expanded and to what depth.
+#[derive(Default)]
 pub struct ExpansionState {
     expanded: HashMap<PathBuf, u8>, // path -> expansion depth
 }
 
-impl Default for ExpansionState {
-    fn default() -> Self {
-        Self {
-            expanded: HashMap::new(),
-        }
-    }
-}
-
 impl ExpansionState {
     /// Expand a folder to depth 1 (single-click).
     pub fn expand(&mut self, path: &Path) {
@@ -0,0 +1,302 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::tree::SizeMode;
+
+/// Broad kind of a file, decided by its extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum FileCategory {
+    Media,      // Images, video, audio
+    Code,       // Source files
+    Archive,    // Compressed archives
+    Document,   // Text, PDF, etc.
+    System,     // Hidden, system files
+    Other,
+}
+
+impl FileCategory {
+    pub fn label(&self) -> &'static str {
+        match self {
+            FileCategory::Media => "media",
+            FileCategory::Code => "code",
+            FileCategory::Archive => "archive",
+            FileCategory::Document => "document",
+            FileCategory::System => "system",
+            FileCategory::Other => "other",
+        }
+    }
+
+    /// Get color hue for category (0-360 degrees)
+    pub fn hue_degrees(&self) -> f32 {
+        match self {
+            FileCategory::Media => 30.0,       // Warm amber/orange
+            FileCategory::Code => 220.0,       // Cool blue
+            FileCategory::Archive => 270.0,    // Purple
+            FileCategory::Document => 160.0,   // Teal
+            FileCategory::System => 240.0,     // Deep blue
+            FileCategory::Other => 0.0,        // Red
+        }
+    }
+
+    /// Classify file by extension
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) => Self::from_extension(&ext.to_lowercase()),
+            None => {
+                // Check for hidden/system files
+                if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
+                    if name.starts_with('.') {
+                        return FileCategory::System;
+                    }
+                }
+                FileCategory::Other
+            }
+        }
+    }
+
+    /// Classify a lowercase extension without its dot
+    pub fn from_extension(ext: &str) -> Self {
+        match ext {
+            // Media
+            "jpg" | "jpeg" | "png" | "gif" | "bmp" | "svg" | "webp" |
+            "mp4" | "mov" | "avi" | "mkv" | "flv" | "wmv" |
+            "mp3" | "wav" | "flac" | "aac" | "ogg" | "m4a" => FileCategory::Media,
+
+            // Code
+            "rs" | "py" | "js" | "ts" | "jsx" | "tsx" | "cpp" | "c" | "h" | "hpp" |
+            "java" | "go" | "rb" | "php" | "swift" | "kt" | "cs" | "m" | "mm" |
+            "html" | "css" | "scss" | "sass" | "vue" | "svelte" => FileCategory::Code,
+
+            // Archives
+            "zip" | "tar" | "gz" | "bz2" | "xz" | "7z" | "rar" | "dmg" | "iso" => FileCategory::Archive,
+
+            // Documents
+            "pdf" | "doc" | "docx" | "txt" | "md" | "rtf" | "odt" |
+            "xls" | "xlsx" | "csv" | "ppt" | "pptx" => FileCategory::Document,
+
+            _ => FileCategory::Other,
+        }
+    }
+}
+
+/// Lowercase extension of `path` without the dot; empty when it has none.
+pub fn extension_key(path: &Path) -> String {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+/// How an extension key reads in a table, e.g. `.mp4` or `(no extension)`.
+pub fn extension_label(key: &str) -> String {
+    if key.is_empty() {
+        "(no extension)".to_string()
+    } else {
+        format!(".{}", key)
+    }
+}
+
+/// File count and bytes of one extension or category.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UsageTotals {
+    pub files: u64,
+    pub size: u64,
+    pub allocated_size: u64,
+}
+
+impl UsageTotals {
+    pub fn size_in(&self, mode: SizeMode) -> u64 {
+        match mode {
+            SizeMode::Apparent => self.size,
+            SizeMode::Allocated => self.allocated_size,
+        }
+    }
+
+    pub(crate) fn add(&mut self, size: u64, allocated_size: u64) {
+        self.files += 1;
+        self.size += size;
+        self.allocated_size += allocated_size;
+    }
+
+    fn add_totals(&mut self, other: &UsageTotals) {
+        self.files += other.files;
+        self.size += other.size;
+        self.allocated_size += other.allocated_size;
+    }
+
+    fn subtract_totals(&mut self, other: &UsageTotals) {
+        self.files = self.files.saturating_sub(other.files);
+        self.size = self.size.saturating_sub(other.size);
+        self.allocated_size = self.allocated_size.saturating_sub(other.allocated_size);
+    }
+}
+
+/// Charged files of a folder or a whole scan, totalled per extension and per category.
+/// Shared hardlinks and special files are left out, as in the folder sizes.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TypeBreakdown {
+    pub by_extension: HashMap<String, UsageTotals>,
+    pub by_category: HashMap<FileCategory, UsageTotals>,
+}
+
+impl TypeBreakdown {
+    pub fn add(&mut self, path: &Path, size: u64, allocated_size: u64) {
+        let category = match path.extension().and_then(|e| e.to_str()) {
+            // The common lowercase case needs no key allocation once the extension has been seen.
+            Some(ext) if !ext.bytes().any(|b| b.is_ascii_uppercase()) => {
+                match self.by_extension.get_mut(ext) {
+                    Some(totals) => totals.add(size, allocated_size),
+                    None => self.by_extension.entry(ext.to_string()).or_default().add(size, allocated_size),
+                }
+                FileCategory::from_extension(ext)
+            }
+            _ => {
+                let key = extension_key(path);
+                let category = if key.is_empty() {
+                    FileCategory::from_path(path)
+                } else {
+                    FileCategory::from_extension(&key)
+                };
+                self.by_extension.entry(key).or_default().add(size, allocated_size);
+                category
+            }
+        };
+        self.by_category.entry(category).or_default().add(size, allocated_size);
+    }
+
+    /// Take in the totals of another breakdown, as a folder does its subfolders'.
+    pub fn add_breakdown(&mut self, other: &TypeBreakdown) {
+        for (key, totals) in &other.by_extension {
+            match self.by_extension.get_mut(key) {
+                Some(ours) => ours.add_totals(totals),
+                None => {
+                    self.by_extension.insert(key.clone(), *totals);
+                }
+            }
+        }
+        for (category, totals) in &other.by_category {
+            self.by_category.entry(*category).or_default().add_totals(totals);
+        }
+    }
+
+    /// Take out totals added earlier; extensions and categories left without files are dropped.
+    pub fn subtract_breakdown(&mut self, other: &TypeBreakdown) {
+        for (key, totals) in &other.by_extension {
+            if let Some(ours) = self.by_extension.get_mut(key) {
+                ours.subtract_totals(totals);
+                if ours.files == 0 {
+                    self.by_extension.remove(key);
+                }
+            }
+        }
+        for (category, totals) in &other.by_category {
+            if let Some(ours) = self.by_category.get_mut(category) {
+                ours.subtract_totals(totals);
+                if ours.files == 0 {
+                    self.by_category.remove(category);
+                }
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_category.is_empty()
+    }
+
+    pub fn total(&self) -> UsageTotals {
+        self.by_category.values().fold(UsageTotals::default(), |acc, totals| UsageTotals {
+            files: acc.files + totals.files,
+            size: acc.size + totals.size,
+            allocated_size: acc.allocated_size + totals.allocated_size,
+        })
+    }
+
+    /// Extensions, largest first in `mode`; ties go alphabetically.
+    pub fn extensions_by_size(&self, mode: SizeMode) -> Vec<(&str, UsageTotals)> {
+        let mut rows: Vec<_> = self
+            .by_extension
+            .iter()
+            .map(|(key, totals)| (key.as_str(), *totals))
+            .collect();
+        rows.sort_by(|a, b| b.1.size_in(mode).cmp(&a.1.size_in(mode)).then(a.0.cmp(b.0)));
+        rows
+    }
+
+    /// Categories that hold any files, largest first in `mode`.
+    pub fn categories_by_size(&self, mode: SizeMode) -> Vec<(FileCategory, UsageTotals)> {
+        let mut rows: Vec<_> = self
+            .by_category
+            .iter()
+            .map(|(category, totals)| (*category, *totals))
+            .collect();
+        rows.sort_by(|a, b| b.1.size_in(mode).cmp(&a.1.size_in(mode)).then(a.0.cmp(&b.0)));
+        rows
+    }
+}
+
+/// Share of `part` in `whole` as a percentage, 0 for an empty whole.
+pub fn percent_of(part: u64, whole: u64) -> f64 {
+    if whole == 0 {
+        0.0
+    } else {
+        part as f64 * 100.0 / whole as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_category_classification() {
+        assert_eq!(FileCategory::from_path(Path::new("image.jpg")), FileCategory::Media);
+        assert_eq!(FileCategory::from_path(Path::new("MOVIE.MP4")), FileCategory::Media);
+        assert_eq!(FileCategory::from_path(Path::new("code.rs")), FileCategory::Code);
+        assert_eq!(FileCategory::from_path(Path::new("archive.zip")), FileCategory::Archive);
+        assert_eq!(FileCategory::from_path(Path::new("doc.pdf")), FileCategory::Document);
+        assert_eq!(FileCategory::from_path(Path::new(".gitignore")), FileCategory::System);
+        assert_eq!(FileCategory::from_path(Path::new("Makefile")), FileCategory::Other);
+    }
+
+    #[test]
+    fn test_breakdown_ranks_extensions() {
+        let mut breakdown = TypeBreakdown::default();
+        breakdown.add(Path::new("/a/movie.mp4"), 400, 400);
+        breakdown.add(Path::new("/a/clip.MP4"), 400, 400);
+        breakdown.add(Path::new("/a/app.log"), 500, 4096);
+        breakdown.add(Path::new("/a/.bashrc"), 100, 512);
+
+        let extensions = breakdown.extensions_by_size(SizeMode::Apparent);
+        assert_eq!(extensions[0].0, "mp4");
+        assert_eq!(extensions[0].1.files, 2);
+        assert_eq!(extensions_labels(&extensions), [".mp4", ".log", "(no extension)"]);
+        assert_eq!(breakdown.extensions_by_size(SizeMode::Allocated)[0].0, "log");
+
+        let total = breakdown.total();
+        assert_eq!((total.files, total.size), (4, 1400));
+        assert_eq!(breakdown.by_category[&FileCategory::System].size, 100);
+        assert_eq!(breakdown.categories_by_size(SizeMode::Apparent)[0].0, FileCategory::Media);
+        assert_eq!(percent_of(extensions[0].1.size, total.size).round(), 57.0);
+    }
+
+    #[test]
+    fn test_breakdowns_add_and_subtract() {
+        let mut logs = TypeBreakdown::default();
+        logs.add(Path::new("/a/logs/app.log"), 500, 4096);
+        let mut all = TypeBreakdown::default();
+        all.add(Path::new("/a/movie.mp4"), 400, 400);
+        all.add_breakdown(&logs);
+        all.add_breakdown(&logs);
+        assert_eq!(all.by_extension["log"].files, 2);
+        assert_eq!(all.total().size, 1400);
+
+        all.subtract_breakdown(&logs);
+        all.subtract_breakdown(&logs);
+        assert!(!all.by_extension.contains_key("log"));
+        assert!(!all.by_category.contains_key(&FileCategory::System));
+        assert_eq!(all.total().files, 1);
+    }
+
+    fn extensions_labels(rows: &[(&str, UsageTotals)]) -> Vec<String> {
+        rows.iter().map(|(key, _)| extension_label(key)).collect()
+    }
+}
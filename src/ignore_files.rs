@@ -0,0 +1,191 @@
+use ignore::gitignore::{gitconfig_excludes_path, Gitignore, GitignoreBuilder};
+use ignore::Match;
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Entries of a directory that change the ignore rules in effect below it.
+const RULE_FILES: [&str; 3] = [".git", ".gitignore", ".ignore"];
+
+/// How `.gitignore`, `.ignore` and git's global excludes affect a scan.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IgnoreMode {
+    /// Ignore files are not consulted
+    #[default]
+    Off,
+    /// Ignored entries are left out of the tree entirely
+    Skip,
+    /// Ignored entries are scanned but flagged, so tracked and ignored bytes can be told apart
+    Tag,
+}
+
+impl IgnoreMode {
+    pub fn label(&self) -> &'static str {
+        match self {
+            IgnoreMode::Off => "off",
+            IgnoreMode::Skip => "skip ignored",
+            IgnoreMode::Tag => "tag ignored",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            IgnoreMode::Off => IgnoreMode::Skip,
+            IgnoreMode::Skip => IgnoreMode::Tag,
+            IgnoreMode::Tag => IgnoreMode::Off,
+        }
+    }
+}
+
+/// Ignore matchers in effect for one directory, innermost last.
+///
+/// Carried through the walk as jwalk's per-directory state: every directory
+/// inherits its parent's stack and pushes its own ignore files on top.
+/// `.gitignore` and the global/`info/exclude` rules only apply inside a git
+/// repository; `.ignore` files apply everywhere.
+#[derive(Debug, Clone, Default)]
+pub struct IgnoreStack {
+    matchers: Vec<Arc<Gitignore>>,
+    /// Whether the directory itself is ignored (everything below it is too)
+    ignored: bool,
+    in_repo: bool,
+    /// Walker paths may be relative; matchers are rooted at absolute paths.
+    scan_root: PathBuf,
+    absolute_root: PathBuf,
+    global_excludes: Option<PathBuf>,
+}
+
+impl IgnoreStack {
+    /// Stack for the directories above `scan_root`, so a scan started inside a
+    /// repository still honours the ignore files between it and the repo root.
+    pub fn for_scan_root(scan_root: &Path) -> Self {
+        let absolute_root = if scan_root.is_absolute() {
+            scan_root.to_path_buf()
+        } else {
+            std::fs::canonicalize(scan_root).unwrap_or_else(|_| scan_root.to_path_buf())
+        };
+        let mut stack = Self {
+            scan_root: scan_root.to_path_buf(),
+            absolute_root: absolute_root.clone(),
+            global_excludes: gitconfig_excludes_path().filter(|path| path.is_file()),
+            ..Self::default()
+        };
+
+        let Some(parent) = absolute_root.parent() else {
+            return stack;
+        };
+        let Some(repo_root) = parent.ancestors().find(|dir| dir.join(".git").exists()) else {
+            return stack;
+        };
+
+        let mut dirs: Vec<&Path> = parent
+            .ancestors()
+            .take_while(|dir| *dir != repo_root)
+            .collect();
+        dirs.push(repo_root);
+        for dir in dirs.into_iter().rev() {
+            stack.enter_absolute(dir, Self::rule_files_in(dir));
+        }
+        stack
+    }
+
+    /// Stack in effect for the entries of `dir`, a directory below `scan_root`,
+    /// as a walk from `scan_root` builds it on the way down.
+    pub fn for_dir(scan_root: &Path, dir: &Path) -> Self {
+        let mut stack = Self::for_scan_root(scan_root);
+        let Ok(rel) = dir.strip_prefix(scan_root) else {
+            return stack;
+        };
+        let mut current = scan_root.to_path_buf();
+        stack.enter(&current, Self::rule_files_in(&current));
+        for component in rel.components() {
+            current.push(component);
+            stack.enter(&current, Self::rule_files_in(&current));
+        }
+        stack
+    }
+
+    fn rule_files_in(dir: &Path) -> impl Iterator<Item = &'static str> + '_ {
+        RULE_FILES.into_iter().filter(|name| dir.join(name).exists())
+    }
+
+    /// Descend into `dir`, whose entries include `names`.
+    pub fn enter<'a>(&mut self, dir: &Path, names: impl Iterator<Item = &'a str>) {
+        let absolute = self.absolute(dir).into_owned();
+        self.enter_absolute(&absolute, names);
+    }
+
+    fn enter_absolute<'a>(&mut self, dir: &Path, names: impl Iterator<Item = &'a str>) {
+        self.ignored = self.ignored || self.matches(dir, true);
+
+        let mut has_gitignore = false;
+        let mut has_ignore = false;
+        for name in names {
+            match name {
+                ".git" => {
+                    // A nested repository is governed only by its own rules.
+                    self.matchers.clear();
+                    self.in_repo = true;
+                    let mut builder = GitignoreBuilder::new(dir);
+                    if let Some(global) = &self.global_excludes {
+                        builder.add(global);
+                    }
+                    let info_exclude = dir.join(".git/info/exclude");
+                    if info_exclude.is_file() {
+                        builder.add(info_exclude);
+                    }
+                    self.push(builder);
+                }
+                ".gitignore" => has_gitignore = true,
+                ".ignore" => has_ignore = true,
+                _ => {}
+            }
+        }
+
+        if has_gitignore && self.in_repo {
+            let mut builder = GitignoreBuilder::new(dir);
+            builder.add(dir.join(".gitignore"));
+            self.push(builder);
+        }
+        if has_ignore {
+            let mut builder = GitignoreBuilder::new(dir);
+            builder.add(dir.join(".ignore"));
+            self.push(builder);
+        }
+    }
+
+    fn push(&mut self, builder: GitignoreBuilder) {
+        if let Ok(matcher) = builder.build() {
+            if !matcher.is_empty() {
+                self.matchers.push(Arc::new(matcher));
+            }
+        }
+    }
+
+    /// Whether an entry of the current directory is ignored.
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        self.ignored || self.matches(&self.absolute(path), is_dir)
+    }
+
+    fn matches(&self, path: &Path, is_dir: bool) -> bool {
+        // Innermost rules win; a whitelist (`!pattern`) stops the search.
+        for matcher in self.matchers.iter().rev() {
+            match matcher.matched(path, is_dir) {
+                Match::Ignore(_) => return true,
+                Match::Whitelist(_) => return false,
+                Match::None => {}
+            }
+        }
+        false
+    }
+
+    fn absolute<'p>(&self, path: &'p Path) -> Cow<'p, Path> {
+        if path.is_absolute() {
+            return Cow::Borrowed(path);
+        }
+        match path.strip_prefix(&self.scan_root) {
+            Ok(rel) => Cow::Owned(self.absolute_root.join(rel)),
+            Err(_) => Cow::Borrowed(path),
+        }
+    }
+}
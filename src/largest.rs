@@ -0,0 +1,106 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::path::{Path, PathBuf};
+
+use crate::tree::SizeMode;
+
+/// One row of a "largest files" or "largest folders" list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LargestEntry {
+    pub path: PathBuf,
+    pub size: u64,
+    pub allocated_size: u64,
+}
+
+impl LargestEntry {
+    pub fn size_in(&self, mode: SizeMode) -> u64 {
+        match mode {
+            SizeMode::Apparent => self.size,
+            SizeMode::Allocated => self.allocated_size,
+        }
+    }
+}
+
+/// Swap the entries under `dir` for those of a fresh scan of it, keeping the list largest first.
+pub fn replace_under(entries: &mut Vec<LargestEntry>, dir: &Path, fresh: Vec<LargestEntry>) {
+    let limit = entries.len().max(fresh.len());
+    entries.retain(|entry| !entry.path.starts_with(dir));
+    entries.extend(fresh);
+    entries.sort_by_key(|entry| Reverse(entry.size));
+    entries.truncate(limit);
+}
+
+/// Heap key: size, then offer order reversed so earlier entries win ties.
+type Ranked = (u64, Reverse<u64>, PathBuf, u64);
+
+/// Bounded min-heap holding the `limit` largest entries offered so far,
+/// ranked by apparent size. Ties keep the entry offered first.
+#[derive(Debug, Clone)]
+pub(crate) struct TopN {
+    limit: usize,
+    heap: BinaryHeap<Reverse<Ranked>>,
+    offered: u64,
+}
+
+impl TopN {
+    pub(crate) fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            heap: BinaryHeap::with_capacity(limit + 1),
+            offered: 0,
+        }
+    }
+
+    /// Whether an entry of this size would make the list; lets callers skip building its path.
+    pub(crate) fn would_keep(&self, size: u64) -> bool {
+        if self.limit == 0 {
+            return false;
+        }
+        self.heap.len() < self.limit || self.heap.peek().is_some_and(|Reverse(smallest)| size > smallest.0)
+    }
+
+    pub(crate) fn offer(&mut self, path: PathBuf, size: u64, allocated_size: u64) {
+        if !self.would_keep(size) {
+            return;
+        }
+        self.offered += 1;
+        self.heap.push(Reverse((size, Reverse(self.offered), path, allocated_size)));
+        if self.heap.len() > self.limit {
+            self.heap.pop();
+        }
+    }
+
+    /// The kept entries, largest first.
+    pub(crate) fn sorted(&self) -> Vec<LargestEntry> {
+        let mut entries: Vec<_> = self.heap.iter().map(|Reverse(entry)| entry).collect();
+        entries.sort_by(|a, b| b.cmp(a));
+        entries
+            .into_iter()
+            .map(|(size, _, path, allocated_size)| LargestEntry {
+                path: path.clone(),
+                size: *size,
+                allocated_size: *allocated_size,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keeps_largest_in_order() {
+        let mut top = TopN::new(3);
+        for (name, size) in [("a", 5), ("b", 50), ("c", 1), ("d", 20), ("e", 50), ("f", 2)] {
+            top.offer(PathBuf::from(name), size, size * 2);
+        }
+        assert!(!top.would_keep(20));
+        assert!(top.would_keep(21));
+
+        let sorted = top.sorted();
+        let names: Vec<_> = sorted.iter().map(|e| e.path.to_str().unwrap()).collect();
+        assert_eq!(names, ["b", "e", "d"]);
+        assert_eq!(sorted[2].size_in(SizeMode::Allocated), 40);
+    }
+}
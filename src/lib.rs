@@ -1,6 +1,17 @@
+pub mod age;
 pub mod animation;
 pub mod crawler;
+pub mod deadline;
+pub mod entry_meta;
+pub mod exclude;
 pub mod expand_state;
+pub mod file_types;
+pub mod ignore_files;
+pub mod largest;
+pub mod mounts;
+pub mod owners;
 pub mod render_tree;
+pub mod scan_cache;
+pub mod throttle;
 pub mod tree;
 pub mod treemap;
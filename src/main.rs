@@ -8,13 +8,26 @@ use std::sync::Arc;
 use std::thread;
 use std::time::Instant;
 
+use spaceinsight::age::DEFAULT_STALE_DAYS;
 use spaceinsight::animation::LayoutAnimator;
-use spaceinsight::crawler::{FileCrawler, ScanPhase, ScanProgress, ScanStats, ScanTopLevelPreview};
+use spaceinsight::crawler::{
+    read_timeout_label, CancelToken, FileCrawler, ScanError, ScanOptions, ScanOrder, ScanProgress,
+    ScanStats, ScanTopLevelPreview, SymlinkPolicy, READ_TIMEOUT_CHOICES,
+};
+use spaceinsight::entry_meta::{format_timestamp, FileKind, MetadataLevel};
+use spaceinsight::exclude::ExcludeRule;
 use spaceinsight::expand_state::ExpansionState;
+use spaceinsight::file_types::{extension_label, percent_of};
+use spaceinsight::ignore_files::IgnoreMode;
+use spaceinsight::largest::{self, LargestEntry};
+use spaceinsight::mounts::VirtualFsPolicy;
+use spaceinsight::owners::{OwnerNames, OwnerUsage};
 use spaceinsight::render_tree::{
     build_render_tree, RenderNode, BORDER_VISUAL_WIDTH, HEADER_HEIGHT, SIDE_INSET,
 };
-use spaceinsight::tree::FileTree;
+use spaceinsight::scan_cache::ScanCache;
+use spaceinsight::throttle::ScanProfile;
+use spaceinsight::tree::{FileTree, Placeholder, SizeMode, TreeNode};
 use spaceinsight::treemap::{Rect, SquarifiedTreemap, TreemapItem};
 
 const TILE_GUTTER: f32 = 1.0;
@@ -43,7 +56,20 @@ fn main() -> Result<(), eframe::Error> {
         options,
         Box::new(|cc| {
             configure_custom_style(&cc.egui_ctx);
-            Box::new(SpaceInsightApp::default())
+            let mut app = SpaceInsightApp {
+                stale_days: DEFAULT_STALE_DAYS,
+                remember_scans: true,
+                ..SpaceInsightApp::default()
+            };
+            // Paths given on the command line are scanned right away, side by side.
+            let paths: Vec<PathBuf> = std::env::args_os().skip(1).map(PathBuf::from).collect();
+            if let Ok(joined) = std::env::join_paths(&paths) {
+                if !paths.is_empty() {
+                    app.scan_path = joined.to_string_lossy().into_owned();
+                    app.start_scan();
+                }
+            }
+            Box::new(app)
         }),
     )
 }
@@ -87,11 +113,12 @@ enum ClickAction {
     SelectFile(PathBuf),
     OpenPath(PathBuf),
     OpenInFileManager { path: PathBuf, is_dir: bool },
+    Rescan(PathBuf),
 }
 
 enum ScanEvent {
-    Progress(ScanProgress),
-    Completed(ScanResult),
+    Progress(Box<ScanProgress>),
+    Completed(Box<ScanResult>),
 }
 
 struct LayoutTransition {
@@ -100,20 +127,60 @@ struct LayoutTransition {
     elapsed: f32,
 }
 
+#[derive(Default)]
 struct SpaceInsightApp {
     scan_path: String,
+    scan_options: ScanOptions,
+    /// Save finished scans and reuse their unchanged folders on the next scan
+    remember_scans: bool,
+    /// `ScanStats::reused_dirs` and `reread_dirs` of the last full scan
+    reused_dirs: u64,
+    reread_dirs: u64,
+    /// `ScanStats::unresponsive_dirs` of the last full scan
+    unresponsive_dirs: u64,
+    exclude_input: String,
+    exclude_error: Option<String>,
     is_scanning: bool,
+    cancel_token: Option<CancelToken>,
+    scan_errors: Vec<ScanError>,
+    scan_error_count: u64,
+    show_errors: bool,
+    largest_files: Vec<LargestEntry>,
+    largest_dirs: Vec<LargestEntry>,
+    show_largest: bool,
+    show_file_types: bool,
+    show_owners: bool,
+    /// Per-user totals of the folder the "Owners" window shows; dropped whenever the tree changes
+    owners: Option<(PathBuf, OwnerUsage)>,
+    /// Loaded the first time an owner is shown
+    owner_names: Option<OwnerNames>,
+    /// User whose files stay lit in the treemap while everything else is dimmed
+    highlight_uid: Option<u32>,
+    /// That user's bytes at or below each node; rebuilt whenever the tree changes
+    highlight_sizes: Option<HashMap<indextree::NodeId, u64>>,
+    /// Cut-off of the "older than" figure shown for the selected folder
+    stale_days: u32,
+    /// Bytes older than `stale_days` in the selected folder; dropped whenever the tree changes
+    stale_size: Option<(PathBuf, u32, u64)>,
     scan_rx: Option<Receiver<ScanEvent>>,
     scan_progress: Option<ScanProgress>,
     scan_preview_items: Vec<TopLevelItem>,
+    /// Latest `ScanProgress::live_tree` of the running scan
+    live_tree: Option<FileTree>,
+    live_render_nodes: Vec<RenderNode>,
+    /// Rect `live_render_nodes` were laid out for; `None` once they are out of date
+    live_layout_rect: Option<egui::Rect>,
+    rescan_rx: Option<Receiver<RescanResult>>,
+    rescan_path: Option<PathBuf>,
     watcher: Option<RecommendedWatcher>,
     watcher_rx: Option<Receiver<notify::Result<Event>>>,
-    watched_root: Option<PathBuf>,
+    watched_roots: Vec<PathBuf>,
     has_data: bool,
     file_tree: Option<FileTree>,
     root_node_id: Option<indextree::NodeId>,
     view_root_path: Option<PathBuf>,
     expansion_state: ExpansionState,
+    size_mode: SizeMode,
     split_small_items_roots: HashSet<PathBuf>,
     render_nodes: Vec<RenderNode>,
     layout_transition: Option<LayoutTransition>,
@@ -134,38 +201,15 @@ struct TopLevelItem {
     is_dir: bool,
 }
 
-impl Default for SpaceInsightApp {
-    fn default() -> Self {
-        Self {
-            scan_path: String::default(),
-            is_scanning: false,
-            scan_rx: None,
-            scan_progress: None,
-            scan_preview_items: Vec::new(),
-            watcher: None,
-            watcher_rx: None,
-            watched_root: None,
-            has_data: false,
-            file_tree: None,
-            root_node_id: None,
-            view_root_path: None,
-            expansion_state: ExpansionState::default(),
-            split_small_items_roots: HashSet::new(),
-            render_nodes: Vec::new(),
-            layout_transition: None,
-            hovered_path: None,
-            selected_path: None,
-            animator: LayoutAnimator::default(),
-            last_frame_time: None,
-            last_container_rect: None,
-            top_level_items: Vec::new(),
-        }
-    }
+struct ScanResult {
+    tree: FileTree,
+    stats: ScanStats,
 }
 
-struct ScanResult {
+/// A single folder scanned again, to be grafted into the current tree.
+struct RescanResult {
+    path: PathBuf,
     tree: FileTree,
-    #[allow(dead_code)]
     stats: ScanStats,
 }
 
@@ -179,63 +223,145 @@ impl SpaceInsightApp {
             return;
         }
 
-        let path = if self.scan_path.is_empty() {
-            ".".to_string()
-        } else {
-            self.scan_path.clone()
-        };
-
-        self.scan_path = path.clone();
+        let paths = self.scan_paths();
         self.is_scanning = true;
         self.scan_preview_items.clear();
-        self.scan_progress = Some(ScanProgress {
-            phase: ScanPhase::Discovering,
-            discovered_entries: 0,
-            processed_entries: 0,
-            total_entries: None,
-            total_files: 0,
-            total_dirs: 0,
-            total_size: 0,
-            top_level_preview: Vec::new(),
-        });
+        self.scan_progress = Some(ScanProgress::default());
+        // Folders opened in the live tree stay open once the scan is done.
+        self.expansion_state = ExpansionState::default();
+        self.split_small_items_roots.clear();
+        self.live_tree = None;
+        self.live_layout_rect = None;
 
         let (tx, rx) = mpsc::channel::<ScanEvent>();
         self.scan_rx = Some(rx);
+        let size_mode = self.size_mode;
+        let options = self.scan_options.clone();
+        let cache = ScanCache::default_location();
+        let remember_scans = self.remember_scans;
+
+        let mut crawler = FileCrawler::with_options(options.clone());
+        self.cancel_token = Some(crawler.cancel_token());
 
         thread::spawn(move || {
-            let mut crawler = FileCrawler::new();
             let progress_tx = tx.clone();
             let reporter = Arc::new(move |progress: ScanProgress| {
-                let _ = progress_tx.send(ScanEvent::Progress(progress));
+                let _ = progress_tx.send(ScanEvent::Progress(Box::new(progress)));
             });
 
-            let (nodes, stats) = crawler.scan_with_progress(&path, Some(reporter));
-
-            let mut tree = FileTree::new(&path);
-
-            for node in nodes {
-                tree.upsert_node(node.path, node.size, node.is_dir);
+            if let Some(cache) = &cache {
+                if let Some(estimate) = cache.load_estimate(&paths) {
+                    crawler.set_estimate(estimate);
+                }
+                let previous = remember_scans.then(|| cache.load(&paths, &options)).flatten();
+                if let Some(previous) = previous {
+                    crawler.set_previous_scan(previous);
+                }
             }
+            let (mut tree, stats) = crawler.scan_paths_with_progress(&paths, Some(reporter));
+            if let Some(cache) = &cache {
+                // A scan that can't be saved just won't speed up or estimate the next one.
+                if !stats.cancelled {
+                    let _ = cache.store_estimate(&paths, stats.estimate());
+                }
+                if remember_scans {
+                    let _ = cache.store(&tree, &options);
+                }
+            }
+            tree.set_size_mode(size_mode);
 
-            tree.calculate_sizes();
-
-            let _ = tx.send(ScanEvent::Completed(ScanResult {
+            let _ = tx.send(ScanEvent::Completed(Box::new(ScanResult {
                 tree,
                 stats,
-            }));
+            })));
         });
     }
 
+    /// Scan `path` again in the background; `check_rescan_result` grafts it into the tree.
+    fn start_rescan(&mut self, path: PathBuf) {
+        if self.is_scanning || self.rescan_path.is_some() || self.file_tree.is_none() {
+            return;
+        }
+
+        let (tx, rx) = mpsc::channel::<RescanResult>();
+        self.rescan_rx = Some(rx);
+        self.rescan_path = Some(path.clone());
+        let size_mode = self.size_mode;
+        let mut crawler = FileCrawler::with_options(self.scan_options.clone());
+
+        thread::spawn(move || {
+            let (mut tree, stats) = crawler.scan_with_progress(&path, None);
+            tree.set_size_mode(size_mode);
+            let _ = tx.send(RescanResult { path, tree, stats });
+        });
+    }
+
+    fn check_rescan_result(&mut self, container_rect: egui::Rect) {
+        let result = match self.rescan_rx.as_ref().map(|rx| rx.try_recv()) {
+            Some(Ok(result)) => result,
+            Some(Err(TryRecvError::Empty)) | None => return,
+            Some(Err(TryRecvError::Disconnected)) => {
+                self.rescan_rx = None;
+                self.rescan_path = None;
+                return;
+            }
+        };
+        self.rescan_rx = None;
+        self.rescan_path = None;
+
+        let Some(tree) = self.file_tree.as_mut() else {
+            return;
+        };
+        if !tree.graft_subtree(&result.path, result.tree) {
+            return;
+        }
+
+        // Errors from the old scan of that folder are replaced by the fresh ones.
+        let before = self.scan_errors.len() as u64;
+        self.scan_errors.retain(|error| !error.path.starts_with(&result.path));
+        let dropped = before - self.scan_errors.len() as u64;
+        self.scan_error_count = self.scan_error_count.saturating_sub(dropped) + result.stats.error_count;
+        self.scan_errors.extend(result.stats.errors);
+        self.scan_errors.sort_by(|a, b| a.path.cmp(&b.path));
+        largest::replace_under(&mut self.largest_files, &result.path, result.stats.largest_files);
+        largest::replace_under(&mut self.largest_dirs, &result.path, result.stats.largest_dirs);
+
+        self.prune_view_state_after_diff();
+        self.populate_top_level_items();
+        self.rebuild_render_tree(container_rect);
+    }
+
+    /// Paths in the path field, separated like `PATH` entries; `.` when it is empty.
+    fn scan_paths(&self) -> Vec<PathBuf> {
+        let paths: Vec<PathBuf> = std::env::split_paths(&self.scan_path)
+            .filter(|path| !path.as_os_str().is_empty())
+            .collect();
+        if paths.is_empty() {
+            vec![PathBuf::from(".")]
+        } else {
+            paths
+        }
+    }
+
     fn check_scan_result(&mut self, container_rect: egui::Rect) {
-        let mut completed_scan: Option<ScanResult> = None;
+        let mut completed_scan: Option<Box<ScanResult>> = None;
         let mut rx_disconnected = false;
 
         if let Some(rx) = self.scan_rx.as_ref() {
             loop {
                 match rx.try_recv() {
-                    Ok(ScanEvent::Progress(progress)) => {
-                        self.scan_preview_items = Self::preview_to_top_level_items(&progress.top_level_preview);
-                        self.scan_progress = Some(progress);
+                    Ok(ScanEvent::Progress(mut progress)) => {
+                        if let Some(live) = progress.live_tree.take() {
+                            let mut tree = Arc::try_unwrap(live).unwrap_or_else(|live| (*live).clone());
+                            tree.set_size_mode(self.size_mode);
+                            self.live_tree = Some(tree);
+                            self.live_layout_rect = None;
+                        }
+                        self.scan_preview_items = Self::preview_to_top_level_items(
+                            &progress.top_level_preview,
+                            self.size_mode,
+                        );
+                        self.scan_progress = Some(*progress);
                     }
                     Ok(ScanEvent::Completed(result)) => {
                         completed_scan = Some(result);
@@ -252,14 +378,19 @@ impl SpaceInsightApp {
         if rx_disconnected {
             self.scan_rx = None;
             self.is_scanning = false;
+            self.cancel_token = None;
+            self.live_tree = None;
         }
 
         if let Some(result) = completed_scan {
             self.scan_rx = None;
             self.is_scanning = false;
+            self.cancel_token = None;
             self.scan_progress = None;
             self.scan_preview_items.clear();
-            self.apply_scan_result(result, container_rect);
+            self.live_tree = None;
+            self.live_render_nodes.clear();
+            self.apply_scan_result(*result, container_rect);
         }
 
         // Recompute render tree if container size changed
@@ -284,10 +415,17 @@ impl SpaceInsightApp {
         let root = result.tree.get_root();
         self.root_node_id = Some(root);
         self.file_tree = Some(result.tree);
+        self.scan_errors = result.stats.errors;
+        self.scan_error_count = result.stats.error_count;
+        self.show_errors = false;
+        self.largest_files = result.stats.largest_files;
+        self.largest_dirs = result.stats.largest_dirs;
+        self.reused_dirs = result.stats.reused_dirs;
+        self.reread_dirs = result.stats.reread_dirs;
+        self.unresponsive_dirs = result.stats.unresponsive_dirs;
 
         self.view_root_path = None;
-        self.expansion_state = ExpansionState::default();
-        self.split_small_items_roots.clear();
+        self.prune_view_state_after_diff();
         self.selected_path = None;
         self.hovered_path = None;
         self.render_nodes.clear();
@@ -301,11 +439,13 @@ impl SpaceInsightApp {
         let Some(tree) = self.file_tree.as_ref() else {
             return;
         };
-        let Some(root_path) = tree.root_path().map(|p| p.to_path_buf()) else {
-            return;
-        };
+        let root_paths: Vec<PathBuf> = tree
+            .scan_root_paths()
+            .into_iter()
+            .map(Path::to_path_buf)
+            .collect();
 
-        if self.watched_root.as_ref() == Some(&root_path) && self.watcher.is_some() {
+        if self.watched_roots == root_paths && self.watcher.is_some() {
             return;
         }
 
@@ -320,19 +460,24 @@ impl SpaceInsightApp {
             Err(_) => {
                 self.watcher = None;
                 self.watcher_rx = None;
-                self.watched_root = None;
+                self.watched_roots.clear();
                 return;
             }
         };
 
-        if watcher.watch(&root_path, RecursiveMode::Recursive).is_ok() {
-            self.watcher = Some(watcher);
-            self.watcher_rx = Some(rx);
-            self.watched_root = Some(root_path);
-        } else {
+        // Roots that can't be watched just don't get live updates.
+        let watched: Vec<PathBuf> = root_paths
+            .into_iter()
+            .filter(|root| watcher.watch(root, RecursiveMode::Recursive).is_ok())
+            .collect();
+        if watched.is_empty() {
             self.watcher = None;
             self.watcher_rx = None;
-            self.watched_root = None;
+            self.watched_roots.clear();
+        } else {
+            self.watcher = Some(watcher);
+            self.watcher_rx = Some(rx);
+            self.watched_roots = watched;
         }
     }
 
@@ -368,7 +513,7 @@ impl SpaceInsightApp {
         if disconnected {
             self.watcher = None;
             self.watcher_rx = None;
-            self.watched_root = None;
+            self.watched_roots.clear();
         }
 
         if changed {
@@ -387,7 +532,7 @@ impl SpaceInsightApp {
         match event.kind {
             EventKind::Create(_) => {
                 for path in event.paths {
-                    if let Some(meta) = std::fs::metadata(&path).ok() {
+                    if let Ok(meta) = std::fs::metadata(&path) {
                         changed |= self.apply_upsert_path(path, &meta);
                     }
                 }
@@ -402,12 +547,12 @@ impl SpaceInsightApp {
                     let from = &event.paths[0];
                     let to = &event.paths[1];
                     changed |= self.apply_remove_path(from.as_path());
-                    if let Some(meta) = std::fs::metadata(to).ok() {
+                    if let Ok(meta) = std::fs::metadata(to) {
                         changed |= self.apply_upsert_path(to.clone(), &meta);
                     }
                 } else {
                     for path in event.paths {
-                        if let Some(meta) = std::fs::metadata(&path).ok() {
+                        if let Ok(meta) = std::fs::metadata(&path) {
                             changed |= self.apply_upsert_path(path, &meta);
                         } else {
                             changed |= self.apply_remove_path(path.as_path());
@@ -417,7 +562,7 @@ impl SpaceInsightApp {
             }
             EventKind::Modify(_) => {
                 for path in event.paths {
-                    if let Some(meta) = std::fs::metadata(&path).ok() {
+                    if let Ok(meta) = std::fs::metadata(&path) {
                         changed |= self.apply_upsert_path(path, &meta);
                     } else {
                         changed |= self.apply_remove_path(path.as_path());
@@ -435,17 +580,11 @@ impl SpaceInsightApp {
             return false;
         };
 
-        let Some(root) = tree.root_path() else {
+        // Entries the scan would have left out stay out.
+        let Some(node) = self.scan_options.watched_node(tree, &path, meta) else {
             return false;
         };
-        if !path.starts_with(root) {
-            return false;
-        }
-
-        let size = if meta.is_file() { meta.len() } else { 0 };
-        let is_dir = meta.is_dir();
-
-        tree.upsert_node(path, size, is_dir);
+        tree.upsert_file_node(node);
         true
     }
 
@@ -454,10 +593,7 @@ impl SpaceInsightApp {
             return false;
         };
 
-        let Some(root) = tree.root_path() else {
-            return false;
-        };
-        if !path.starts_with(root) {
+        if !tree.contains_path(path) {
             return false;
         }
 
@@ -494,21 +630,81 @@ impl SpaceInsightApp {
             .retain(|path| tree.get_node(path).is_some());
     }
 
-    fn preview_to_top_level_items(preview: &[ScanTopLevelPreview]) -> Vec<TopLevelItem> {
+    fn preview_to_top_level_items(
+        preview: &[ScanTopLevelPreview],
+        size_mode: SizeMode,
+    ) -> Vec<TopLevelItem> {
         let mut items: Vec<TopLevelItem> = preview
             .iter()
             .map(|item| TopLevelItem {
                 name: item.name.clone(),
-                size: item.size,
+                size: item.size_in(size_mode),
                 is_dir: item.is_dir,
             })
             .collect();
 
-        items.sort_by(|a, b| b.size.cmp(&a.size));
+        items.sort_by_key(|item| std::cmp::Reverse(item.size));
         items
     }
 
+    /// The tree found so far, laid out like the finished one. Folders can be
+    /// opened and closed while it grows; selecting and rescanning wait for the
+    /// finished tree.
     fn render_live_scan_preview(
+        &mut self,
+        ui: &mut egui::Ui,
+        painter: &egui::Painter,
+        available_rect: egui::Rect,
+        hovered_path: &mut Option<PathBuf>,
+    ) {
+        let Some(tree) = &self.live_tree else {
+            self.render_scan_preview_buckets(ui, painter, available_rect);
+            return;
+        };
+        if self.live_layout_rect != Some(available_rect) {
+            let padded = Self::padded_container(available_rect);
+            let container = Rect::new(padded.min.x, padded.min.y, padded.width(), padded.height());
+            self.live_render_nodes = build_render_tree(
+                tree,
+                tree.get_root(),
+                container,
+                &self.expansion_state,
+                usize::MAX,
+                &self.split_small_items_roots,
+            );
+            self.live_layout_rect = Some(available_rect);
+        }
+
+        let action = Self::render_nodes_recursive(
+            &self.live_render_nodes,
+            ui,
+            painter,
+            &self.selected_path,
+            hovered_path,
+            self.animator.tier.min_label_area(),
+            None,
+        );
+        // Folders past the live tree's depth hold no entries yet, so there is nothing to open.
+        let listed = |path: &Path| {
+            tree.get_node(path)
+                .is_some_and(|id| id.children(tree.get_arena()).next().is_some())
+        };
+        match action {
+            Some(ClickAction::Expand(path)) if listed(&path) => self.expansion_state.expand(&path),
+            Some(ClickAction::Deepen(path)) if listed(&path) => self.expansion_state.deepen(&path),
+            Some(ClickAction::Collapse(path)) => self.expansion_state.collapse_recursive(&path),
+            Some(ClickAction::SplitSmallItems(path)) => {
+                self.split_small_items_roots.insert(path);
+            }
+            Some(ClickAction::OpenPath(path)) => Self::open_path(&path),
+            Some(ClickAction::OpenInFileManager { path, is_dir }) => Self::open_in_file_manager(&path, is_dir),
+            _ => return,
+        }
+        self.live_layout_rect = None;
+    }
+
+    /// Top-level buckets of `ScanProgress::top_level_preview`, until the first live tree arrives.
+    fn render_scan_preview_buckets(
         &self,
         ui: &mut egui::Ui,
         painter: &egui::Painter,
@@ -652,6 +848,9 @@ impl SpaceInsightApp {
 
     fn rebuild_render_tree(&mut self, container_rect: egui::Rect) {
         self.last_container_rect = Some(container_rect);
+        self.owners = None;
+        self.highlight_sizes = None;
+        self.stale_size = None;
 
         if let (Some(tree), Some(root_id)) = (&self.file_tree, self.active_root_node_id()) {
             let padded = Self::padded_container(container_rect);
@@ -762,7 +961,11 @@ impl SpaceInsightApp {
     fn current_root_label(&self) -> String {
         if let (Some(tree), Some(active_root_id)) = (&self.file_tree, self.active_root_node_id()) {
             if let Some(node) = tree.get_arena().get(active_root_id) {
-                return node.get().path.display().to_string();
+                let node = node.get();
+                if node.path.as_os_str().is_empty() {
+                    return node.name.clone();
+                }
+                return node.path.display().to_string();
             }
         }
 
@@ -773,6 +976,21 @@ impl SpaceInsightApp {
         }
     }
 
+    fn apply_size_mode(&mut self) {
+        if let Some(tree) = self.live_tree.as_mut() {
+            tree.set_size_mode(self.size_mode);
+            self.live_layout_rect = None;
+        }
+        let Some(tree) = self.file_tree.as_mut() else {
+            return;
+        };
+        tree.set_size_mode(self.size_mode);
+        self.populate_top_level_items();
+        if let Some(rect) = self.last_container_rect {
+            self.rebuild_render_tree(rect);
+        }
+    }
+
     fn current_root_size(&self) -> Option<u64> {
         let (Some(tree), Some(active_root_id)) = (&self.file_tree, self.active_root_node_id()) else {
             return None;
@@ -783,6 +1001,26 @@ impl SpaceInsightApp {
             .map(|node| node.get().cumulative_size)
     }
 
+    fn current_root_shared_size(&self) -> Option<u64> {
+        let (Some(tree), Some(active_root_id)) = (&self.file_tree, self.active_root_node_id()) else {
+            return None;
+        };
+
+        tree.get_arena()
+            .get(active_root_id)
+            .map(|node| node.get().shared_size)
+    }
+
+    fn current_root_ignored_size(&self) -> Option<u64> {
+        let (Some(tree), Some(active_root_id)) = (&self.file_tree, self.active_root_node_id()) else {
+            return None;
+        };
+
+        tree.get_arena()
+            .get(active_root_id)
+            .map(|node| node.get().ignored_size)
+    }
+
     fn step_out_view_root(&mut self) {
         let (Some(tree), Some(global_root_id), Some(current_view_root)) =
             (&self.file_tree, self.root_node_id, self.view_root_path.clone())
@@ -801,17 +1039,12 @@ impl SpaceInsightApp {
             return;
         }
 
-        if let Some(parent) = current_view_root.parent() {
-            let parent_buf = parent.to_path_buf();
-            if parent_buf == global_root_path {
-                self.view_root_path = None;
-            } else if tree.get_node(&parent_buf).is_some() {
-                self.view_root_path = Some(parent_buf);
-            } else {
-                self.view_root_path = None;
+        // The tree's own parent link also steps from a scanned path up to a synthetic root.
+        match tree.parent_path(&current_view_root) {
+            Some(parent) if parent != global_root_path => {
+                self.view_root_path = Some(parent.to_path_buf());
             }
-        } else {
-            self.view_root_path = None;
+            _ => self.view_root_path = None,
         }
     }
 
@@ -977,17 +1210,18 @@ impl SpaceInsightApp {
             .cloned()
             .unwrap_or_else(|| global_root_path.clone());
 
-        if path == current_root || !path.starts_with(&current_root) {
+        if path == current_root || tree.get_node(path).is_none() {
             return None;
         }
 
         // Auto-jump goes only one level deeper at a time.
-        let relative = path.strip_prefix(&current_root).ok()?;
-        let first_component = relative.components().next()?;
-        let target = current_root.join(first_component.as_os_str());
-
-        if tree.get_node(&target).is_none() {
-            return None;
+        let mut target = path.to_path_buf();
+        loop {
+            let parent = tree.parent_path(&target)?;
+            if parent == current_root {
+                break;
+            }
+            target = parent.to_path_buf();
         }
 
         if self.view_root_path.as_deref() == Some(target.as_path()) {
@@ -1179,6 +1413,7 @@ impl SpaceInsightApp {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn draw_centered_two_line_label(
         painter: &egui::Painter,
         rect: egui::Rect,
@@ -1278,6 +1513,512 @@ impl SpaceInsightApp {
         egui::Color32::from_rgb(r as u8, g as u8, b as u8)
     }
 
+    /// Add/remove exclude rules and set the small-file cutoff for the next scan.
+    fn draw_exclude_rules_editor(&mut self, ui: &mut egui::Ui) {
+        ui.label("Exclude (applies to next scan)");
+
+        let mut remove_index = None;
+        for (index, rule) in self.scan_options.exclude.rules().iter().enumerate() {
+            ui.horizontal(|ui| {
+                if ui.small_button("✕").clicked() {
+                    remove_index = Some(index);
+                }
+                ui.label(rule.to_string());
+            });
+        }
+        if let Some(index) = remove_index {
+            self.scan_options.exclude.remove(index);
+        }
+
+        ui.horizontal(|ui| {
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut self.exclude_input)
+                    .hint_text("node_modules, /abs/path or **/*.iso")
+                    .desired_width(200.0),
+            );
+            let submitted =
+                response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            if ui.button("Add").clicked() || submitted {
+                if let Some(rule) = ExcludeRule::parse(&self.exclude_input) {
+                    match self.scan_options.exclude.push(rule) {
+                        Ok(()) => {
+                            self.exclude_input.clear();
+                            self.exclude_error = None;
+                        }
+                        Err(err) => self.exclude_error = Some(err.to_string()),
+                    }
+                }
+            }
+        });
+        if let Some(err) = &self.exclude_error {
+            ui.colored_label(egui::Color32::from_rgb(248, 113, 113), err);
+        }
+
+        let mut min_kib = self.scan_options.exclude.min_file_size / 1024;
+        ui.horizontal(|ui| {
+            ui.label("Skip files smaller than");
+            ui.add(egui::DragValue::new(&mut min_kib).clamp_range(0..=1_048_576).suffix(" KiB"));
+        });
+        self.scan_options.exclude.min_file_size = min_kib * 1024;
+    }
+
+    /// Scan profile picker, plus the individual limits once the scan is throttled.
+    fn draw_throttle_editor(&mut self, ui: &mut egui::Ui) {
+        let current = self.scan_options.throttle.profile();
+        let mut profile = current;
+        ui.horizontal(|ui| {
+            ui.label("Profile");
+            egui::ComboBox::from_id_source("scan_profile")
+                .selected_text(profile.label())
+                .show_ui(ui, |ui| {
+                    for option in [ScanProfile::Fast, ScanProfile::Gentle] {
+                        ui.selectable_value(&mut profile, option, option.label());
+                    }
+                });
+        })
+        .response
+        .on_hover_text("Gentle uses fewer threads, caps the entry rate and idles its disk I/O (Linux)");
+        if profile != current {
+            self.scan_options.throttle = profile.throttle();
+        }
+        if profile != ScanProfile::Gentle {
+            return;
+        }
+
+        let throttle = &mut self.scan_options.throttle;
+        let mut threads = throttle.threads.unwrap_or(0);
+        ui.horizontal(|ui| {
+            ui.label("Threads");
+            ui.add(egui::DragValue::new(&mut threads).clamp_range(0..=64))
+                .on_hover_text("0 keeps the usual thread count");
+        });
+        throttle.threads = (threads > 0).then_some(threads);
+
+        let mut rate = throttle.max_entries_per_sec.unwrap_or(0);
+        ui.horizontal(|ui| {
+            ui.label("Max entries/s");
+            ui.add(egui::DragValue::new(&mut rate).clamp_range(0..=1_000_000).speed(100))
+                .on_hover_text("0 means no cap");
+        });
+        throttle.max_entries_per_sec = (rate > 0).then_some(rate);
+
+        ui.checkbox(&mut throttle.idle_io, "Idle I/O priority")
+            .on_hover_text("Only read the disk when nothing else needs it (Linux ioprio)");
+    }
+
+    /// Muted tile with a dashed outline for entries that hold no scanned bytes.
+    fn draw_marker_tile(painter: &egui::Painter, rect: egui::Rect, corner_radius: f32, fill: egui::Color32) {
+        painter.rect(rect, corner_radius, fill, egui::Stroke::NONE);
+
+        let inset = rect.shrink(1.5);
+        let outline = [
+            inset.left_top(),
+            inset.right_top(),
+            inset.right_bottom(),
+            inset.left_bottom(),
+            inset.left_top(),
+        ];
+        painter.extend(egui::Shape::dashed_line(
+            &outline,
+            egui::Stroke::new(1.0, egui::Color32::from_rgba_unmultiplied(255, 255, 255, 90)),
+            4.0,
+            3.0,
+        ));
+    }
+
+    fn placeholder_color(placeholder: &Placeholder, is_hovered: bool) -> egui::Color32 {
+        match (placeholder, is_hovered) {
+            (Placeholder::MountPoint(_), false) => egui::Color32::from_rgb(45, 52, 64),
+            (Placeholder::MountPoint(_), true) => egui::Color32::from_rgb(62, 70, 84),
+            (Placeholder::Excluded(_), false) => egui::Color32::from_rgb(58, 46, 52),
+            (Placeholder::Excluded(_), true) => egui::Color32::from_rgb(78, 62, 70),
+            (Placeholder::SymlinkLoop, false) => egui::Color32::from_rgb(40, 66, 72),
+            (Placeholder::SymlinkLoop, true) => egui::Color32::from_rgb(54, 86, 94),
+            (Placeholder::VirtualFs(_), false) => egui::Color32::from_rgb(50, 50, 62),
+            (Placeholder::VirtualFs(_), true) => egui::Color32::from_rgb(68, 68, 84),
+            (Placeholder::Unresponsive, false) => egui::Color32::from_rgb(72, 44, 40),
+            (Placeholder::Unresponsive, true) => egui::Color32::from_rgb(96, 60, 54),
+        }
+    }
+
+    /// One muted hue per special file type, so sockets, FIFOs and devices can be told apart.
+    fn special_file_color(kind: FileKind, is_hovered: bool) -> egui::Color32 {
+        match (kind, is_hovered) {
+            (FileKind::Socket, false) => egui::Color32::from_rgb(70, 58, 96),
+            (FileKind::Socket, true) => egui::Color32::from_rgb(92, 78, 124),
+            (FileKind::Fifo, false) => egui::Color32::from_rgb(52, 78, 70),
+            (FileKind::Fifo, true) => egui::Color32::from_rgb(70, 102, 92),
+            (FileKind::BlockDevice, false) => egui::Color32::from_rgb(88, 70, 44),
+            (FileKind::BlockDevice, true) => egui::Color32::from_rgb(112, 92, 60),
+            (_, false) => egui::Color32::from_rgb(64, 74, 44),
+            (_, true) => egui::Color32::from_rgb(84, 96, 60),
+        }
+    }
+
+    fn partial_marker_color() -> egui::Color32 {
+        egui::Color32::from_rgb(251, 191, 36)
+    }
+
+    /// Small corner triangle on tiles whose size is a lower bound.
+    fn draw_partial_marker(painter: &egui::Painter, rect: egui::Rect) {
+        let size = 7.0_f32.min(rect.width() * 0.5).min(rect.height() * 0.5);
+        if size < 3.0 {
+            return;
+        }
+        let corner = rect.right_top();
+        painter.add(egui::Shape::convex_polygon(
+            vec![
+                corner,
+                egui::pos2(corner.x, corner.y + size),
+                egui::pos2(corner.x - size, corner.y),
+            ],
+            Self::partial_marker_color(),
+            egui::Stroke::NONE,
+        ));
+    }
+
+    fn draw_errors_window(&mut self, ctx: &egui::Context) {
+        let title = format!("Scan errors ({})", self.scan_error_count);
+        let hidden = self.scan_error_count.saturating_sub(self.scan_errors.len() as u64);
+        let errors = &self.scan_errors;
+        egui::Window::new(title)
+            .open(&mut self.show_errors)
+            .default_width(520.0)
+            .show(ctx, |ui| {
+                ui.label("These entries could not be read, so the sizes above them are lower bounds.");
+                egui::ScrollArea::vertical().max_height(360.0).show(ui, |ui| {
+                    for error in errors {
+                        ui.horizontal(|ui| {
+                            ui.label(
+                                egui::RichText::new(format!("{:?}", error.kind))
+                                    .color(Self::partial_marker_color()),
+                            );
+                            ui.label(error.path.display().to_string())
+                                .on_hover_text(&error.message);
+                        });
+                    }
+                });
+                if hidden > 0 {
+                    ui.label(format!("... and {} more", hidden));
+                }
+            });
+    }
+
+    /// Largest files and leaf folders; live from the progress while scanning.
+    /// Returns the row the user clicked.
+    fn draw_largest_window(&mut self, ctx: &egui::Context) -> Option<PathBuf> {
+        let (files, dirs) = match (&self.scan_progress, self.is_scanning) {
+            (Some(progress), true) => (&progress.largest_files, &progress.largest_dirs),
+            _ => (&self.largest_files, &self.largest_dirs),
+        };
+        let can_jump = !self.is_scanning && self.file_tree.is_some();
+        let size_mode = self.size_mode;
+        let mut clicked = None;
+
+        let mut list = |ui: &mut egui::Ui, entries: &[LargestEntry]| {
+            if entries.is_empty() {
+                ui.weak("Nothing yet");
+            }
+            for entry in entries {
+                ui.horizontal(|ui| {
+                    ui.label(
+                        egui::RichText::new(Self::format_size(entry.size_in(size_mode))).monospace(),
+                    );
+                    let name = entry
+                        .path
+                        .file_name()
+                        .map(|name| name.to_string_lossy().into_owned())
+                        .unwrap_or_else(|| entry.path.display().to_string());
+                    let response = ui
+                        .add_enabled(can_jump, egui::Link::new(name))
+                        .on_hover_text(entry.path.display().to_string());
+                    if response.clicked() {
+                        clicked = Some(entry.path.clone());
+                    }
+                });
+            }
+        };
+
+        egui::Window::new("Largest files")
+            .open(&mut self.show_largest)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                egui::ScrollArea::vertical().max_height(480.0).show(ui, |ui| {
+                    list(ui, files);
+                    ui.separator();
+                    ui.strong("Largest folders without subfolders");
+                    list(ui, dirs);
+                });
+            });
+        clicked
+    }
+
+    /// The selected folder, or the folder in view when a file or nothing is selected.
+    fn breakdown_folder(&self) -> Option<indextree::NodeId> {
+        let tree = self.file_tree.as_ref()?;
+        self.selected_path
+            .as_ref()
+            .and_then(|path| tree.get_node(path))
+            .filter(|id| tree.get_arena()[*id].get().is_dir)
+            .or_else(|| self.active_root_node_id())
+    }
+
+    /// Share of each category and extension in a folder's bytes.
+    fn draw_file_types_window(&mut self, ctx: &egui::Context) {
+        let Some(tree) = self.file_tree.as_ref() else {
+            return;
+        };
+        let Some(folder_id) = self.breakdown_folder() else {
+            return;
+        };
+        let folder_path = tree.get_arena()[folder_id].get().path.clone();
+        let breakdown = tree.type_breakdown(folder_id);
+        let size_mode = self.size_mode;
+        let total = breakdown.total().size_in(size_mode);
+
+        egui::Window::new("File types")
+            .open(&mut self.show_file_types)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                ui.label(format!("{} in {}", Self::format_size(total), folder_path.display()));
+                if breakdown.is_empty() {
+                    ui.weak("No files here");
+                    return;
+                }
+                ui.separator();
+                egui::Grid::new("file_type_categories").striped(true).show(ui, |ui| {
+                    for (category, totals) in breakdown.categories_by_size(size_mode) {
+                        let hue = category.hue_degrees() / 360.0;
+                        let swatch = egui::Color32::from(egui::ecolor::Hsva::new(hue, 0.55, 0.8, 1.0));
+                        ui.colored_label(swatch, format!("■ {}", category.label()));
+                        ui.label(format!("{:.1}%", percent_of(totals.size_in(size_mode), total)));
+                        ui.label(Self::format_size(totals.size_in(size_mode)));
+                        ui.weak(format!("{} files", totals.files));
+                        ui.end_row();
+                    }
+                });
+                ui.separator();
+                egui::ScrollArea::vertical().max_height(360.0).show(ui, |ui| {
+                    egui::Grid::new("file_type_extensions").striped(true).show(ui, |ui| {
+                        for (key, totals) in breakdown.extensions_by_size(size_mode) {
+                            ui.monospace(extension_label(key));
+                            ui.label(format!("{:.1}%", percent_of(totals.size_in(size_mode), total)));
+                            ui.label(Self::format_size(totals.size_in(size_mode)));
+                            ui.weak(format!("{} files", totals.files));
+                            ui.end_row();
+                        }
+                    });
+                });
+            });
+    }
+
+    /// Disk usage per user and group in a folder; a user can be highlighted in the treemap.
+    fn draw_owners_window(&mut self, ctx: &egui::Context) {
+        let Some(tree) = self.file_tree.as_ref() else {
+            return;
+        };
+        let Some(folder_id) = self.breakdown_folder() else {
+            return;
+        };
+        let folder_path = tree.get_arena()[folder_id].get().path.clone();
+        if self.owners.as_ref().map(|(path, _)| path) != Some(&folder_path) {
+            self.owners = Some((folder_path.clone(), tree.owner_usage(folder_id)));
+        }
+        let names = self.owner_names.get_or_insert_with(OwnerNames::load);
+        let Some((_, usage)) = &self.owners else {
+            return;
+        };
+        let size_mode = self.size_mode;
+        let total = usage.total().size_in(size_mode);
+        let highlighted = self.highlight_uid;
+        let mut toggled: Option<Option<u32>> = None;
+
+        egui::Window::new("Owners")
+            .open(&mut self.show_owners)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                ui.label(format!("{} in {}", Self::format_size(total), folder_path.display()));
+                if let Some(uid) = highlighted {
+                    ui.horizontal(|ui| {
+                        ui.label(format!("Highlighting {}", names.user(uid)));
+                        if ui.small_button("Clear").clicked() {
+                            toggled = Some(None);
+                        }
+                    });
+                }
+                if usage.is_empty() {
+                    ui.weak("No files here");
+                    return;
+                }
+                ui.separator();
+                ui.strong("Users");
+                egui::Grid::new("owner_users").striped(true).show(ui, |ui| {
+                    for (uid, totals) in usage.users_by_size(size_mode) {
+                        let lit = highlighted == Some(uid);
+                        if ui
+                            .selectable_label(lit, names.user(uid))
+                            .on_hover_text("Highlight this user's files in the treemap")
+                            .clicked()
+                        {
+                            toggled = Some(if lit { None } else { Some(uid) });
+                        }
+                        ui.label(format!("{:.1}%", percent_of(totals.size_in(size_mode), total)));
+                        ui.label(Self::format_size(totals.size_in(size_mode)));
+                        ui.weak(format!("{} files", totals.files));
+                        ui.end_row();
+                    }
+                });
+                ui.separator();
+                ui.strong("Groups");
+                egui::Grid::new("owner_groups").striped(true).show(ui, |ui| {
+                    for (gid, totals) in usage.groups_by_size(size_mode) {
+                        ui.label(names.group(gid));
+                        ui.label(format!("{:.1}%", percent_of(totals.size_in(size_mode), total)));
+                        ui.label(Self::format_size(totals.size_in(size_mode)));
+                        ui.weak(format!("{} files", totals.files));
+                        ui.end_row();
+                    }
+                });
+            });
+
+        if let Some(uid) = toggled {
+            self.highlight_uid = uid;
+            self.highlight_sizes = None;
+        }
+    }
+
+    /// Zoom to the folder holding `path` and select it. The folder's small items are
+    /// split out so the entry gets a tile of its own.
+    fn reveal_path(&mut self, path: &Path, container_rect: egui::Rect) {
+        let Some(tree) = self.file_tree.as_ref() else {
+            return;
+        };
+        if tree.get_node(path).is_none() {
+            return;
+        }
+        let root_path = self
+            .root_node_id
+            .and_then(|id| tree.get_arena().get(id))
+            .map(|node| node.get().path.clone());
+        let parent = tree.parent_path(path).map(Path::to_path_buf);
+
+        self.view_root_path = parent.clone().filter(|parent| Some(parent) != root_path.as_ref());
+        if let Some(parent) = parent {
+            self.split_small_items_roots.insert(parent);
+        }
+        self.selected_path = Some(path.to_path_buf());
+        self.rebuild_render_tree(container_rect);
+    }
+
+    fn selected_tree_node(&self) -> Option<&TreeNode> {
+        let tree = self.file_tree.as_ref()?;
+        let node_id = tree.get_node(self.selected_path.as_ref()?)?;
+        tree.get_arena().get(node_id).map(|n| n.get())
+    }
+
+    /// Bytes in the selected folder untouched for `stale_days`, cached per folder and cut-off.
+    fn selected_stale_size(&mut self) -> Option<u64> {
+        let tree = self.file_tree.as_ref()?;
+        let path = self.selected_path.as_ref()?;
+        let node_id = tree.get_node(path)?;
+        if !tree.get_arena()[node_id].get().is_dir {
+            return None;
+        }
+        match &self.stale_size {
+            Some((cached, days, size)) if cached == path && *days == self.stale_days => Some(*size),
+            _ => {
+                let size = tree.bytes_older_than(node_id, self.stale_days);
+                self.stale_size = Some((path.clone(), self.stale_days, size));
+                Some(size)
+            }
+        }
+    }
+
+    fn draw_selection_panel(ui: &mut egui::Ui, node: &TreeNode, stale_days: &mut u32, stale_size: Option<u64>) {
+        ui.horizontal_wrapped(|ui| {
+            ui.strong(&node.name);
+            ui.label(node.path.display().to_string());
+            ui.separator();
+            ui.label(Self::format_size(node.cumulative_size));
+            if let Some(target) = &node.symlink_target {
+                ui.separator();
+                ui.label(format!("→ {}", target.display()));
+            }
+            if let Some(kind) = node.special {
+                ui.separator();
+                ui.label(format!("{} (no data)", kind.label()));
+            }
+        });
+        if node.is_dir {
+            let total = node.age.total();
+            ui.horizontal_wrapped(|ui| {
+                ui.label("Last modified:");
+                for (label, bytes) in node.age.rows() {
+                    ui.separator();
+                    ui.label(format!(
+                        "{} {} ({:.0}%)",
+                        label,
+                        Self::format_size(bytes),
+                        percent_of(bytes, total)
+                    ));
+                }
+                if let Some(stale) = stale_size {
+                    ui.separator();
+                    ui.label("Older than");
+                    ui.add(egui::DragValue::new(stale_days).clamp_range(1..=3650).suffix(" days"));
+                    ui.strong(format!(
+                        "{} ({:.0}%)",
+                        Self::format_size(stale),
+                        percent_of(stale, node.cumulative_size)
+                    ));
+                }
+            });
+        } else if node.special.is_none() {
+            ui.label(format!("Modified {}", format_timestamp(node.modified)));
+        }
+        let Some(meta) = &node.meta else {
+            ui.weak("Times, owner and permissions are recorded with Options → Metadata: full");
+            return;
+        };
+        ui.horizontal_wrapped(|ui| {
+            ui.monospace(meta.mode_string());
+            ui.separator();
+            ui.label(format!("Owner {}:{}", meta.uid, meta.gid));
+            ui.separator();
+            ui.label(format!("Modified {}", format_timestamp(meta.modified)));
+            ui.separator();
+            ui.label(format!("Accessed {}", format_timestamp(meta.accessed)));
+            ui.separator();
+            ui.label(format!("Inode {}", meta.inode));
+            ui.separator();
+            ui.label(meta.kind.label());
+        });
+    }
+
+    fn symlink_color(is_hovered: bool) -> egui::Color32 {
+        if is_hovered {
+            egui::Color32::from_rgb(74, 136, 146)
+        } else {
+            egui::Color32::from_rgb(56, 112, 120)
+        }
+    }
+
+    fn ignored_color(is_hovered: bool) -> egui::Color32 {
+        if is_hovered {
+            egui::Color32::from_rgb(112, 104, 128)
+        } else {
+            egui::Color32::from_rgb(88, 80, 104)
+        }
+    }
+
+    fn shared_link_color(is_hovered: bool) -> egui::Color32 {
+        if is_hovered {
+            egui::Color32::from_rgb(110, 128, 150)
+        } else {
+            egui::Color32::from_rgb(84, 99, 120)
+        }
+    }
+
     fn draw_aurora_background(painter: &egui::Painter, rect: egui::Rect) {
         let top_color = egui::Color32::from_rgb(30, 41, 59);
         let bottom_color = egui::Color32::from_rgb(15, 118, 110);
@@ -1389,6 +2130,11 @@ impl SpaceInsightApp {
                     "Scanning {} / {} entries",
                     scan_progress.processed_entries, total
                 )
+            } else if let Some(estimate) = scan_progress.estimated_entries {
+                format!(
+                    "Scanning {} / ~{} entries (last scan's count)",
+                    scan_progress.processed_entries, estimate
+                )
             } else {
                 format!("Discovering entries: {}", scan_progress.discovered_entries)
             };
@@ -1404,6 +2150,7 @@ impl SpaceInsightApp {
         selected_path: &Option<PathBuf>,
         hovered_path: &mut Option<PathBuf>,
         min_label_area: f32,
+        highlight: Option<(&FileTree, &HashMap<indextree::NodeId, u64>)>,
     ) -> Option<ClickAction> {
         let mut action: Option<ClickAction> = None;
         let level_total_size: u64 = nodes.iter().map(|node| node.size).sum();
@@ -1471,6 +2218,10 @@ impl SpaceInsightApp {
                         });
                         ui.close_menu();
                     }
+                    if !node.path.as_os_str().is_empty() && ui.button("Rescan this folder").clicked() {
+                        action = Some(ClickAction::Rescan(node.path.clone()));
+                        ui.close_menu();
+                    }
                 });
                 if header_response.hovered() {
                     *hovered_path = Some(node.path.clone());
@@ -1494,6 +2245,7 @@ impl SpaceInsightApp {
                         selected_path,
                         hovered_path,
                         min_label_area,
+                        highlight,
                     ) {
                         if action.is_none() {
                             action = Some(sub_action);
@@ -1528,9 +2280,14 @@ impl SpaceInsightApp {
                             });
                             ui.close_menu();
                         }
+                        if node.is_dir && ui.button("Rescan this folder").clicked() {
+                            action = Some(ClickAction::Rescan(node.path.clone()));
+                            ui.close_menu();
+                        }
                     });
                 }
                 let is_hovered = response.hovered();
+                let expandable = node.is_dir && node.placeholder.is_none();
 
                 if is_hovered {
                     *hovered_path = if node.is_aggregate {
@@ -1538,7 +2295,7 @@ impl SpaceInsightApp {
                     } else {
                         Some(node.path.clone())
                     };
-                    if node.is_dir || node.is_aggregate {
+                    if expandable || node.is_aggregate {
                         ui.ctx().set_cursor_icon(egui::CursorIcon::PointingHand);
                     }
                 }
@@ -1551,10 +2308,10 @@ impl SpaceInsightApp {
                                 action = Some(ClickAction::SplitSmallItems(container_path));
                             }
                         }
-                    } else if response.double_clicked() && node.is_dir {
+                    } else if response.double_clicked() && expandable {
                         action = Some(ClickAction::Deepen(node.path.clone()));
                     } else if response.clicked() {
-                        if node.is_dir {
+                        if expandable {
                             let content_width = (node.outer_rect.width - 2.0 * SIDE_INSET).max(1.0);
                             let content_height =
                                 (node.outer_rect.height - HEADER_HEIGHT - SIDE_INSET).max(1.0);
@@ -1614,9 +2371,90 @@ impl SpaceInsightApp {
                             node.aggregate_count
                         ));
                     }
+                } else if let Some(placeholder) = &node.placeholder {
+                    // --- Unscanned directory marker ---
+                    let area = pw * ph;
+                    let detail = match &node.symlink_target {
+                        Some(target) => format!("{} → {}", placeholder.label(), target.display()),
+                        None => placeholder.label(),
+                    };
+                    Self::draw_marker_tile(
+                        painter,
+                        egui_rect,
+                        corner_radius,
+                        Self::placeholder_color(placeholder, is_hovered),
+                    );
+                    if area > min_label_area {
+                        let _ = Self::draw_centered_two_line_label(
+                            painter,
+                            egui_rect,
+                            &node.name,
+                            11.0,
+                            &detail,
+                            10.0,
+                            egui::Color32::from_rgba_unmultiplied(255, 255, 255, 200),
+                            egui::Color32::from_rgba_unmultiplied(255, 255, 255, 130),
+                        );
+                    }
+                    if is_hovered {
+                        let reason = match placeholder {
+                            Placeholder::MountPoint(_) => "Not scanned",
+                            Placeholder::Excluded(_) => "Excluded from scan",
+                            Placeholder::SymlinkLoop => "Not followed: points back at an ancestor",
+                            Placeholder::VirtualFs(_) => "Virtual filesystem, not scanned",
+                            Placeholder::Unresponsive => "Didn't answer in time, left unread",
+                        };
+                        response.on_hover_text(format!("{}\n{}\n{}", node.name, detail, reason));
+                    }
+                } else if let Some(kind) = node.special {
+                    // --- Socket, FIFO or device node: no bytes, only a marker ---
+                    let area = pw * ph;
+                    Self::draw_marker_tile(
+                        painter,
+                        egui_rect,
+                        corner_radius,
+                        Self::special_file_color(kind, is_hovered),
+                    );
+                    if area > min_label_area {
+                        let _ = Self::draw_centered_two_line_label(
+                            painter,
+                            egui_rect,
+                            &node.name,
+                            11.0,
+                            kind.label(),
+                            10.0,
+                            egui::Color32::from_rgba_unmultiplied(255, 255, 255, 200),
+                            egui::Color32::from_rgba_unmultiplied(255, 255, 255, 130),
+                        );
+                    }
+                    if is_hovered {
+                        response.on_hover_text(format!(
+                            "{}\n{}, holds no data (counted as 0 bytes)",
+                            node.name,
+                            kind.label()
+                        ));
+                    }
                 } else {
                     // --- Normal file/folder block ---
-                    let base_color = Self::get_temperature_color(size_ratio, is_hovered);
+                    let mut base_color = if node.is_shared_link {
+                        Self::shared_link_color(is_hovered)
+                    } else if node.symlink_target.is_some() && !node.is_dir {
+                        Self::symlink_color(is_hovered)
+                    } else if node.is_ignored {
+                        Self::ignored_color(is_hovered)
+                    } else {
+                        Self::get_temperature_color(size_ratio, is_hovered)
+                    };
+                    // Bytes of the highlighted user in this tile; tiles without any fade out
+                    let highlighted_size = highlight.map(|(tree, sizes)| {
+                        tree.get_node(&node.path)
+                            .and_then(|id| sizes.get(&id))
+                            .copied()
+                            .unwrap_or(0)
+                    });
+                    if highlighted_size == Some(0) {
+                        base_color = base_color.gamma_multiply(0.25);
+                    }
 
                     // Shadow
                     let shadow_rect = egui_rect.translate(egui::vec2(0.0, 2.0));
@@ -1644,6 +2482,23 @@ impl SpaceInsightApp {
                     };
                     painter.rect_stroke(egui_rect, corner_radius, border_stroke);
 
+                    if node.partial {
+                        Self::draw_partial_marker(painter, egui_rect);
+                    }
+
+                    // Ignored share of a partly ignored folder, as a strip along the bottom
+                    if node.is_dir && !node.is_ignored && node.ignored_size > 0 && node.size > 0 {
+                        let fraction = node.ignored_size as f32 / node.size as f32;
+                        let strip = egui::Rect::from_min_max(
+                            egui::pos2(egui_rect.left(), egui_rect.bottom() - 3.0),
+                            egui::pos2(
+                                egui_rect.left() + egui_rect.width() * fraction,
+                                egui_rect.bottom(),
+                            ),
+                        );
+                        painter.rect_filled(strip, 0.0, Self::ignored_color(true));
+                    }
+
                     // Selection glow
                     if selected_path.as_ref() == Some(&node.path) && !node.is_dir {
                         let glow_rect = egui_rect.expand(2.0);
@@ -1673,7 +2528,13 @@ impl SpaceInsightApp {
                         } else {
                             node.name.clone()
                         };
-                        let size_text = Self::format_size(node.size);
+                        let mut size_text = Self::format_size(node.size);
+                        if node.partial {
+                            size_text.insert_str(0, "≥ ");
+                        }
+                        if let Some(target) = &node.symlink_target {
+                            size_text = format!("{} → {}", size_text, target.display());
+                        }
                         let _ = Self::draw_centered_two_line_label(
                             painter,
                             egui_rect,
@@ -1687,12 +2548,37 @@ impl SpaceInsightApp {
                     }
                     if is_hovered {
                         let dir_indicator = if node.is_dir { "+" } else { "" };
-                        let tooltip_text = format!(
+                        let mut tooltip_text = format!(
                             "{} {} ({})",
                             dir_indicator,
                             node.name,
                             Self::format_size(node.size)
                         );
+                        if node.is_shared_link {
+                            tooltip_text.push_str("\nHardlink: bytes are counted at another path");
+                        }
+                        if let Some(target) = &node.symlink_target {
+                            tooltip_text.push_str(&format!("\nSymlink → {}", target.display()));
+                        }
+                        if node.partial {
+                            tooltip_text
+                                .push_str("\nSome entries could not be read; size is a lower bound");
+                        }
+                        if let Some(owned) = highlighted_size {
+                            tooltip_text.push_str(&format!(
+                                "\nHighlighted user: {}",
+                                Self::format_size(owned)
+                            ));
+                        }
+                        if node.is_ignored {
+                            tooltip_text.push_str("\nIgnored by .gitignore/.ignore");
+                        } else if node.ignored_size > 0 {
+                            tooltip_text.push_str(&format!(
+                                "\nIgnored: {} / tracked: {}",
+                                Self::format_size(node.ignored_size),
+                                Self::format_size(node.size.saturating_sub(node.ignored_size))
+                            ));
+                        }
                         response.on_hover_text(tooltip_text);
                     }
                 }
@@ -1844,7 +2730,10 @@ impl eframe::App for SpaceInsightApp {
                 ui.separator();
 
                 ui.label("Path:");
-                ui.text_edit_singleline(&mut self.scan_path);
+                ui.text_edit_singleline(&mut self.scan_path).on_hover_text(format!(
+                    "Scan several paths side by side by separating them with '{}'",
+                    if cfg!(windows) { ';' } else { ':' }
+                ));
 
                 if ui.button("Browse...").clicked() {
                     if let Some(path) = Self::pick_folder_path() {
@@ -1856,16 +2745,156 @@ impl eframe::App for SpaceInsightApp {
                     self.start_scan();
                 }
 
+                let previous_size_mode = self.size_mode;
+                egui::ComboBox::from_id_source("size_mode")
+                    .selected_text(self.size_mode.label())
+                    .show_ui(ui, |ui| {
+                        for mode in [SizeMode::Apparent, SizeMode::Allocated] {
+                            ui.selectable_value(&mut self.size_mode, mode, mode.label());
+                        }
+                    });
+                if self.size_mode != previous_size_mode {
+                    self.apply_size_mode();
+                }
+
+                ui.menu_button("Options", |ui| {
+                    ui.checkbox(&mut self.scan_options.one_file_system, "Stay on one filesystem")
+                        .on_hover_text("Don't descend into other mounts (like du -x); they show up as placeholders");
+                    ui.horizontal(|ui| {
+                        ui.label("Virtual filesystems");
+                        egui::ComboBox::from_id_source("virtual_fs")
+                            .selected_text(self.scan_options.virtual_fs.label())
+                            .show_ui(ui, |ui| {
+                                for policy in [
+                                    VirtualFsPolicy::Skip,
+                                    VirtualFsPolicy::SkipWithTmpfs,
+                                    VirtualFsPolicy::Scan,
+                                ] {
+                                    ui.selectable_value(
+                                        &mut self.scan_options.virtual_fs,
+                                        policy,
+                                        policy.label(),
+                                    );
+                                }
+                            });
+                    })
+                    .response
+                    .on_hover_text("/proc, /sys, /dev, cgroup and similar mounts report made-up sizes; skipped ones show up as placeholders");
+                    ui.horizontal(|ui| {
+                        ui.label("Ignore files");
+                        egui::ComboBox::from_id_source("ignore_files")
+                            .selected_text(self.scan_options.ignore_files.label())
+                            .show_ui(ui, |ui| {
+                                for mode in [IgnoreMode::Off, IgnoreMode::Skip, IgnoreMode::Tag] {
+                                    ui.selectable_value(
+                                        &mut self.scan_options.ignore_files,
+                                        mode,
+                                        mode.label(),
+                                    );
+                                }
+                            });
+                    })
+                    .response
+                    .on_hover_text(".gitignore, .ignore and global git excludes");
+                    ui.horizontal(|ui| {
+                        ui.label("Symlinks");
+                        egui::ComboBox::from_id_source("symlinks")
+                            .selected_text(self.scan_options.symlinks.label())
+                            .show_ui(ui, |ui| {
+                                for policy in [
+                                    SymlinkPolicy::Ignore,
+                                    SymlinkPolicy::CountLink,
+                                    SymlinkPolicy::Follow,
+                                ] {
+                                    ui.selectable_value(
+                                        &mut self.scan_options.symlinks,
+                                        policy,
+                                        policy.label(),
+                                    );
+                                }
+                            });
+                    })
+                    .response
+                    .on_hover_text("Following links charges their targets; links back to an ancestor are not followed");
+                    ui.horizontal(|ui| {
+                        ui.label("Folder order");
+                        egui::ComboBox::from_id_source("scan_order")
+                            .selected_text(self.scan_options.order.label())
+                            .show_ui(ui, |ui| {
+                                for order in [ScanOrder::Listing, ScanOrder::LargestFirst] {
+                                    ui.selectable_value(&mut self.scan_options.order, order, order.label());
+                                }
+                            });
+                    })
+                    .response
+                    .on_hover_text("Largest first reads the biggest folders first at every level, by the last scan (or, without one, by entry count at the top level), so the dominant ones show up in the live preview early");
+                    ui.horizontal(|ui| {
+                        ui.label("Metadata");
+                        egui::ComboBox::from_id_source("metadata_level")
+                            .selected_text(self.scan_options.metadata.label())
+                            .show_ui(ui, |ui| {
+                                for level in [MetadataLevel::Basic, MetadataLevel::Full] {
+                                    ui.selectable_value(
+                                        &mut self.scan_options.metadata,
+                                        level,
+                                        level.label(),
+                                    );
+                                }
+                            });
+                    })
+                    .response
+                    .on_hover_text("Full also records times, owner, permissions and inode for every entry");
+                    ui.horizontal(|ui| {
+                        ui.label("Read timeout");
+                        egui::ComboBox::from_id_source("read_timeout")
+                            .selected_text(read_timeout_label(self.scan_options.read_timeout))
+                            .show_ui(ui, |ui| {
+                                for timeout in READ_TIMEOUT_CHOICES {
+                                    ui.selectable_value(
+                                        &mut self.scan_options.read_timeout,
+                                        timeout,
+                                        read_timeout_label(timeout),
+                                    );
+                                }
+                            });
+                    })
+                    .response
+                    .on_hover_text("On network and FUSE mounts, entries whose stat or listing takes longer are left unread and marked unresponsive while the rest of the scan goes on");
+                    self.draw_throttle_editor(ui);
+                    ui.checkbox(&mut self.remember_scans, "Remember scans")
+                        .on_hover_text("Save each finished scan and copy folders whose times haven't changed on the next scan of the same paths. Files rewritten in place keep their old size until something else in their folder changes.");
+                    ui.separator();
+                    self.draw_exclude_rules_editor(ui);
+                });
+
                 if self.is_scanning {
+                    if let Some(token) = &self.cancel_token {
+                        let cancelling = token.is_cancelled();
+                        let label = if cancelling { "Cancelling..." } else { "Cancel" };
+                        if ui.add_enabled(!cancelling, egui::Button::new(label)).clicked() {
+                            token.cancel();
+                        }
+                    }
                     Self::draw_scan_progress_circle(ui, self.scan_progress.as_ref());
                     if let Some(progress) = &self.scan_progress {
                         if let Some(fraction) = progress.fraction() {
+                            let approx = if progress.is_estimated() { "~" } else { "" };
                             ui.label(format!(
-                                "Scanning... {} / {} ({:.0}%)",
+                                "Scanning... {} / {}{} ({}{:.0}%)",
                                 progress.processed_entries,
-                                progress.total_entries.unwrap_or_default(),
+                                approx,
+                                progress.total_entries.or(progress.estimated_entries).unwrap_or_default(),
+                                approx,
                                 fraction * 100.0,
-                            ));
+                            ))
+                            .on_hover_text_at_pointer(if progress.is_estimated() {
+                                "Estimated from the last scan of these paths"
+                            } else {
+                                "Entries processed"
+                            });
+                            if let Some(eta) = progress.eta_label() {
+                                ui.label(eta);
+                            }
                         } else {
                             ui.label(format!(
                                 "Scanning... discovering {} entries",
@@ -1876,20 +2905,56 @@ impl eframe::App for SpaceInsightApp {
                             "Files: {}  Dirs: {}  Size: {}",
                             progress.total_files,
                             progress.total_dirs,
-                            Self::format_size(progress.total_size)
+                            Self::format_size(progress.total_size_in(self.size_mode))
                         ));
+                        ui.label(format!(
+                            "{:.0} entries/s  {}/s",
+                            progress.entries_per_sec,
+                            Self::format_size(progress.bytes_per_sec as u64)
+                        ));
+                        if progress.error_count > 0 {
+                            ui.colored_label(
+                                Self::partial_marker_color(),
+                                format!("⚠ {} unreadable", progress.error_count),
+                            );
+                        }
                     } else {
                         ui.label("Scanning...");
                     }
                 }
 
-                if self.has_data {
-                    if ui.button("Collapse All").clicked() {
-                        self.expansion_state.collapse_all();
-                        self.split_small_items_roots.clear();
-                        if let Some(rect) = self.last_container_rect {
-                            self.rebuild_render_tree(rect);
-                        }
+                if (self.has_data || self.is_scanning)
+                    && ui
+                        .selectable_label(self.show_largest, "Largest files")
+                        .on_hover_text("Biggest files and leaf folders; click one to jump to it")
+                        .clicked()
+                {
+                    self.show_largest = !self.show_largest;
+                }
+
+                if self.has_data
+                    && ui
+                        .selectable_label(self.show_file_types, "File types")
+                        .on_hover_text("Share of each extension in the selected or current folder")
+                        .clicked()
+                {
+                    self.show_file_types = !self.show_file_types;
+                }
+
+                if self.has_data
+                    && ui
+                        .selectable_label(self.show_owners, "Owners")
+                        .on_hover_text("Disk usage per user and group; highlight one user's files")
+                        .clicked()
+                {
+                    self.show_owners = !self.show_owners;
+                }
+
+                if self.has_data && ui.button("Collapse All").clicked() {
+                    self.expansion_state.collapse_all();
+                    self.split_small_items_roots.clear();
+                    if let Some(rect) = self.last_container_rect {
+                        self.rebuild_render_tree(rect);
                     }
                 }
             });
@@ -1908,6 +2973,53 @@ impl eframe::App for SpaceInsightApp {
                         ui.separator();
                         ui.label(format!("Folder Size: {}", Self::format_size(size)));
                     }
+                    if let Some(shared) = self.current_root_shared_size().filter(|s| *s > 0) {
+                        ui.separator();
+                        ui.label(format!("Shared hardlinks: {}", Self::format_size(shared)))
+                            .on_hover_text("Extra links to files already counted elsewhere");
+                    }
+                    if let Some(ignored) = self.current_root_ignored_size().filter(|s| *s > 0) {
+                        ui.separator();
+                        ui.label(format!("Ignored: {}", Self::format_size(ignored)))
+                            .on_hover_text("Bytes matched by .gitignore / .ignore rules");
+                    }
+                    if self.file_tree.as_ref().is_some_and(|tree| tree.is_incomplete()) {
+                        ui.separator();
+                        ui.colored_label(Self::partial_marker_color(), "Scan cancelled: partial results")
+                            .on_hover_text("Folders that were not reached yet are missing; rescan for full totals");
+                    }
+                    if self.reused_dirs > 0 {
+                        ui.separator();
+                        ui.label(format!(
+                            "Reused {} folders, re-read {}",
+                            self.reused_dirs, self.reread_dirs
+                        ))
+                        .on_hover_text("Folders unchanged since the last scan of these paths were copied from it");
+                    }
+                    if self.unresponsive_dirs > 0 {
+                        ui.separator();
+                        ui.colored_label(
+                            Self::partial_marker_color(),
+                            format!("{} unresponsive folders left unread", self.unresponsive_dirs),
+                        )
+                        .on_hover_text("These folders didn't answer within the read timeout; they are listed with the scan errors");
+                    }
+                    if self.scan_error_count > 0 {
+                        ui.separator();
+                        let label = format!("⚠ {} unreadable", self.scan_error_count);
+                        if ui
+                            .button(egui::RichText::new(label).color(Self::partial_marker_color()))
+                            .on_hover_text("Some entries could not be read; sizes are lower bounds")
+                            .clicked()
+                        {
+                            self.show_errors = !self.show_errors;
+                        }
+                    }
+                    if let Some(path) = &self.rescan_path {
+                        ui.separator();
+                        ui.spinner();
+                        ui.label(format!("Rescanning {}", path.display()));
+                    }
                     if let Some(ref hovered) = self.hovered_path {
                         ui.separator();
                         ui.label(format!("{}", hovered.display()));
@@ -1916,12 +3028,43 @@ impl eframe::App for SpaceInsightApp {
             }
         });
 
+        if self.show_errors {
+            self.draw_errors_window(ctx);
+        }
+
+        if self.show_largest {
+            if let (Some(path), Some(rect)) = (self.draw_largest_window(ctx), self.last_container_rect) {
+                self.reveal_path(&path, rect);
+            }
+        }
+
+        if self.show_file_types {
+            self.draw_file_types_window(ctx);
+        }
+
+        if self.show_owners {
+            self.draw_owners_window(ctx);
+        }
+
+        let stale_size = self.selected_stale_size();
+        let mut stale_days = self.stale_days;
+        if let Some(node) = self.selected_tree_node() {
+            egui::TopBottomPanel::bottom("selection_panel").show(ctx, |ui| {
+                Self::draw_selection_panel(ui, node, &mut stale_days, stale_size);
+            });
+        }
+        if stale_days != self.stale_days {
+            self.stale_days = stale_days;
+            self.stale_size = None;
+        }
+
         egui::CentralPanel::default().show(ctx, |ui| {
             let available_rect = ui.available_rect_before_wrap();
 
             self.consume_file_events(available_rect);
 
             self.check_scan_result(available_rect);
+            self.check_rescan_result(available_rect);
 
             if animation_just_finished {
                 self.rebuild_render_tree(available_rect);
@@ -1947,13 +3090,19 @@ impl eframe::App for SpaceInsightApp {
                     }
                 }
             } else if self.is_scanning {
-                self.render_live_scan_preview(ui, &painter, available_rect);
+                self.render_live_scan_preview(ui, &painter, available_rect, &mut new_hovered_path);
             } else {
                 let display_nodes = self.current_display_nodes();
 
                 if !display_nodes.is_empty() {
                     // Normal recursive rendering
                     let min_label_area = self.animator.tier.min_label_area();
+                    if let (Some(uid), None, Some(tree)) =
+                        (self.highlight_uid, &self.highlight_sizes, &self.file_tree)
+                    {
+                        self.highlight_sizes = Some(tree.user_sizes(uid));
+                    }
+                    let highlight = self.file_tree.as_ref().zip(self.highlight_sizes.as_ref());
 
                     let action = Self::render_nodes_recursive(
                         &display_nodes,
@@ -1962,6 +3111,7 @@ impl eframe::App for SpaceInsightApp {
                         &self.selected_path,
                         &mut new_hovered_path,
                         min_label_area,
+                        highlight,
                     );
 
                     // Process click action
@@ -2032,6 +3182,9 @@ impl eframe::App for SpaceInsightApp {
                             ClickAction::OpenInFileManager { path, is_dir } => {
                                 Self::open_in_file_manager(&path, is_dir);
                             }
+                            ClickAction::Rescan(path) => {
+                                self.start_rescan(path);
+                            }
                         }
                     }
                 }
@@ -2040,6 +3193,7 @@ impl eframe::App for SpaceInsightApp {
             self.hovered_path = new_hovered_path;
 
             if self.is_scanning
+                || self.rescan_path.is_some()
                 || still_animating
                 || self.animator.is_animating
                 || layout_transition_active
@@ -0,0 +1,297 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Kernel-provided filesystems whose "files" are not stored anywhere: sizes are
+/// made up (`/proc/kcore`) and some reads block forever.
+const VIRTUAL_FS_TYPES: &[&str] = &[
+    "proc",
+    "sysfs",
+    "devtmpfs",
+    "devpts",
+    "cgroup",
+    "cgroup2",
+    "debugfs",
+    "tracefs",
+    "securityfs",
+    "pstore",
+    "bpf",
+    "configfs",
+    "fusectl",
+    "mqueue",
+    "hugetlbfs",
+    "binfmt_misc",
+    "efivarfs",
+];
+
+/// Network filesystems, whose reads wait on a server that may be gone. Any FUSE
+/// filesystem (`fuse`, `fuseblk`, `fuse.*`) can likewise stop answering.
+const REMOTE_FS_TYPES: &[&str] = &[
+    "nfs",
+    "nfs4",
+    "cifs",
+    "smb3",
+    "smbfs",
+    "ncpfs",
+    "afs",
+    "9p",
+    "ceph",
+    "glusterfs",
+    "lustre",
+    "davfs",
+];
+
+/// What a scan does with virtual filesystems mounted below its root.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VirtualFsPolicy {
+    /// Leave proc, sysfs, devtmpfs, cgroup and friends as placeholders
+    #[default]
+    Skip,
+    /// Also leave RAM-backed `tmpfs` mounts (`/run`, `/dev/shm`) as placeholders
+    SkipWithTmpfs,
+    /// Walk into them like any other directory
+    Scan,
+}
+
+impl VirtualFsPolicy {
+    pub fn label(&self) -> &'static str {
+        match self {
+            VirtualFsPolicy::Skip => "skip",
+            VirtualFsPolicy::SkipWithTmpfs => "skip + tmpfs",
+            VirtualFsPolicy::Scan => "scan",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            VirtualFsPolicy::Skip => VirtualFsPolicy::SkipWithTmpfs,
+            VirtualFsPolicy::SkipWithTmpfs => VirtualFsPolicy::Scan,
+            VirtualFsPolicy::Scan => VirtualFsPolicy::Skip,
+        }
+    }
+
+    /// Whether a filesystem of this type is left unscanned.
+    pub fn skips(&self, fs_type: &str) -> bool {
+        match self {
+            VirtualFsPolicy::Scan => false,
+            VirtualFsPolicy::Skip => VIRTUAL_FS_TYPES.contains(&fs_type),
+            VirtualFsPolicy::SkipWithTmpfs => fs_type == "tmpfs" || VIRTUAL_FS_TYPES.contains(&fs_type),
+        }
+    }
+}
+
+/// A single mounted filesystem.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MountInfo {
+    pub mount_point: PathBuf,
+    pub fs_type: String,
+    pub source: String,
+}
+
+impl MountInfo {
+    /// Short human-readable description, e.g. `nfs4 server:/export`.
+    pub fn label(&self) -> String {
+        if self.source.is_empty() || self.source == self.fs_type || self.source == "none" {
+            self.fs_type.clone()
+        } else {
+            format!("{} {}", self.fs_type, self.source)
+        }
+    }
+
+    /// Whether reads here can hang on something outside this machine: a network
+    /// filesystem or a FUSE daemon.
+    pub fn is_remote(&self) -> bool {
+        REMOTE_FS_TYPES.contains(&self.fs_type.as_str()) || self.fs_type.starts_with("fuse")
+    }
+}
+
+/// Snapshot of the mount table, used to label filesystem boundaries.
+#[derive(Debug, Clone, Default)]
+pub struct MountTable {
+    mounts: Vec<MountInfo>,
+}
+
+impl MountTable {
+    /// Read the current process' mount table. Empty on platforms without `/proc`.
+    pub fn load() -> Self {
+        #[cfg(target_os = "linux")]
+        {
+            std::fs::read_to_string("/proc/self/mountinfo")
+                .map(|content| Self::parse_mountinfo(&content))
+                .unwrap_or_default()
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        {
+            Self::default()
+        }
+    }
+
+    /// Parse the format of `/proc/<pid>/mountinfo` (see proc(5)).
+    pub fn parse_mountinfo(content: &str) -> Self {
+        let mounts = content
+            .lines()
+            .filter_map(|line| {
+                let (before, after) = line.split_once(" - ")?;
+                let mount_point = before.split(' ').nth(4)?;
+                let mut after = after.split(' ');
+                let fs_type = after.next()?;
+                let source = after.next().unwrap_or("");
+
+                Some(MountInfo {
+                    mount_point: PathBuf::from(unescape_octal(mount_point)),
+                    fs_type: unescape_octal(fs_type),
+                    source: unescape_octal(source),
+                })
+            })
+            .collect();
+
+        Self { mounts }
+    }
+
+    pub fn mounts(&self) -> &[MountInfo] {
+        &self.mounts
+    }
+
+    /// Mount points the policy leaves unscanned, mapped to their labels.
+    /// Later mounts shadow earlier ones at the same point.
+    pub fn skipped_mounts(&self, policy: VirtualFsPolicy) -> HashMap<PathBuf, String> {
+        let mut skipped = HashMap::new();
+        for mount in &self.mounts {
+            if policy.skips(&mount.fs_type) {
+                skipped.insert(mount.mount_point.clone(), mount.label());
+            } else {
+                skipped.remove(&mount.mount_point);
+            }
+        }
+        skipped
+    }
+
+    /// Mount points of network and FUSE filesystems. A local filesystem mounted
+    /// over one shadows it, as in `skipped_mounts`.
+    pub fn remote_mounts(&self) -> HashSet<PathBuf> {
+        let mut remote = HashSet::new();
+        for mount in &self.mounts {
+            if mount.is_remote() {
+                remote.insert(mount.mount_point.clone());
+            } else {
+                remote.remove(&mount.mount_point);
+            }
+        }
+        remote
+    }
+
+    /// The filesystem `path` (canonical) lives on: the mount with the longest
+    /// mount point above it. Later mounts shadow earlier ones.
+    pub fn mount_containing(&self, path: &Path) -> Option<&MountInfo> {
+        self.mounts
+            .iter()
+            .filter(|mount| path.starts_with(&mount.mount_point))
+            .fold(None, |best: Option<&MountInfo>, mount| match best {
+                Some(best) if best.mount_point.as_os_str().len() > mount.mount_point.as_os_str().len() => Some(best),
+                _ => Some(mount),
+            })
+    }
+
+    /// The filesystem mounted exactly at `path`, if any. Later mounts shadow earlier ones.
+    pub fn mount_at(&self, path: &Path) -> Option<&MountInfo> {
+        let canonical = std::fs::canonicalize(path).ok();
+        let target = canonical.as_deref().unwrap_or(path);
+        self.mounts.iter().rev().find(|m| m.mount_point == target)
+    }
+}
+
+/// mountinfo escapes space, tab, newline and backslash as `\ooo`.
+fn unescape_octal(field: &str) -> String {
+    let bytes = field.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'\\' && i + 4 <= bytes.len() {
+            if let Some(value) = std::str::from_utf8(&bytes[i + 1..i + 4])
+                .ok()
+                .and_then(|digits| u8::from_str_radix(digits, 8).ok())
+            {
+                out.push(value);
+                i += 4;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "\
+23 28 0:22 / /proc rw,relatime - proc proc rw
+28 1 254:0 / / rw,relatime shared:1 - ext4 /dev/vda rw
+41 28 0:40 / /mnt/nfs\\040share rw,relatime shared:20 - nfs4 server:/export rw,vers=4.2
+";
+
+    #[test]
+    fn test_parse_mountinfo() {
+        let table = MountTable::parse_mountinfo(SAMPLE);
+        assert_eq!(table.mounts().len(), 3);
+
+        let nfs = &table.mounts()[2];
+        assert_eq!(nfs.mount_point, PathBuf::from("/mnt/nfs share"));
+        assert_eq!(nfs.fs_type, "nfs4");
+        assert_eq!(nfs.label(), "nfs4 server:/export");
+
+        assert_eq!(table.mounts()[0].label(), "proc");
+    }
+
+    #[test]
+    fn test_skipped_mounts() {
+        let table = MountTable::parse_mountinfo(&format!(
+            "{}{}",
+            SAMPLE,
+            "\
+50 28 0:5 / /dev rw,nosuid - devtmpfs devtmpfs rw
+51 50 0:24 / /dev/shm rw,nosuid - tmpfs tmpfs rw
+52 28 0:26 / /sys/fs/cgroup rw - cgroup2 cgroup2 rw
+53 28 0:27 / /mnt/overlay rw - tmpfs tmpfs rw
+54 28 0:28 / /mnt/overlay rw - ext4 /dev/vdb rw
+"
+        ));
+
+        let skipped = table.skipped_mounts(VirtualFsPolicy::Skip);
+        assert_eq!(skipped.len(), 3);
+        assert_eq!(skipped.get(Path::new("/proc")).map(String::as_str), Some("proc"));
+        assert!(skipped.contains_key(Path::new("/sys/fs/cgroup")));
+        assert!(!skipped.contains_key(Path::new("/dev/shm")));
+
+        let with_tmpfs = table.skipped_mounts(VirtualFsPolicy::SkipWithTmpfs);
+        assert!(with_tmpfs.contains_key(Path::new("/dev/shm")));
+        // A real filesystem mounted over the tmpfs is scanned again.
+        assert!(!with_tmpfs.contains_key(Path::new("/mnt/overlay")));
+
+        assert!(table.skipped_mounts(VirtualFsPolicy::Scan).is_empty());
+    }
+
+    #[test]
+    fn test_remote_mounts() {
+        let table = MountTable::parse_mountinfo(&format!(
+            "{}{}",
+            SAMPLE,
+            "\
+60 28 0:50 / /home/me/remote rw - fuse.sshfs me@host: rw
+61 28 0:51 / /mnt/nfs\\040share/local rw - ext4 /dev/vdc rw
+"
+        ));
+        let remote = table.remote_mounts();
+        assert_eq!(remote.len(), 2);
+        assert!(remote.contains(Path::new("/mnt/nfs share")));
+        assert!(remote.contains(Path::new("/home/me/remote")));
+
+        let on = |path: &str| table.mount_containing(Path::new(path)).map(|mount| mount.fs_type.as_str());
+        assert_eq!(on("/mnt/nfs share/a/b"), Some("nfs4"));
+        assert_eq!(on("/mnt/nfs share/local/x"), Some("ext4"));
+        assert_eq!(on("/home/me"), Some("ext4"));
+        assert_eq!(on("/proc/1"), Some("proc"));
+    }
+}
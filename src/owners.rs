@@ -0,0 +1,138 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::entry_meta::Owner;
+use crate::file_types::UsageTotals;
+use crate::tree::SizeMode;
+
+/// User and group names from the local passwd and group files.
+///
+/// Ids without an entry (network accounts, deleted users) show as their number.
+#[derive(Debug, Clone, Default)]
+pub struct OwnerNames {
+    users: HashMap<u32, String>,
+    groups: HashMap<u32, String>,
+}
+
+impl OwnerNames {
+    /// Read `/etc/passwd` and `/etc/group`; missing or unreadable files leave ids unnamed.
+    pub fn load() -> Self {
+        Self::from_files(Path::new("/etc/passwd"), Path::new("/etc/group"))
+    }
+
+    pub fn from_files(passwd: &Path, group: &Path) -> Self {
+        let read = |path: &Path| std::fs::read_to_string(path).unwrap_or_default();
+        Self {
+            users: parse_id_names(&read(passwd)),
+            groups: parse_id_names(&read(group)),
+        }
+    }
+
+    pub fn user(&self, uid: u32) -> String {
+        self.users.get(&uid).cloned().unwrap_or_else(|| uid.to_string())
+    }
+
+    pub fn group(&self, gid: u32) -> String {
+        self.groups.get(&gid).cloned().unwrap_or_else(|| gid.to_string())
+    }
+}
+
+/// `name:password:id:...` lines, the shape shared by passwd and group files.
+/// The first name wins when an id appears twice.
+fn parse_id_names(text: &str) -> HashMap<u32, String> {
+    let mut names = HashMap::new();
+    for line in text.lines() {
+        if line.starts_with('#') {
+            continue;
+        }
+        let mut fields = line.split(':');
+        let (Some(name), Some(_), Some(id)) = (fields.next(), fields.next(), fields.next()) else {
+            continue;
+        };
+        if let (false, Ok(id)) = (name.is_empty(), id.parse()) {
+            names.entry(id).or_insert_with(|| name.to_string());
+        }
+    }
+    names
+}
+
+/// Charged files of a folder or a whole scan, totalled per owning user and group.
+/// Shared hardlinks and special files are left out, as in the folder sizes.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OwnerUsage {
+    pub by_user: HashMap<u32, UsageTotals>,
+    pub by_group: HashMap<u32, UsageTotals>,
+}
+
+impl OwnerUsage {
+    pub fn add(&mut self, owner: Owner, size: u64, allocated_size: u64) {
+        self.by_user.entry(owner.uid).or_default().add(size, allocated_size);
+        self.by_group.entry(owner.gid).or_default().add(size, allocated_size);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_user.is_empty()
+    }
+
+    pub fn total(&self) -> UsageTotals {
+        let mut total = UsageTotals::default();
+        for totals in self.by_user.values() {
+            total.files += totals.files;
+            total.size += totals.size;
+            total.allocated_size += totals.allocated_size;
+        }
+        total
+    }
+
+    /// Users, largest first in `mode`; ties go by uid.
+    pub fn users_by_size(&self, mode: SizeMode) -> Vec<(u32, UsageTotals)> {
+        ranked(&self.by_user, mode)
+    }
+
+    /// Groups, largest first in `mode`; ties go by gid.
+    pub fn groups_by_size(&self, mode: SizeMode) -> Vec<(u32, UsageTotals)> {
+        ranked(&self.by_group, mode)
+    }
+}
+
+fn ranked(totals: &HashMap<u32, UsageTotals>, mode: SizeMode) -> Vec<(u32, UsageTotals)> {
+    let mut rows: Vec<_> = totals.iter().map(|(id, totals)| (*id, *totals)).collect();
+    rows.sort_by(|a, b| b.1.size_in(mode).cmp(&a.1.size_in(mode)).then(a.0.cmp(&b.0)));
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_passwd_and_group() {
+        let passwd = "# local accounts\nroot:x:0:0:root:/root:/bin/bash\nalice:x:1000:1000::/home/alice:/bin/sh\nalias:x:1000:1000::/:/bin/sh\nbroken line\n";
+        let users = parse_id_names(passwd);
+        assert_eq!(users.get(&0).map(String::as_str), Some("root"));
+        assert_eq!(users.get(&1000).map(String::as_str), Some("alice"));
+        assert_eq!(users.len(), 2);
+
+        let groups = parse_id_names("wheel:x:10:alice,bob\nbuild::2000:\n");
+        assert_eq!(groups.get(&2000).map(String::as_str), Some("build"));
+
+        let names = OwnerNames { users, groups };
+        assert_eq!(names.user(1000), "alice");
+        assert_eq!(names.user(4242), "4242");
+        assert_eq!(names.group(10), "wheel");
+    }
+
+    #[test]
+    fn test_usage_ranks_users() {
+        let mut usage = OwnerUsage::default();
+        usage.add(Owner { uid: 1000, gid: 100 }, 300, 4096);
+        usage.add(Owner { uid: 1001, gid: 100 }, 500, 512);
+        usage.add(Owner { uid: 1000, gid: 100 }, 300, 4096);
+
+        let users: Vec<_> = usage.users_by_size(SizeMode::Apparent).into_iter().map(|(uid, _)| uid).collect();
+        assert_eq!(users, [1000, 1001]);
+        assert_eq!(usage.users_by_size(SizeMode::Allocated)[0].1.allocated_size, 8192);
+        assert_eq!(usage.groups_by_size(SizeMode::Apparent), [(100, usage.total())]);
+        assert_eq!(usage.total().files, 3);
+    }
+}
@@ -1,21 +1,11 @@
 use glam::Vec2;
-use std::path::Path;
+
+use crate::file_types::FileCategory;
 
 /// Golden ratio constant for center positioning
 const GOLDEN_RATIO_X: f32 = 0.38;
 const GOLDEN_RATIO_Y: f32 = 0.62;
 
-/// File category determines angular cluster position
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub enum FileCategory {
-    Media,      // Images, video, audio
-    Code,       // Source files
-    Archive,    // Compressed archives
-    Document,   // Text, PDF, etc.
-    System,     // Hidden, system files
-    Other,
-}
-
 impl FileCategory {
     /// Get base angle in radians for category clustering
     pub fn base_angle(&self) -> f32 {
@@ -28,53 +18,6 @@ impl FileCategory {
             FileCategory::Other => 0.0_f32.to_radians(),       // 12 o'clock - neutral
         }
     }
-
-    /// Get color hue for category (0-360 degrees)
-    pub fn hue_degrees(&self) -> f32 {
-        match self {
-            FileCategory::Media => 30.0,       // Warm amber/orange
-            FileCategory::Code => 220.0,       // Cool blue
-            FileCategory::Archive => 270.0,    // Purple
-            FileCategory::Document => 160.0,   // Teal
-            FileCategory::System => 240.0,     // Deep blue
-            FileCategory::Other => 0.0,        // Red
-        }
-    }
-
-    /// Classify file by extension
-    pub fn from_path(path: &Path) -> Self {
-        if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
-            let ext = ext.to_lowercase();
-            match ext.as_str() {
-                // Media
-                "jpg" | "jpeg" | "png" | "gif" | "bmp" | "svg" | "webp" |
-                "mp4" | "mov" | "avi" | "mkv" | "flv" | "wmv" |
-                "mp3" | "wav" | "flac" | "aac" | "ogg" | "m4a" => FileCategory::Media,
-                
-                // Code
-                "rs" | "py" | "js" | "ts" | "jsx" | "tsx" | "cpp" | "c" | "h" | "hpp" |
-                "java" | "go" | "rb" | "php" | "swift" | "kt" | "cs" | "m" | "mm" |
-                "html" | "css" | "scss" | "sass" | "vue" | "svelte" => FileCategory::Code,
-                
-                // Archives
-                "zip" | "tar" | "gz" | "bz2" | "xz" | "7z" | "rar" | "dmg" | "iso" => FileCategory::Archive,
-                
-                // Documents
-                "pdf" | "doc" | "docx" | "txt" | "md" | "rtf" | "odt" |
-                "xls" | "xlsx" | "csv" | "ppt" | "pptx" => FileCategory::Document,
-                
-                _ => FileCategory::Other,
-            }
-        } else {
-            // Check for hidden/system files
-            if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                if name.starts_with('.') {
-                    return FileCategory::System;
-                }
-            }
-            FileCategory::Other
-        }
-    }
 }
 
 /// Radial node with orbital positioning
@@ -255,15 +198,6 @@ fn apply_magnetic_repulsion(nodes: &mut [RadialNode], iterations: usize) {
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_file_category_classification() {
-        assert_eq!(FileCategory::from_path(Path::new("image.jpg")), FileCategory::Media);
-        assert_eq!(FileCategory::from_path(Path::new("code.rs")), FileCategory::Code);
-        assert_eq!(FileCategory::from_path(Path::new("archive.zip")), FileCategory::Archive);
-        assert_eq!(FileCategory::from_path(Path::new("doc.pdf")), FileCategory::Document);
-        assert_eq!(FileCategory::from_path(Path::new(".gitignore")), FileCategory::System);
-    }
-
     #[test]
     fn test_orbital_distance_logarithmic() {
         let items = vec![
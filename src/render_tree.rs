@@ -2,8 +2,9 @@ use std::collections::{hash_map::DefaultHasher, HashSet};
 use std::hash::{Hash, Hasher};
 use std::path::PathBuf;
 
+use crate::entry_meta::FileKind;
 use crate::expand_state::ExpansionState;
-use crate::tree::FileTree;
+use crate::tree::{FileTree, Placeholder};
 use crate::treemap::{Rect, SquarifiedTreemap, TreemapItem};
 
 /// Visible border thickness (drawn).
@@ -36,6 +37,10 @@ const SPLIT_MIN_AREA_PCT: f32 = 0.0;
 const SPLIT_PREFERRED_MAX_ITEMS: usize = usize::MAX;
 const SPLIT_MAX_AGGREGATE_FRACTION: f32 = 1.0;
 
+/// Layout share given to zero-byte marker tiles (e.g. skipped mount points)
+/// so they stay visible instead of vanishing into empty space.
+const MARKER_WEIGHT_FRACTION: f64 = 0.02;
+
 #[derive(Clone, Copy)]
 struct AggregatePolicy {
     min_useful_area: f32,
@@ -76,6 +81,20 @@ pub struct RenderNode {
     pub is_aggregate: bool,
     /// Number of items aggregated (only meaningful if is_aggregate).
     pub aggregate_count: usize,
+    /// Hardlink whose bytes are charged to another path.
+    pub is_shared_link: bool,
+    /// Directory whose contents were deliberately not scanned.
+    pub placeholder: Option<Placeholder>,
+    /// Matched by a `.gitignore`/`.ignore` rule.
+    pub is_ignored: bool,
+    /// Ignored bytes inside this node (part of `size`).
+    pub ignored_size: u64,
+    /// Something at or below this node could not be read; `size` is a lower bound.
+    pub partial: bool,
+    /// Target of a symbolic link.
+    pub symlink_target: Option<PathBuf>,
+    /// Socket, FIFO or device node, drawn as a marker since it holds no bytes.
+    pub special: Option<FileKind>,
 }
 
 fn path_hash(path: &PathBuf) -> u64 {
@@ -90,7 +109,16 @@ struct ChildInfo {
     path: PathBuf,
     name: String,
     size: u64,
+    /// Layout weight; equals `size` except for zero-byte marker tiles.
+    weight: u64,
     is_dir: bool,
+    is_shared_link: bool,
+    placeholder: Option<Placeholder>,
+    is_ignored: bool,
+    ignored_size: u64,
+    partial: bool,
+    symlink_target: Option<PathBuf>,
+    special: Option<FileKind>,
 }
 
 /// Partition children into (kept individually, aggregated into grey block).
@@ -105,7 +133,7 @@ struct ChildInfo {
 /// and the visible layout has a manageable number of well-sized items.
 fn partition_children(
     children: &[ChildInfo],
-    total_size: u64,
+    total_weight: u64,
     container_area: f32,
     policy: AggregatePolicy,
 ) -> (Vec<usize>, Vec<usize>) {
@@ -113,7 +141,7 @@ fn partition_children(
     if n == 0 {
         return (vec![], vec![]);
     }
-    // children are already sorted by size descending
+    // children are already sorted by weight descending
 
     let min_area = policy.min_useful_area.max(container_area * policy.min_area_pct);
 
@@ -121,9 +149,9 @@ fn partition_children(
     let mut kept: Vec<usize> = Vec::new();
     let mut aggregated: Vec<usize> = Vec::new();
 
-    for i in 0..n {
-        let estimated_area = if total_size > 0 {
-            children[i].size as f32 / total_size as f32 * container_area
+    for (i, child) in children.iter().enumerate() {
+        let estimated_area = if total_weight > 0 {
+            child.weight as f32 / total_weight as f32 * container_area
         } else {
             0.0
         };
@@ -141,14 +169,14 @@ fn partition_children(
     }
 
     // Phase 3: budget rescue — if aggregate too large, pull items back
-    let budget = total_size as f64 * policy.max_aggregate_fraction as f64;
-    let mut agg_total: u64 = aggregated.iter().map(|&i| children[i].size).sum();
+    let budget = total_weight as f64 * policy.max_aggregate_fraction as f64;
+    let mut agg_total: u64 = aggregated.iter().map(|&i| children[i].weight).sum();
 
     while agg_total as f64 > budget && !aggregated.is_empty() {
         // aggregated is ordered: largest first (from phase 2 inserts), then smallest
         // Pull the largest item (first element) back to kept
         let rescued = aggregated.remove(0);
-        agg_total -= children[rescued].size;
+        agg_total -= children[rescued].weight;
         kept.push(rescued);
     }
 
@@ -159,7 +187,7 @@ fn partition_children(
     }
 
     // Re-sort kept by size descending for consistent layout
-    kept.sort_by(|&a, &b| children[b].size.cmp(&children[a].size));
+    kept.sort_by(|&a, &b| children[b].weight.cmp(&children[a].weight));
 
     (kept, aggregated)
 }
@@ -195,7 +223,15 @@ pub fn build_render_tree(
                     path: data.path.clone(),
                     name: data.name.clone(),
                     size: data.cumulative_size,
+                    weight: data.cumulative_size,
                     is_dir: data.is_dir,
+                    is_shared_link: data.shared_link,
+                    placeholder: data.placeholder.clone(),
+                    is_ignored: data.ignored,
+                    ignored_size: data.ignored_size,
+                    partial: data.partial,
+                    symlink_target: data.symlink_target.clone(),
+                    special: data.special,
                 }
             })
         })
@@ -205,18 +241,28 @@ pub fn build_render_tree(
         return Vec::new();
     }
 
-    // Sort by size descending
-    children.sort_by(|a, b| b.size.cmp(&a.size));
-
+    // Empty markers get a small fixed share so they remain visible.
     let total_size: u64 = children.iter().map(|c| c.size).sum();
+    let marker_weight = ((total_size as f64 * MARKER_WEIGHT_FRACTION) as u64).max(1);
+    for child in &mut children {
+        if child.size == 0 && (child.placeholder.is_some() || child.special.is_some()) {
+            child.weight = marker_weight;
+        }
+    }
+
+    // Sort by weight descending
+    children.sort_by_key(|child| std::cmp::Reverse(child.weight));
+
+    let total_weight: u64 = children.iter().map(|c| c.weight).sum();
     let container_area = container.area();
 
     let (kept_indices, agg_indices) =
-        partition_children(&children, total_size, container_area, aggregation_policy);
+        partition_children(&children, total_weight, container_area, aggregation_policy);
 
     let aggregate_size: u64 = agg_indices.iter().map(|&i| children[i].size).sum();
+    let aggregate_weight: u64 = agg_indices.iter().map(|&i| children[i].weight).sum();
     let aggregate_count = agg_indices.len();
-    let has_aggregate = aggregate_count > 0 && aggregate_size > 0;
+    let has_aggregate = aggregate_count > 0 && aggregate_weight > 0;
 
     let aggregate_path = container_root_path(root_id, arena);
     let aggregate_item_index = kept_indices.len(); // treemap index for aggregate
@@ -226,14 +272,14 @@ pub fn build_render_tree(
         .iter()
         .enumerate()
         .map(|(treemap_idx, &child_idx)| TreemapItem {
-            size: children[child_idx].size,
+            size: children[child_idx].weight,
             index: treemap_idx,
         })
         .collect();
 
     if has_aggregate {
         items.push(TreemapItem {
-            size: aggregate_size,
+            size: aggregate_weight,
             index: aggregate_item_index,
         });
     }
@@ -263,6 +309,13 @@ pub fn build_render_tree(
                 stable_id: path_hash(&aggregate_path) ^ 0xA66E,
                 is_aggregate: true,
                 aggregate_count,
+                is_shared_link: false,
+                placeholder: None,
+                is_ignored: false,
+                ignored_size: 0,
+                partial: false,
+                symlink_target: None,
+                special: None,
             });
             continue;
         }
@@ -274,7 +327,8 @@ pub fn build_render_tree(
         let child = &children[child_idx];
 
         let exp_depth = expansion.depth(&child.path);
-        let is_expanded = child.is_dir && exp_depth > 0 && max_depth > 0;
+        let is_expanded =
+            child.is_dir && child.placeholder.is_none() && exp_depth > 0 && max_depth > 0;
 
         let outer_rect = lr.rect;
 
@@ -315,6 +369,13 @@ pub fn build_render_tree(
             stable_id: path_hash(&child.path),
             is_aggregate: false,
             aggregate_count: 0,
+            is_shared_link: child.is_shared_link,
+            placeholder: child.placeholder.clone(),
+            is_ignored: child.is_ignored,
+            ignored_size: child.ignored_size,
+            partial: child.partial,
+            symlink_target: child.symlink_target.clone(),
+            special: child.special,
         });
     }
 
@@ -352,7 +413,23 @@ mod tests {
                     path: PathBuf::from(format!("item_{}", i)),
                     name: format!("item_{}", i),
                     size: s,
+                    allocated_size: s,
                     cumulative_size: s,
+                    shared_link: false,
+                    shared_size: 0,
+                    placeholder: None,
+                    ignored: false,
+                    ignored_size: 0,
+                    read_error: false,
+                    partial: false,
+                    symlink_target: None,
+                    meta: None,
+                    special: None,
+                    owner: Default::default(),
+                    modified: 0,
+                    dir_stamp: None,
+                    age: Default::default(),
+                    file_types: None,
                     is_dir: false,
                 });
                 ChildInfo {
@@ -360,11 +437,19 @@ mod tests {
                     path: PathBuf::from(format!("item_{}", i)),
                     name: format!("item_{}", i),
                     size: s,
+                    weight: s,
                     is_dir: false,
+                    is_shared_link: false,
+                    placeholder: None,
+                    is_ignored: false,
+                    ignored_size: 0,
+                    partial: false,
+                    symlink_target: None,
+                    special: None,
                 }
             })
             .collect();
-        v.sort_by(|a, b| b.size.cmp(&a.size));
+        v.sort_by_key(|child| std::cmp::Reverse(child.size));
         (arena, v)
     }
 
@@ -372,11 +457,9 @@ mod tests {
     fn test_dominant_item_small_aggregate() {
         // 90% dominant + 9% medium + 20 tiny items = 1%
         let mut sizes = vec![9000, 900];
-        for _ in 0..20 {
-            sizes.push(5); // 20 × 5 = 100 = 1%
-        }
+        sizes.extend([5; 20]); // 20 × 5 = 100 = 1%
         let (_arena, children) = make_children(&sizes);
-        let total: u64 = children.iter().map(|c| c.size).sum();
+        let total: u64 = children.iter().map(|c| c.weight).sum();
         let container_area = 936_000.0;
 
         let (kept, agg) =
@@ -396,7 +479,7 @@ mod tests {
         // 50 items of equal size — aggregate should not dominate
         let sizes: Vec<u64> = vec![100; 50];
         let (_arena, children) = make_children(&sizes);
-        let total: u64 = children.iter().map(|c| c.size).sum();
+        let total: u64 = children.iter().map(|c| c.weight).sum();
         let container_area = 936_000.0;
 
         let (kept, agg) =
@@ -407,7 +490,7 @@ mod tests {
         // With 50 equal items, budget rescue should prevent the aggregate from being huge
         assert!(agg_frac <= MAX_AGGREGATE_FRACTION + 0.01,
                 "Aggregate fraction {:.1}% exceeds budget", agg_frac * 100.0);
-        assert!(kept.len() >= 1, "Should keep at least 1 item");
+        assert!(!kept.is_empty(), "Should keep at least 1 item");
     }
 
     #[test]
@@ -415,7 +498,7 @@ mod tests {
         // 3 large items — nothing should be aggregated
         let sizes = vec![5000, 3000, 2000];
         let (_arena, children) = make_children(&sizes);
-        let total: u64 = children.iter().map(|c| c.size).sum();
+        let total: u64 = children.iter().map(|c| c.weight).sum();
         let container_area = 936_000.0;
 
         let (kept, agg) =
@@ -425,11 +508,50 @@ mod tests {
         assert_eq!(agg.len(), 0);
     }
 
+    #[test]
+    fn test_placeholder_marker_stays_visible() {
+        let mut tree = FileTree::new("/scan");
+        tree.upsert_node(PathBuf::from("/scan/big.bin"), 1_000_000, false);
+        tree.upsert_file_node(crate::crawler::FileNode {
+            path: PathBuf::from("/scan/mnt"),
+            is_dir: true,
+            placeholder: Some(Placeholder::MountPoint("nfs4 server:/export".to_string())),
+            ..Default::default()
+        });
+        tree.upsert_file_node(crate::crawler::FileNode {
+            path: PathBuf::from("/scan/app.sock"),
+            special: Some(FileKind::Socket),
+            ..Default::default()
+        });
+        tree.calculate_sizes();
+
+        let nodes = build_render_tree(
+            &tree,
+            tree.get_root(),
+            Rect::new(0.0, 0.0, 800.0, 600.0),
+            &ExpansionState::default(),
+            4,
+            &HashSet::new(),
+        );
+
+        let marker = nodes
+            .iter()
+            .find(|node| node.placeholder.is_some())
+            .expect("mount placeholder should get its own tile");
+        assert_eq!(marker.size, 0);
+        assert!(marker.outer_rect.area() > 0.0);
+        let socket = nodes
+            .iter()
+            .find(|node| node.special == Some(FileKind::Socket))
+            .expect("special files get a marker tile too");
+        assert!(socket.outer_rect.area() > 0.0);
+    }
+
     #[test]
     fn test_split_policy_keeps_more_items() {
         let sizes: Vec<u64> = vec![100; 50];
         let (_arena, children) = make_children(&sizes);
-        let total: u64 = children.iter().map(|c| c.size).sum();
+        let total: u64 = children.iter().map(|c| c.weight).sum();
         let container_area = 936_000.0;
 
         let (default_kept, _default_agg) =
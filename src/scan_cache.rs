@@ -0,0 +1,312 @@
+use serde::{Deserialize, Serialize};
+use std::ffi::OsString;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use indextree::NodeId;
+
+use crate::crawler::{FileNode, ScanEstimate, ScanOptions};
+use crate::entry_meta::{DirStamp, EntryMeta, FileKind, Owner};
+use crate::tree::{FileTree, Placeholder};
+
+/// Bumped whenever the saved layout changes; older files are ignored.
+const FORMAT_VERSION: u32 = 1;
+/// `CachedEntry::parent` of the scanned paths themselves
+const NO_PARENT: u64 = u64::MAX;
+
+/// Finished scans saved on disk, one file per set of scanned paths, for
+/// `FileCrawler::set_previous_scan` to reuse on the next scan of them.
+/// Their sizes are kept apart, for `FileCrawler::set_estimate`.
+#[derive(Debug, Clone)]
+pub struct ScanCache {
+    dir: PathBuf,
+}
+
+/// What a saved scan starts with; the entries follow it one by one.
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheHeader {
+    version: u32,
+    /// The scanned paths as they were given, which is how the tree spells them
+    roots: Vec<PathBuf>,
+    /// The same paths resolved, so a relative path is only reused from the same directory
+    canonical_roots: Vec<PathBuf>,
+    options: String,
+    entries: u64,
+}
+
+/// How big the last finished scan of a set of paths was.
+#[derive(Debug, Serialize, Deserialize)]
+struct EstimateRecord {
+    version: u32,
+    canonical_roots: Vec<PathBuf>,
+    estimate: ScanEstimate,
+}
+
+/// One tree node, stored by name below an earlier entry.
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedEntry {
+    /// Index of the parent entry, or `NO_PARENT` for a scanned path
+    parent: u64,
+    name: OsString,
+    size: u64,
+    allocated_size: u64,
+    is_dir: bool,
+    shared_link: bool,
+    placeholder: Option<Placeholder>,
+    ignored: bool,
+    read_error: bool,
+    symlink_target: Option<PathBuf>,
+    meta: Option<EntryMeta>,
+    special: Option<FileKind>,
+    owner: Owner,
+    modified: i64,
+    dir_stamp: Option<DirStamp>,
+}
+
+impl ScanCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// `$XDG_CACHE_HOME/spaceinsight/scans`, falling back to `~/.cache`.
+    pub fn default_location() -> Option<Self> {
+        let base = std::env::var_os("XDG_CACHE_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))?;
+        Some(Self::new(base.join("spaceinsight").join("scans")))
+    }
+
+    /// The saved scan of `roots`, if there is one taken with the same options.
+    /// Unreadable or outdated files count as missing.
+    pub fn load<P: AsRef<Path>>(&self, roots: &[P], options: &ScanOptions) -> Option<FileTree> {
+        let canonical_roots = canonical(roots);
+        let file = File::open(self.file_for(&canonical_roots, "scan")).ok()?;
+        let mut reader = BufReader::new(file);
+        let header: CacheHeader = bincode::deserialize_from(&mut reader).ok()?;
+        if header.version != FORMAT_VERSION
+            || header.canonical_roots != canonical_roots
+            || header.options != options_key(options)
+        {
+            return None;
+        }
+
+        let mut tree = FileTree::with_roots(&header.roots);
+        let root_ids = tree.scan_root_ids().to_vec();
+        let mut ids: Vec<NodeId> = Vec::new();
+        for _ in 0..header.entries {
+            let entry: CachedEntry = bincode::deserialize_from(&mut reader).ok()?;
+            let read_error = entry.read_error;
+            let node_id = if entry.parent == NO_PARENT {
+                let node_id = *root_ids.get(ids.len())?;
+                let path = tree.get_arena()[node_id].get().path.clone();
+                tree.apply_root_entry(node_id, entry.into_file_node(path));
+                node_id
+            } else {
+                let parent = *ids.get(entry.parent as usize)?;
+                let path = tree.get_arena()[parent].get().path.join(&entry.name);
+                tree.append_file_node(parent, entry.into_file_node(path))
+            };
+            if read_error {
+                let path = tree.get_arena()[node_id].get().path.clone();
+                tree.mark_read_error(&path);
+            }
+            ids.push(node_id);
+        }
+        tree.calculate_sizes();
+        Some(tree)
+    }
+
+    /// Save `tree` as the latest scan of its paths. Cancelled scans are not
+    /// saved, so a later scan never mistakes a missing folder for an empty one.
+    pub fn store(&self, tree: &FileTree, options: &ScanOptions) -> io::Result<()> {
+        if tree.is_incomplete() {
+            return Ok(());
+        }
+        let roots: Vec<PathBuf> = tree.scan_root_paths().into_iter().map(Path::to_path_buf).collect();
+        let canonical_roots = canonical(&roots);
+        let arena = tree.get_arena();
+        let header = CacheHeader {
+            version: FORMAT_VERSION,
+            roots,
+            canonical_roots,
+            options: options_key(options),
+            entries: tree
+                .scan_root_ids()
+                .iter()
+                .map(|id| id.descendants(arena).count() as u64)
+                .sum(),
+        };
+
+        std::fs::create_dir_all(&self.dir)?;
+        let path = self.file_for(&header.canonical_roots, "scan");
+        // Written beside the old file and renamed over it, so a crash never leaves half a scan.
+        let partial = path.with_extension("tmp");
+        let mut writer = BufWriter::new(File::create(&partial)?);
+        bincode::serialize_into(&mut writer, &header).map_err(to_io)?;
+
+        let mut index = 0u64;
+        let mut pending: Vec<(NodeId, u64)> =
+            tree.scan_root_ids().iter().rev().map(|id| (*id, NO_PARENT)).collect();
+        while let Some((node_id, parent)) = pending.pop() {
+            let data = arena[node_id].get();
+            let entry = CachedEntry {
+                parent,
+                name: data.path.file_name().map(OsString::from).unwrap_or_default(),
+                size: data.size,
+                allocated_size: data.allocated_size,
+                is_dir: data.is_dir,
+                shared_link: data.shared_link,
+                placeholder: data.placeholder.clone(),
+                ignored: data.ignored,
+                read_error: data.read_error,
+                symlink_target: data.symlink_target.clone(),
+                meta: data.meta.as_deref().cloned(),
+                special: data.special,
+                owner: data.owner,
+                modified: data.modified,
+                dir_stamp: data.dir_stamp,
+            };
+            bincode::serialize_into(&mut writer, &entry).map_err(to_io)?;
+            let children: Vec<NodeId> = node_id.children(arena).collect();
+            pending.extend(children.into_iter().rev().map(|child| (child, index)));
+            index += 1;
+        }
+        writer.flush()?;
+        drop(writer);
+        std::fs::rename(&partial, &path)
+    }
+
+    /// Entry count and duration of the last finished scan of `roots`.
+    pub fn load_estimate<P: AsRef<Path>>(&self, roots: &[P]) -> Option<ScanEstimate> {
+        let canonical_roots = canonical(roots);
+        let file = File::open(self.file_for(&canonical_roots, "estimate")).ok()?;
+        let record: EstimateRecord = bincode::deserialize_from(BufReader::new(file)).ok()?;
+        (record.version == FORMAT_VERSION && record.canonical_roots == canonical_roots).then_some(record.estimate)
+    }
+
+    /// Remember how big a finished scan of `roots` was. Kept even when the
+    /// tree itself isn't stored, since it is only a few bytes.
+    pub fn store_estimate<P: AsRef<Path>>(&self, roots: &[P], estimate: ScanEstimate) -> io::Result<()> {
+        let canonical_roots = canonical(roots);
+        let record = EstimateRecord {
+            version: FORMAT_VERSION,
+            canonical_roots,
+            estimate,
+        };
+        std::fs::create_dir_all(&self.dir)?;
+        let bytes = bincode::serialize(&record).map_err(to_io)?;
+        std::fs::write(self.file_for(&record.canonical_roots, "estimate"), bytes)
+    }
+
+    /// One file of each kind per set of scanned paths, named by a hash of them.
+    fn file_for(&self, canonical_roots: &[PathBuf], extension: &str) -> PathBuf {
+        // FNV-1a: stable across runs and Rust versions, unlike `DefaultHasher`.
+        let mut hash = 0xcbf2_9ce4_8422_2325u64;
+        for root in canonical_roots {
+            for byte in root.as_os_str().as_encoded_bytes().iter().chain([&0u8]) {
+                hash ^= u64::from(*byte);
+                hash = hash.wrapping_mul(0x0100_0000_01b3);
+            }
+        }
+        self.dir.join(format!("{:016x}.{}", hash, extension))
+    }
+}
+
+impl CachedEntry {
+    fn into_file_node(self, path: PathBuf) -> FileNode {
+        FileNode {
+            path,
+            size: self.size,
+            allocated_size: self.allocated_size,
+            is_dir: self.is_dir,
+            shared_link: self.shared_link,
+            placeholder: self.placeholder,
+            ignored: self.ignored,
+            symlink_target: self.symlink_target,
+            meta: self.meta.map(Box::new),
+            special: self.special,
+            owner: self.owner,
+            modified: self.modified,
+            dir_stamp: self.dir_stamp,
+        }
+    }
+}
+
+fn canonical<P: AsRef<Path>>(roots: &[P]) -> Vec<PathBuf> {
+    roots
+        .iter()
+        .map(|root| std::fs::canonicalize(root).unwrap_or_else(|_| root.as_ref().to_path_buf()))
+        .collect()
+}
+
+/// The options that decide what ends up in the tree; throttling, the scan order and the read timeout don't.
+fn options_key(options: &ScanOptions) -> String {
+    format!(
+        "{:?} {:?} {} {:?} {:?} {:?} {:?}",
+        options.one_file_system,
+        options.exclude.rules(),
+        options.exclude.min_file_size,
+        options.ignore_files,
+        options.symlinks,
+        options.metadata,
+        options.virtual_fs,
+    )
+}
+
+fn to_io(err: bincode::Error) -> io::Error {
+    io::Error::other(err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crawler::FileCrawler;
+
+    #[test]
+    fn test_store_and_load_round_trip() {
+        let base = std::env::temp_dir().join(format!("spaceinsight-cache-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&base);
+        let root = base.join("data");
+        std::fs::create_dir_all(root.join("sub/deeper")).unwrap();
+        std::fs::write(root.join("top.bin"), vec![0u8; 700]).unwrap();
+        std::fs::write(root.join("sub/deeper/leaf.txt"), vec![0u8; 40]).unwrap();
+        let cache = ScanCache::new(base.join("cache"));
+        let options = ScanOptions::default();
+
+        let mut crawler = FileCrawler::with_options(options.clone());
+        let (tree, _) = crawler.scan_with_progress(&root, None);
+        cache.store(&tree, &options).unwrap();
+        let loaded = cache.load(&[&root], &options).unwrap();
+
+        let paths = |tree: &FileTree| -> Vec<(PathBuf, u64, Option<DirStamp>)> {
+            let arena = tree.get_arena();
+            tree.get_root()
+                .descendants(arena)
+                .map(|id| arena[id].get())
+                .map(|node| (node.path.clone(), node.cumulative_size, node.dir_stamp))
+                .collect()
+        };
+        assert_eq!(paths(&loaded), paths(&tree));
+        assert!(loaded.get_node(&root).and_then(|id| loaded.get_arena()[id].get().dir_stamp).is_some());
+
+        let other = ScanOptions {
+            one_file_system: true,
+            ..ScanOptions::default()
+        };
+        assert!(cache.load(&[&root], &other).is_none());
+        assert!(cache.load(&[base.join("elsewhere")], &options).is_none());
+
+        crawler.set_previous_scan(loaded);
+        let (_, stats) = crawler.scan_with_progress(&root, None);
+        assert_eq!((stats.reused_dirs, stats.reread_dirs), (3, 0));
+        assert_eq!(stats.total_size, 740);
+
+        assert!(cache.load_estimate(&[&root]).is_none());
+        cache.store_estimate(&[&root], stats.estimate()).unwrap();
+        let estimate = cache.load_estimate(&[&root]);
+        let _ = std::fs::remove_dir_all(&base);
+        assert_eq!(estimate.map(|e| e.entries), Some(5));
+    }
+}
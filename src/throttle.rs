@@ -0,0 +1,175 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Longest single sleep of a throttled worker, so cancellation is still noticed quickly.
+const MAX_THROTTLE_SLEEP: Duration = Duration::from_millis(100);
+
+/// How hard a scan may lean on the machine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScanProfile {
+    /// Every core, no rate cap
+    #[default]
+    Fast,
+    /// Few threads, capped entry rate and idle I/O priority, for busy or spinning disks
+    Gentle,
+}
+
+impl ScanProfile {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ScanProfile::Fast => "fast",
+            ScanProfile::Gentle => "gentle",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            ScanProfile::Fast => ScanProfile::Gentle,
+            ScanProfile::Gentle => ScanProfile::Fast,
+        }
+    }
+
+    /// The limits this profile starts from; callers may tune them afterwards.
+    pub fn throttle(&self) -> ScanThrottle {
+        match self {
+            ScanProfile::Fast => ScanThrottle::default(),
+            ScanProfile::Gentle => ScanThrottle {
+                threads: Some(2),
+                max_entries_per_sec: Some(5_000),
+                idle_io: true,
+            },
+        }
+    }
+}
+
+/// Resource limits for one scan. The default leaves the scan unthrottled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ScanThrottle {
+    /// Worker threads reading directories; `None` keeps the crawler's usual pool size.
+    pub threads: Option<usize>,
+    /// Upper bound on entries listed per second across all workers
+    pub max_entries_per_sec: Option<u64>,
+    /// Put the scan's workers in the idle I/O class (Linux only; ignored elsewhere)
+    pub idle_io: bool,
+}
+
+impl ScanThrottle {
+    pub fn is_unthrottled(&self) -> bool {
+        *self == ScanThrottle::default()
+    }
+
+    /// The profile these limits belong to; any limit at all counts as gentle.
+    pub fn profile(&self) -> ScanProfile {
+        if self.is_unthrottled() {
+            ScanProfile::Fast
+        } else {
+            ScanProfile::Gentle
+        }
+    }
+
+    /// Short description, e.g. `2 threads, 5000 entries/s, idle I/O`.
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(threads) = self.threads {
+            parts.push(format!("{} threads", threads));
+        }
+        if let Some(rate) = self.max_entries_per_sec {
+            parts.push(format!("{} entries/s", rate));
+        }
+        if self.idle_io {
+            parts.push("idle I/O".to_string());
+        }
+        if parts.is_empty() {
+            "unthrottled".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+}
+
+/// Shared entries-per-second budget. Workers that get ahead of it sleep.
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    per_sec: u64,
+    started: Instant,
+    admitted: AtomicU64,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(per_sec: u64) -> Self {
+        Self {
+            per_sec: per_sec.max(1),
+            started: Instant::now(),
+            admitted: AtomicU64::new(0),
+        }
+    }
+
+    /// Charge `entries` against the budget and return how long the caller is ahead of it.
+    pub(crate) fn admit(&self, entries: u64) -> Duration {
+        let admitted = self.admitted.fetch_add(entries, Ordering::Relaxed) + entries;
+        let due = Duration::from_secs_f64(admitted as f64 / self.per_sec as f64);
+        due.saturating_sub(self.started.elapsed())
+    }
+
+    /// Charge `entries` and sleep off any excess, waking early once `stop` returns true.
+    pub(crate) fn throttle(&self, entries: u64, stop: impl Fn() -> bool) {
+        let mut remaining = self.admit(entries);
+        while !remaining.is_zero() && !stop() {
+            let nap = remaining.min(MAX_THROTTLE_SLEEP);
+            std::thread::sleep(nap);
+            remaining -= nap;
+        }
+    }
+}
+
+/// Move the calling thread to the idle I/O scheduling class, so its disk reads
+/// only get bandwidth nobody else wants. Returns whether the kernel accepted it.
+#[cfg(target_os = "linux")]
+pub fn set_idle_io_priority() -> bool {
+    const IOPRIO_WHO_PROCESS: libc::c_int = 1;
+    const IOPRIO_CLASS_IDLE: libc::c_int = 3;
+    const IOPRIO_CLASS_SHIFT: libc::c_int = 13;
+
+    // `who == 0` with IOPRIO_WHO_PROCESS means the calling thread.
+    let result = unsafe {
+        libc::syscall(
+            libc::SYS_ioprio_set,
+            IOPRIO_WHO_PROCESS,
+            0,
+            IOPRIO_CLASS_IDLE << IOPRIO_CLASS_SHIFT,
+        )
+    };
+    result == 0
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn set_idle_io_priority() -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_limiter_budget() {
+        let limiter = RateLimiter::new(1_000);
+        assert!(limiter.admit(1).as_millis() <= 1);
+        // 2001 entries at 1000/s are due two seconds in.
+        let ahead = limiter.admit(2_000);
+        assert!(ahead > Duration::from_millis(1_900), "{:?}", ahead);
+
+        let started = Instant::now();
+        limiter.throttle(0, || true);
+        assert!(started.elapsed() < Duration::from_millis(50));
+    }
+
+    #[test]
+    fn test_profiles() {
+        assert!(ScanProfile::Fast.throttle().is_unthrottled());
+        let gentle = ScanProfile::Gentle.throttle();
+        assert_eq!(gentle.threads, Some(2));
+        assert!(gentle.idle_io);
+        assert_eq!(gentle.summary(), "2 threads, 5000 entries/s, idle I/O");
+    }
+}
@@ -1,23 +1,221 @@
+use crate::crawler::FileNode;
+use crate::age::{self, AgeHistogram};
+use crate::entry_meta::{DirStamp, EntryMeta, FileKind, Owner};
+use crate::file_types::TypeBreakdown;
+use crate::owners::OwnerUsage;
 use indextree::{Arena, NodeId};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
+/// Which size measure drives cumulative sizes (and therefore the treemap).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SizeMode {
+    /// Logical file length, as reported by `ls -l`
+    #[default]
+    Apparent,
+    /// Bytes allocated on disk, as reported by `du` / `df`
+    Allocated,
+}
+
+impl SizeMode {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SizeMode::Apparent => "Apparent size",
+            SizeMode::Allocated => "Disk usage",
+        }
+    }
+
+    pub fn toggled(&self) -> Self {
+        match self {
+            SizeMode::Apparent => SizeMode::Allocated,
+            SizeMode::Allocated => SizeMode::Apparent,
+        }
+    }
+}
+
+/// Why a directory's contents were not scanned.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Placeholder {
+    /// Another filesystem is mounted here; holds a label such as `nfs4 server:/export`.
+    MountPoint(String),
+    /// Left out by an exclude rule; holds the rule as the user wrote it.
+    Excluded(String),
+    /// Symlink to one of its own ancestors, not followed.
+    SymlinkLoop,
+    /// A virtual filesystem such as proc or sysfs; holds its mount label.
+    VirtualFs(String),
+    /// Didn't answer within `ScanOptions::read_timeout`, as a hung network mount does.
+    Unresponsive,
+}
+
+impl Placeholder {
+    pub fn label(&self) -> String {
+        match self {
+            Placeholder::MountPoint(fs) => format!("mount: {}", fs),
+            Placeholder::Excluded(rule) => format!("excluded: {}", rule),
+            Placeholder::SymlinkLoop => "symlink loop".to_string(),
+            Placeholder::VirtualFs(fs) => format!("virtual fs: {}", fs),
+            Placeholder::Unresponsive => "unresponsive".to_string(),
+        }
+    }
+}
+
 /// Represents a node in the directory tree
 #[derive(Debug, Clone)]
 pub struct TreeNode {
     pub path: PathBuf,
     pub name: String,
+    /// Apparent size (logical length). Zero on directories, except in a live
+    /// scan snapshot, where it holds entries too deep for the snapshot to list.
     pub size: u64,
+    /// Bytes allocated on disk
+    pub allocated_size: u64,
     pub is_dir: bool,
-    /// Cumulative size including all children
+    /// Cumulative size including all children, measured in the tree's `SizeMode`.
+    /// A shared hardlink keeps its own size here but is not added to its ancestors.
     pub cumulative_size: u64,
+    /// Extra hardlink to an inode that is charged to another path
+    pub shared_link: bool,
+    /// Bytes of shared hardlinks at or below this node (excluded from `cumulative_size`)
+    pub shared_size: u64,
+    /// Set on directories whose contents were deliberately left unscanned
+    pub placeholder: Option<Placeholder>,
+    /// Matched by a `.gitignore`/`.ignore` rule
+    pub ignored: bool,
+    /// Charged bytes of ignored files at or below this node (part of `cumulative_size`)
+    pub ignored_size: u64,
+    /// This directory could not be listed completely
+    pub read_error: bool,
+    /// A read error at or below this node; its sizes are lower bounds
+    pub partial: bool,
+    /// Where this node points, if it is a symbolic link
+    pub symlink_target: Option<PathBuf>,
+    /// Times, owner and mode, when the scan recorded them
+    pub meta: Option<Box<EntryMeta>>,
+    /// Socket, FIFO or device node; these hold no bytes
+    pub special: Option<FileKind>,
+    pub owner: Owner,
+    /// Last modification time in Unix seconds
+    pub modified: i64,
+    /// Directory times as of the scan, which tell a later scan whether it can reuse this listing
+    pub dir_stamp: Option<DirStamp>,
+    /// Charged bytes at or below this node by time since last modification,
+    /// in the tree's `SizeMode` and as of the last size calculation
+    pub age: AgeHistogram,
+    /// Charged files at or below this directory per extension and category, as
+    /// of the last size calculation; `None` on files and on folders without any
+    pub file_types: Option<Box<TypeBreakdown>>,
+}
+
+impl TreeNode {
+    fn directory(path: PathBuf, name: String) -> Self {
+        TreeNode {
+            path,
+            name,
+            size: 0,
+            allocated_size: 0,
+            is_dir: true,
+            cumulative_size: 0,
+            shared_link: false,
+            shared_size: 0,
+            placeholder: None,
+            ignored: false,
+            ignored_size: 0,
+            read_error: false,
+            partial: false,
+            symlink_target: None,
+            meta: None,
+            special: None,
+            owner: Owner::default(),
+            modified: 0,
+            dir_stamp: None,
+            age: AgeHistogram::default(),
+            file_types: None,
+        }
+    }
+
+    fn from_file_node(node: FileNode, mode: SizeMode) -> Self {
+        let name = node
+            .path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("")
+            .to_string();
+        let mut data = TreeNode {
+            path: node.path,
+            name,
+            size: node.size,
+            allocated_size: node.allocated_size,
+            is_dir: node.is_dir,
+            cumulative_size: 0,
+            shared_link: node.shared_link,
+            shared_size: 0,
+            placeholder: node.placeholder,
+            ignored: node.ignored,
+            ignored_size: 0,
+            read_error: false,
+            partial: false,
+            symlink_target: node.symlink_target,
+            meta: node.meta,
+            special: node.special,
+            owner: node.owner,
+            modified: node.modified,
+            dir_stamp: node.dir_stamp,
+            age: AgeHistogram::default(),
+            file_types: None,
+        };
+        if !data.is_dir {
+            data.cumulative_size = data.size_in(mode);
+        }
+        data
+    }
+
+    /// The crawled entry this node was built from, as `from_file_node` would take it.
+    pub(crate) fn to_file_node(&self) -> FileNode {
+        FileNode {
+            path: self.path.clone(),
+            size: self.size,
+            allocated_size: self.allocated_size,
+            is_dir: self.is_dir,
+            shared_link: self.shared_link,
+            placeholder: self.placeholder.clone(),
+            ignored: self.ignored,
+            symlink_target: self.symlink_target.clone(),
+            meta: self.meta.clone(),
+            special: self.special,
+            owner: self.owner,
+            modified: self.modified,
+            dir_stamp: self.dir_stamp,
+        }
+    }
+
+    /// Own size of this node in the given mode.
+    pub fn size_in(&self, mode: SizeMode) -> u64 {
+        match mode {
+            SizeMode::Apparent => self.size,
+            SizeMode::Allocated => self.allocated_size,
+        }
+    }
+
+    /// A file whose bytes count towards its folders: not a shared hardlink or a special file.
+    pub fn is_charged_file(&self) -> bool {
+        !self.is_dir && !self.shared_link && self.special.is_none()
+    }
 }
 
 /// High-performance hierarchical tree structure using an arena allocator
+#[derive(Debug, Clone)]
 pub struct FileTree {
     arena: Arena<TreeNode>,
     root: NodeId,
     path_to_node: HashMap<PathBuf, NodeId>,
+    size_mode: SizeMode,
+    /// The scan that produced this tree was stopped early
+    incomplete: bool,
+    /// Nodes of the scanned paths: just `root`, or its children when several paths were scanned
+    scan_roots: Vec<NodeId>,
 }
 
 impl FileTree {
@@ -31,15 +229,7 @@ impl FileTree {
             .unwrap_or("/")
             .to_string();
 
-        let root_node = TreeNode {
-            path: root_path_buf.clone(),
-            name: root_name,
-            size: 0,
-            is_dir: true,
-            cumulative_size: 0,
-        };
-
-        let root = arena.new_node(root_node);
+        let root = arena.new_node(TreeNode::directory(root_path_buf.clone(), root_name));
         let mut path_to_node = HashMap::new();
         path_to_node.insert(root_path_buf, root);
 
@@ -47,16 +237,120 @@ impl FileTree {
             arena,
             root,
             path_to_node,
+            size_mode: SizeMode::default(),
+            incomplete: false,
+            scan_roots: vec![root],
+        }
+    }
+
+    /// Create a tree for several scanned paths.
+    ///
+    /// A single path gives the same tree as `new`. Otherwise the root is a
+    /// synthetic node with an empty path, and each scanned path becomes one of
+    /// its children, named by its full path. Repeated paths and paths nested
+    /// inside another scanned path are dropped.
+    pub fn with_roots<P: AsRef<Path>>(root_paths: &[P]) -> Self {
+        let mut paths: Vec<PathBuf> = Vec::new();
+        for path in root_paths.iter().map(|p| p.as_ref()) {
+            if paths.iter().any(|kept| path.starts_with(kept)) {
+                continue;
+            }
+            paths.retain(|kept| !kept.starts_with(path));
+            paths.push(path.to_path_buf());
+        }
+        if paths.len() <= 1 {
+            return Self::new(paths.pop().unwrap_or_else(|| PathBuf::from(".")));
+        }
+
+        let mut arena = Arena::new();
+        let root = arena.new_node(TreeNode::directory(
+            PathBuf::new(),
+            format!("{} roots", paths.len()),
+        ));
+        let mut path_to_node = HashMap::new();
+        path_to_node.insert(PathBuf::new(), root);
+
+        let mut scan_roots = Vec::with_capacity(paths.len());
+        for path in paths {
+            let name = path.display().to_string();
+            let node_id = arena.new_node(TreeNode::directory(path.clone(), name));
+            root.append(node_id, &mut arena);
+            path_to_node.insert(path, node_id);
+            scan_roots.push(node_id);
+        }
+
+        Self {
+            arena,
+            root,
+            path_to_node,
+            size_mode: SizeMode::default(),
+            incomplete: false,
+            scan_roots,
         }
     }
 
+    /// Whether the root is synthetic, holding several scanned paths
+    pub fn is_multi_root(&self) -> bool {
+        self.scan_roots.first() != Some(&self.root)
+    }
+
+    /// Nodes of the scanned paths, in the order they were given
+    pub fn scan_root_ids(&self) -> &[NodeId] {
+        &self.scan_roots
+    }
+
+    pub fn scan_root_paths(&self) -> Vec<&Path> {
+        self.scan_root_path_iter().collect()
+    }
+
+    fn scan_root_path_iter(&self) -> impl Iterator<Item = &Path> + '_ {
+        self.scan_roots
+            .iter()
+            .filter_map(|id| self.arena.get(*id).map(|n| n.get().path.as_path()))
+    }
+
+    /// Whether `path` lies inside one of the scanned paths
+    pub fn contains_path(&self, path: &Path) -> bool {
+        self.scan_root_path_iter().any(|root| path.starts_with(root))
+    }
+
+    fn is_scan_root(&self, path: &Path) -> bool {
+        self.scan_root_path_iter().any(|root| path == root)
+    }
+
+    /// Path of the node above `path` in the tree (the synthetic root's is empty)
+    pub fn parent_path(&self, path: &Path) -> Option<&Path> {
+        let node_id = self.get_node(path)?;
+        let parent_id = self.arena.get(node_id)?.parent()?;
+        self.arena.get(parent_id).map(|n| n.get().path.as_path())
+    }
+
+    pub fn size_mode(&self) -> SizeMode {
+        self.size_mode
+    }
+
+    /// Switch the size measure and recompute cumulative sizes if it changed.
+    pub fn set_size_mode(&mut self, mode: SizeMode) {
+        if self.size_mode == mode {
+            return;
+        }
+        self.size_mode = mode;
+        self.calculate_sizes();
+    }
+
     /// Calculate cumulative sizes for all directories (bottom-up)
     pub fn calculate_sizes(&mut self) {
-        self.calculate_sizes_recursive(self.root);
+        self.calculate_sizes_recursive(self.root, age::now_secs());
     }
 
-    fn calculate_sizes_recursive(&mut self, node_id: NodeId) -> u64 {
+    /// Returns (charged bytes, shared hardlink bytes, ignored bytes, partial, ages) for the subtree.
+    fn calculate_sizes_recursive(&mut self, node_id: NodeId, now: i64) -> (u64, u64, u64, bool, AgeHistogram) {
         let mut total = 0u64;
+        let mut shared = 0u64;
+        let mut ignored = 0u64;
+        let mut partial = false;
+        let mut ages = AgeHistogram::default();
+        let mut file_types = TypeBreakdown::default();
 
         // Collect children first (to avoid borrow issues)
         let children: Vec<NodeId> = node_id
@@ -65,21 +359,60 @@ impl FileTree {
 
         // Recursively calculate sizes for children
         for child in children {
-            total += self.calculate_sizes_recursive(child);
+            let (child_total, child_shared, child_ignored, child_partial, child_ages) =
+                self.calculate_sizes_recursive(child, now);
+            total += child_total;
+            shared += child_shared;
+            ignored += child_ignored;
+            partial |= child_partial;
+            ages.add(&child_ages);
+            let child_data = self.arena[child].get();
+            match &child_data.file_types {
+                Some(child_types) => file_types.add_breakdown(child_types),
+                None if child_data.is_charged_file() => {
+                    file_types.add(&child_data.path, child_data.size, child_data.allocated_size)
+                }
+                None => {}
+            }
         }
 
         // Add own size
+        let mode = self.size_mode;
         if let Some(node) = self.arena.get_mut(node_id) {
             let node_data = node.get_mut();
             if node_data.is_dir {
+                total += node_data.size_in(mode);
                 node_data.cumulative_size = total;
+                node_data.file_types = (!file_types.is_empty()).then(|| Box::new(file_types));
             } else {
-                total += node_data.size;
-                node_data.cumulative_size = node_data.size;
+                let own = node_data.size_in(mode);
+                node_data.cumulative_size = own;
+                if node_data.shared_link {
+                    shared += own;
+                } else {
+                    total += own;
+                    if node_data.ignored {
+                        ignored += own;
+                    }
+                    ages = AgeHistogram::of_file(node_data.modified, now, own);
+                }
             }
+            node_data.age = ages;
+            node_data.shared_size = shared;
+            node_data.ignored_size = ignored;
+            partial |= node_data.read_error;
+            node_data.partial = partial;
         }
 
-        total
+        (total, shared, ignored, partial, ages)
+    }
+
+    /// Shared hardlink bytes in the whole tree
+    pub fn shared_size(&self) -> u64 {
+        self.arena
+            .get(self.root)
+            .map(|n| n.get().shared_size)
+            .unwrap_or(0)
     }
 
     pub fn get_root(&self) -> NodeId {
@@ -103,15 +436,11 @@ impl FileTree {
             return Some(node_id);
         }
 
-        let root_path = self.root_path()?.to_path_buf();
-        if !path.starts_with(&root_path) {
+        // Scanned paths are always in `path_to_node`, so the recursion stops at them.
+        if !self.contains_path(path) {
             return None;
         }
 
-        if path == root_path {
-            return Some(self.root);
-        }
-
         let parent_path = path.parent()?;
         let parent_id = self.ensure_directory_node(parent_path)?;
 
@@ -121,32 +450,104 @@ impl FileTree {
             .unwrap_or("")
             .to_string();
 
-        let node_id = self.arena.new_node(TreeNode {
-            path: path.to_path_buf(),
-            name,
-            size: 0,
-            is_dir: true,
-            cumulative_size: 0,
-        });
+        let node_id = self
+            .arena
+            .new_node(TreeNode::directory(path.to_path_buf(), name));
 
         parent_id.append(node_id, &mut self.arena);
         self.path_to_node.insert(path.to_path_buf(), node_id);
         Some(node_id)
     }
 
-    pub fn upsert_node(&mut self, path: PathBuf, size: u64, is_dir: bool) {
-        if self
-            .root_path()
-            .map(|root| path == root)
-            .unwrap_or(false)
-        {
-            return;
+    /// Copy what the crawler found out about a scanned path onto its existing node.
+    pub(crate) fn apply_root_entry(&mut self, node_id: NodeId, entry: FileNode) {
+        if let Some(node) = self.arena.get_mut(node_id) {
+            let data = node.get_mut();
+            data.placeholder = entry.placeholder;
+            data.meta = entry.meta;
+            data.owner = entry.owner;
+            data.modified = entry.modified;
+            data.dir_stamp = entry.dir_stamp;
         }
+    }
 
-        let Some(root_path) = self.root_path().map(|p| p.to_path_buf()) else {
-            return;
-        };
-        if !path.starts_with(&root_path) {
+    /// Count bytes of entries below `dir` that the tree doesn't hold towards `dir` itself.
+    pub(crate) fn add_unlisted_bytes(&mut self, dir: NodeId, size: u64, allocated_size: u64) {
+        if let Some(node) = self.arena.get_mut(dir) {
+            let data = node.get_mut();
+            data.size += size;
+            data.allocated_size += allocated_size;
+        }
+    }
+
+    /// Flag a directory whose listing was incomplete; `calculate_sizes` propagates it upwards.
+    pub fn mark_read_error(&mut self, dir: &Path) {
+        if let Some(node_id) = self.ensure_directory_node(dir) {
+            if let Some(node) = self.arena.get_mut(node_id) {
+                node.get_mut().read_error = true;
+            }
+        }
+    }
+
+    pub fn set_incomplete(&mut self, incomplete: bool) {
+        self.incomplete = incomplete;
+    }
+
+    /// Whether the scan was cancelled before the whole tree was walked
+    pub fn is_incomplete(&self) -> bool {
+        self.incomplete
+    }
+
+    /// Whether any part of the tree could not be read
+    pub fn is_partial(&self) -> bool {
+        self.arena
+            .get(self.root)
+            .map(|n| n.get().partial)
+            .unwrap_or(false)
+    }
+
+    pub fn upsert_node(&mut self, path: PathBuf, size: u64, is_dir: bool) {
+        self.upsert_file_node(FileNode {
+            path,
+            size,
+            allocated_size: size,
+            is_dir,
+            ..FileNode::default()
+        });
+    }
+
+    /// Whether a folder above `path` is a placeholder, standing in for contents
+    /// the scan left unread on purpose.
+    fn below_placeholder(&self, path: &Path) -> bool {
+        path.ancestors()
+            .skip(1)
+            .take_while(|dir| self.contains_path(dir))
+            .filter_map(|dir| self.path_to_node.get(dir))
+            .filter_map(|id| self.arena.get(*id))
+            .any(|node| node.get().placeholder.is_some())
+    }
+
+    /// Insert or update a node from a crawled entry, creating missing parent directories.
+    /// Entries below a placeholder are left out, and a placeholder keeps standing in
+    /// for its folder when that is updated.
+    pub fn upsert_file_node(&mut self, node: FileNode) {
+        let FileNode {
+            path,
+            size,
+            allocated_size,
+            is_dir,
+            shared_link,
+            placeholder,
+            ignored,
+            symlink_target,
+            meta,
+            special,
+            owner,
+            modified,
+            dir_stamp,
+        } = node;
+
+        if self.is_scan_root(&path) || !self.contains_path(&path) || self.below_placeholder(&path) {
             return;
         }
 
@@ -156,6 +557,14 @@ impl FileTree {
                     let data = node.get_mut();
                     data.is_dir = true;
                     data.size = 0;
+                    data.allocated_size = 0;
+                    data.placeholder = data.placeholder.take().or(placeholder);
+                    data.ignored = ignored;
+                    data.symlink_target = symlink_target;
+                    data.meta = meta;
+                    data.owner = owner;
+                    data.modified = modified;
+                    data.dir_stamp = dir_stamp;
                 }
             }
             return;
@@ -173,40 +582,159 @@ impl FileTree {
                 let data = node.get_mut();
                 data.is_dir = false;
                 data.size = size;
-                data.cumulative_size = size;
+                data.allocated_size = allocated_size;
+                data.shared_link = shared_link;
+                data.ignored = ignored;
+                data.symlink_target = symlink_target;
+                data.meta = meta;
+                data.special = special;
+                data.owner = owner;
+                data.modified = modified;
+                data.cumulative_size = data.size_in(self.size_mode);
             }
             return;
         }
 
-        let name = path
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("")
-            .to_string();
-
-        let node_id = self.arena.new_node(TreeNode {
-            path: path.clone(),
-            name,
-            size,
-            is_dir: false,
-            cumulative_size: size,
-        });
-
+        let data = TreeNode::from_file_node(
+            FileNode {
+                path: path.clone(),
+                size,
+                allocated_size,
+                is_dir: false,
+                shared_link,
+                placeholder: None,
+                ignored,
+                symlink_target,
+                meta,
+                special,
+                owner,
+                modified,
+                dir_stamp: None,
+            },
+            self.size_mode,
+        );
+        let node_id = self.arena.new_node(data);
         parent_id.append(node_id, &mut self.arena);
         self.path_to_node.insert(path, node_id);
     }
 
-    pub fn remove_path_recursive(&mut self, path: &Path) -> bool {
-        let Some(root_path) = self.root_path().map(|p| p.to_path_buf()) else {
+    /// Attach a crawled entry directly under `parent`.
+    ///
+    /// The crawler adds entries in walk order, so the parent always exists and
+    /// no path lookups or intermediate directories are needed.
+    pub(crate) fn append_file_node(&mut self, parent: NodeId, node: FileNode) -> NodeId {
+        let path = node.path.clone();
+        let node_id = self.arena.new_node(TreeNode::from_file_node(node, self.size_mode));
+        parent.append(node_id, &mut self.arena);
+        self.path_to_node.insert(path, node_id);
+        node_id
+    }
+
+    /// Replace everything below `path` with `subtree`, a fresh scan of that same directory.
+    ///
+    /// Only the ancestors of `path` are touched afterwards: their totals move by
+    /// the difference between the old and new subtree, so the rest of the tree
+    /// is not recomputed. Hardlinks are only deduplicated within the rescan.
+    /// Returns false if `path` is not a directory here or `subtree` has another root.
+    pub fn graft_subtree(&mut self, path: &Path, mut subtree: FileTree) -> bool {
+        let Some(target) = self.get_node(path) else {
             return false;
         };
-        if path == root_path {
+        if !self.arena[target].get().is_dir
+            || subtree.is_multi_root()
+            || subtree.root_path() != Some(path)
+        {
             return false;
         }
+        subtree.set_size_mode(self.size_mode);
+
+        let old_children: Vec<NodeId> = target.children(&self.arena).collect();
+        for child in old_children {
+            for id in child.descendants(&self.arena) {
+                self.path_to_node.remove(&self.arena[id].get().path);
+            }
+            child.remove_subtree(&mut self.arena);
+        }
+
+        let old = self.arena[target].get();
+        let (old_total, old_shared, old_ignored, old_ages) =
+            (old.cumulative_size, old.shared_size, old.ignored_size, old.age);
+        let old_types = old.file_types.clone();
+        let fresh = subtree.arena[subtree.root].get();
+        let (new_total, new_shared, new_ignored, new_ages) =
+            (fresh.cumulative_size, fresh.shared_size, fresh.ignored_size, fresh.age);
+        let new_types = fresh.file_types.clone();
+        {
+            let data = self.arena[target].get_mut();
+            data.cumulative_size = new_total;
+            data.age = new_ages;
+            data.file_types = new_types.clone();
+            data.shared_size = new_shared;
+            data.ignored_size = new_ignored;
+            data.read_error = fresh.read_error;
+            data.partial = fresh.partial;
+            data.placeholder = fresh.placeholder.clone();
+            data.meta = fresh.meta.clone();
+            data.owner = fresh.owner;
+            data.modified = fresh.modified;
+            data.dir_stamp = fresh.dir_stamp;
+        }
 
+        // Move the fresh nodes over in pre-order, so parents always exist first.
+        let mut pending: Vec<(NodeId, NodeId)> = subtree
+            .root
+            .children(&subtree.arena)
+            .map(|child| (child, target))
+            .collect();
+        pending.reverse();
+        while let Some((source, parent)) = pending.pop() {
+            // `subtree` is dropped afterwards, so an empty node is left behind in its place.
+            let detached = TreeNode::directory(PathBuf::new(), String::new());
+            let data = std::mem::replace(subtree.arena[source].get_mut(), detached);
+            let path = data.path.clone();
+            let node_id = self.arena.new_node(data);
+            parent.append(node_id, &mut self.arena);
+            self.path_to_node.insert(path, node_id);
+            let children: Vec<NodeId> = source.children(&subtree.arena).collect();
+            pending.extend(children.into_iter().rev().map(|child| (child, node_id)));
+        }
+
+        let mut ancestor = self.arena[target].parent();
+        while let Some(node_id) = ancestor {
+            let partial_child = node_id
+                .children(&self.arena)
+                .any(|child| self.arena[child].get().partial);
+            let data = self.arena[node_id].get_mut();
+            data.cumulative_size = data.cumulative_size.saturating_sub(old_total) + new_total;
+            data.shared_size = data.shared_size.saturating_sub(old_shared) + new_shared;
+            data.ignored_size = data.ignored_size.saturating_sub(old_ignored) + new_ignored;
+            data.age.subtract(&old_ages);
+            data.age.add(&new_ages);
+            let mut file_types = data.file_types.take().unwrap_or_default();
+            if let Some(old_types) = &old_types {
+                file_types.subtract_breakdown(old_types);
+            }
+            if let Some(new_types) = &new_types {
+                file_types.add_breakdown(new_types);
+            }
+            data.file_types = (!file_types.is_empty()).then_some(file_types);
+            data.partial = data.read_error || partial_child;
+            ancestor = self.arena[node_id].parent();
+        }
+
+        if subtree.incomplete {
+            self.incomplete = true;
+        }
+        true
+    }
+
+    pub fn remove_path_recursive(&mut self, path: &Path) -> bool {
         let Some(node_id) = self.path_to_node.get(path).copied() else {
             return false;
         };
+        if node_id == self.root || self.scan_roots.contains(&node_id) {
+            return false;
+        }
 
         let mut stack = vec![node_id];
         let mut ids = Vec::new();
@@ -227,6 +755,64 @@ impl FileTree {
         true
     }
 
+    /// Per-extension and per-category totals of the files at or below `node_id`,
+    /// kept on each directory by the size calculation.
+    pub fn type_breakdown(&self, node_id: NodeId) -> Cow<'_, TypeBreakdown> {
+        let node = self.arena[node_id].get();
+        if let Some(file_types) = &node.file_types {
+            return Cow::Borrowed(file_types);
+        }
+        let mut breakdown = TypeBreakdown::default();
+        if node.is_charged_file() {
+            breakdown.add(&node.path, node.size, node.allocated_size);
+        }
+        Cow::Owned(breakdown)
+    }
+
+    /// Charged bytes at or below `node_id` last modified at least `days` ago, in the
+    /// tree's `SizeMode`. Unlike `TreeNode::age` this takes any cut-off, at the cost of a walk.
+    pub fn bytes_older_than(&self, node_id: NodeId, days: u32) -> u64 {
+        let now = age::now_secs();
+        node_id
+            .descendants(&self.arena)
+            .map(|id| self.arena[id].get())
+            .filter(|node| !node.is_dir && !node.shared_link && age::is_older_than(node.modified, now, days))
+            .map(|node| node.size_in(self.size_mode))
+            .sum()
+    }
+
+    /// Per-user and per-group totals of the files at or below `node_id`.
+    pub fn owner_usage(&self, node_id: NodeId) -> OwnerUsage {
+        let mut usage = OwnerUsage::default();
+        for id in node_id.descendants(&self.arena) {
+            let node = self.arena[id].get();
+            if !node.is_dir && !node.shared_link && node.special.is_none() {
+                usage.add(node.owner, node.size, node.allocated_size);
+            }
+        }
+        usage
+    }
+
+    /// Bytes owned by `uid` at or below every node that holds any, in the tree's `SizeMode`.
+    /// One pass over the tree, so frontends can shade a whole treemap by owner.
+    pub fn user_sizes(&self, uid: u32) -> HashMap<NodeId, u64> {
+        let mut sizes = HashMap::new();
+        for id in self.root.descendants(&self.arena) {
+            let node = self.arena[id].get();
+            if node.is_dir || node.shared_link || node.owner.uid != uid {
+                continue;
+            }
+            let size = node.size_in(self.size_mode);
+            if size == 0 {
+                continue;
+            }
+            for ancestor in id.ancestors(&self.arena) {
+                *sizes.entry(ancestor).or_insert(0) += size;
+            }
+        }
+        sizes
+    }
+
     /// Get total size of the tree
     pub fn total_size(&self) -> u64 {
         self.arena
@@ -251,4 +837,198 @@ mod tests {
         
         assert_eq!(tree.total_size(), 300);
     }
+
+    #[test]
+    fn test_size_mode_switch() {
+        let mut tree = FileTree::new("/test");
+        tree.upsert_file_node(FileNode {
+            path: PathBuf::from("/test/sparse.img"),
+            size: 10_000,
+            allocated_size: 4096,
+            ..FileNode::default()
+        });
+        tree.upsert_file_node(FileNode {
+            path: PathBuf::from("/test/dir1/tiny.txt"),
+            size: 10,
+            allocated_size: 4096,
+            ..FileNode::default()
+        });
+
+        tree.calculate_sizes();
+        assert_eq!(tree.total_size(), 10_010);
+
+        tree.set_size_mode(SizeMode::Allocated);
+        assert_eq!(tree.total_size(), 8192);
+
+        let dir = tree.get_node(Path::new("/test/dir1")).unwrap();
+        assert_eq!(tree.get_arena()[dir].get().cumulative_size, 4096);
+    }
+
+    #[test]
+    fn test_shared_links_not_charged_to_ancestors() {
+        let mut tree = FileTree::new("/test");
+        tree.upsert_node(PathBuf::from("/test/store/blob"), 500, false);
+        tree.upsert_file_node(FileNode {
+            path: PathBuf::from("/test/snapshot/blob"),
+            size: 500,
+            allocated_size: 500,
+            shared_link: true,
+            ..FileNode::default()
+        });
+
+        tree.calculate_sizes();
+
+        assert_eq!(tree.total_size(), 500);
+        assert_eq!(tree.shared_size(), 500);
+        let snapshot = tree.get_node(Path::new("/test/snapshot")).unwrap();
+        let data = tree.get_arena()[snapshot].get();
+        assert_eq!(data.cumulative_size, 0);
+        assert_eq!(data.shared_size, 500);
+    }
+
+    #[test]
+    fn test_upserts_leave_placeholders_alone() {
+        let mut tree = FileTree::new("/test");
+        tree.upsert_file_node(FileNode {
+            path: PathBuf::from("/test/node_modules"),
+            is_dir: true,
+            placeholder: Some(Placeholder::Excluded("node_modules".to_string())),
+            ..FileNode::default()
+        });
+        tree.upsert_node(PathBuf::from("/test/node_modules"), 0, true);
+        tree.upsert_node(PathBuf::from("/test/node_modules/pkg/index.js"), 100, false);
+        tree.upsert_node(PathBuf::from("/test/node_modules/pkg"), 0, true);
+        tree.calculate_sizes();
+
+        let dir = tree.get_node(Path::new("/test/node_modules")).unwrap();
+        assert!(tree.get_arena()[dir].get().placeholder.is_some());
+        assert!(tree.get_node(Path::new("/test/node_modules/pkg")).is_none());
+        assert_eq!(tree.total_size(), 0);
+    }
+
+    #[test]
+    fn test_read_errors_mark_ancestors_partial() {
+        let mut tree = FileTree::new("/test");
+        tree.upsert_node(PathBuf::from("/test/a/b/file.txt"), 10, false);
+        tree.upsert_node(PathBuf::from("/test/c/file.txt"), 10, false);
+        tree.mark_read_error(Path::new("/test/a/b"));
+        tree.calculate_sizes();
+
+        let node = |path: &str| tree.get_arena()[tree.get_node(Path::new(path)).unwrap()].get();
+        assert!(node("/test/a/b").read_error);
+        assert!(node("/test/a").partial && !node("/test/a").read_error);
+        assert!(!node("/test/c").partial);
+        assert!(tree.is_partial());
+    }
+
+    #[test]
+    fn test_multiple_roots_under_synthetic_root() {
+        let mut tree = FileTree::with_roots(&["/home", "/var", "/home/me", "/var"]);
+        assert!(tree.is_multi_root());
+        assert_eq!(tree.scan_root_paths(), vec![Path::new("/home"), Path::new("/var")]);
+
+        tree.upsert_node(PathBuf::from("/home/me/notes.txt"), 100, false);
+        tree.upsert_node(PathBuf::from("/var/log/syslog"), 50, false);
+        tree.upsert_node(PathBuf::from("/opt/app"), 1000, false);
+        tree.calculate_sizes();
+
+        assert_eq!(tree.total_size(), 150);
+        assert!(tree.get_node(Path::new("/opt/app")).is_none());
+        assert_eq!(tree.parent_path(Path::new("/var/log")), Some(Path::new("/var")));
+        assert_eq!(tree.parent_path(Path::new("/var")), Some(Path::new("")));
+        assert!(!tree.remove_path_recursive(Path::new("/var")));
+        assert!(tree.remove_path_recursive(Path::new("/var/log")));
+
+        let single = FileTree::with_roots(&["/data", "/data/sub"]);
+        assert!(!single.is_multi_root());
+        assert_eq!(single.root_path(), Some(Path::new("/data")));
+    }
+
+    #[test]
+    fn test_graft_subtree_updates_ancestors() {
+        let mut tree = FileTree::new("/test");
+        tree.upsert_node(PathBuf::from("/test/a/b/old.bin"), 1000, false);
+        tree.upsert_node(PathBuf::from("/test/a/b/gone/x.bin"), 500, false);
+        tree.upsert_node(PathBuf::from("/test/a/keep.txt"), 10, false);
+        tree.upsert_node(PathBuf::from("/test/other.txt"), 5, false);
+        tree.mark_read_error(Path::new("/test/a/b/gone"));
+        tree.calculate_sizes();
+        assert!(tree.is_partial());
+
+        let mut fresh = FileTree::new("/test/a/b");
+        fresh.upsert_node(PathBuf::from("/test/a/b/new.bin"), 200, false);
+        let owner = Owner { uid: 1000, gid: 100 };
+        fresh.apply_root_entry(
+            fresh.get_root(),
+            FileNode {
+                owner,
+                modified: 1_700_000_000,
+                ..FileNode::default()
+            },
+        );
+        fresh.calculate_sizes();
+        assert!(tree.graft_subtree(Path::new("/test/a/b"), fresh));
+
+        let size = |tree: &FileTree, path: &str| {
+            tree.get_arena()[tree.get_node(Path::new(path)).unwrap()].get().cumulative_size
+        };
+        assert_eq!(size(&tree, "/test/a/b"), 200);
+        assert_eq!(size(&tree, "/test/a"), 210);
+        assert_eq!(tree.total_size(), 215);
+        assert!(tree.get_node(Path::new("/test/a/b/gone/x.bin")).is_none());
+        assert!(tree.get_node(Path::new("/test/a/b/new.bin")).is_some());
+        assert!(!tree.is_partial());
+        let grafted = tree.get_arena()[tree.get_node(Path::new("/test/a/b")).unwrap()].get();
+        assert_eq!((grafted.owner, grafted.modified), (owner, 1_700_000_000));
+        let types = |tree: &FileTree, path: &str| {
+            let breakdown = tree.type_breakdown(tree.get_node(Path::new(path)).unwrap());
+            let mut rows: Vec<_> = breakdown.by_extension.iter().map(|(key, totals)| (key.clone(), totals.files)).collect();
+            rows.sort();
+            rows
+        };
+        assert_eq!(types(&tree, "/test/a"), [("bin".to_string(), 1), ("txt".to_string(), 1)]);
+        assert_eq!(tree.type_breakdown(tree.get_root()).total().size, 215);
+
+        // A full recomputation agrees with the incremental update.
+        let grafted = types(&tree, "/test");
+        tree.calculate_sizes();
+        assert_eq!(tree.total_size(), 215);
+        assert_eq!(types(&tree, "/test"), grafted);
+        assert!(!tree.graft_subtree(Path::new("/test/other.txt"), FileTree::new("/test/other.txt")));
+    }
+
+    #[test]
+    fn test_age_histogram_per_directory() {
+        let now = age::now_secs();
+        let file = |path: &str, size: u64, days_old: i64| FileNode {
+            path: PathBuf::from(path),
+            size,
+            allocated_size: size,
+            modified: now - days_old * 86_400,
+            ..FileNode::default()
+        };
+        let mut tree = FileTree::new("/test");
+        tree.upsert_file_node(file("/test/logs/today.log", 10, 0));
+        tree.upsert_file_node(file("/test/logs/old.log", 100, 400));
+        tree.upsert_file_node(file("/test/data/q1.csv", 1000, 120));
+        tree.upsert_file_node(file("/test/data/q2.csv", 50, 45));
+        tree.calculate_sizes();
+
+        let node = |tree: &FileTree, path: &str| tree.get_node(Path::new(path)).unwrap();
+        let ages = |tree: &FileTree, path: &str| tree.get_arena()[node(tree, path)].get().age.buckets;
+        assert_eq!(ages(&tree, "/test/logs"), [10, 0, 0, 100]);
+        assert_eq!(ages(&tree, "/test/data"), [0, 50, 1000, 0]);
+        assert_eq!(ages(&tree, "/test"), [10, 50, 1000, 100]);
+        assert_eq!(tree.bytes_older_than(tree.get_root(), 90), 1100);
+        assert_eq!(tree.bytes_older_than(node(&tree, "/test/data"), 30), 1050);
+
+        // A rescan that finds the old log gone moves only that folder's share.
+        let mut fresh = FileTree::new("/test/logs");
+        fresh.upsert_file_node(file("/test/logs/today.log", 20, 0));
+        fresh.calculate_sizes();
+        assert!(tree.graft_subtree(Path::new("/test/logs"), fresh));
+        assert_eq!(ages(&tree, "/test"), [20, 50, 1000, 0]);
+        tree.calculate_sizes();
+        assert_eq!(ages(&tree, "/test"), [20, 50, 1000, 0]);
+    }
 }